@@ -0,0 +1,91 @@
+//! Procedural reference grid on the ground plane
+
+struct CameraUniform {
+    view_proj: mat4x4<f32>,
+    view: mat4x4<f32>,
+    proj: mat4x4<f32>,
+    position: vec4<f32>,
+}
+
+struct GridUniform {
+    color: vec3<f32>,
+    spacing: f32,
+    height: f32,
+    fade_distance: f32,
+    show_axes: u32,
+    _padding: f32,
+}
+
+// World axes through the origin, colored like the usual gizmo convention
+const X_AXIS_COLOR: vec3<f32> = vec3<f32>(0.9, 0.25, 0.25);
+const Z_AXIS_COLOR: vec3<f32> = vec3<f32>(0.25, 0.45, 0.95);
+
+@group(0) @binding(0)
+var<uniform> camera: CameraUniform;
+
+@group(1) @binding(0)
+var<uniform> grid: GridUniform;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) world_position: vec3<f32>,
+}
+
+// Square around the camera that follows it, so the grid appears infinite
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(-1.0, -1.0),
+        vec2<f32>(1.0, -1.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(-1.0, -1.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(-1.0, 1.0),
+    );
+    let xz = corners[vertex_index] * grid.fade_distance + camera.position.xz;
+    let world_position = vec3<f32>(xz.x, grid.height, xz.y);
+
+    var out: VertexOutput;
+    out.clip_position = camera.view_proj * vec4<f32>(world_position, 1.0);
+    out.world_position = world_position;
+    return out;
+}
+
+// Anti-aliased line coverage for a grid with cells of size 1 in `coord`
+fn grid_line(coord: vec2<f32>) -> f32 {
+    let distance_in_pixels = abs(fract(coord - 0.5) - 0.5) / fwidth(coord);
+    return 1.0 - min(min(distance_in_pixels.x, distance_in_pixels.y), 1.0);
+}
+
+// Anti-aliased coverage of a line at 0 in `coord`, slightly wider than grid lines
+fn axis_line(coord: f32) -> f32 {
+    let distance_in_pixels = abs(coord) / fwidth(coord);
+    return 1.0 - min(distance_in_pixels / 1.5, 1.0);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let coord = in.world_position.xz / grid.spacing;
+    let minor = grid_line(coord);
+    // Every 10th line is drawn at full strength
+    let major = grid_line(coord / 10.0);
+
+    let distance = length(in.world_position.xz - camera.position.xz);
+    let fade = 1.0 - smoothstep(grid.fade_distance * 0.4, grid.fade_distance, distance);
+
+    var color = grid.color;
+    var alpha = max(minor * 0.4, major);
+    if grid.show_axes != 0u {
+        // The X axis runs along z = 0, the Z axis along x = 0
+        let x_axis = axis_line(in.world_position.z);
+        let z_axis = axis_line(in.world_position.x);
+        color = mix(color, X_AXIS_COLOR, x_axis);
+        color = mix(color, Z_AXIS_COLOR, z_axis);
+        alpha = max(alpha, max(x_axis, z_axis));
+    }
+    alpha *= fade;
+    if alpha <= 0.001 {
+        discard;
+    }
+    return vec4<f32>(color, alpha);
+}
@@ -6,21 +6,47 @@ struct VertexInput {
     @location(1) normal: vec3<f32>,
     @location(2) uv: vec2<f32>,
     @location(3) tangent: vec4<f32>,
+    @location(4) uv1: vec2<f32>,
+}
+
+// Average length of the edges meeting at the vertex (mesh_buffer.rs), only used by vs_main
+struct EdgeLengthInput {
+    @location(8) edge_length: f32,
+}
+
+// Per-instance inputs (material matrix)
+struct InstanceInput {
+    @location(5) offset: vec3<f32>,
+    @location(6) override_mask: u32,
+    @location(7) params: vec4<f32>,  // x=metallic, y=roughness, z=tint brightness
 }
 
 // Vertex shader outputs
 struct VertexOutput {
-    @builtin(position) clip_position: vec4<f32>,
+    @builtin(position) @invariant clip_position: vec4<f32>,  // depth pre-pass tests Equal
     @location(0) world_position: vec3<f32>,
     @location(1) world_normal: vec3<f32>,
     @location(2) uv: vec2<f32>,
     @location(3) tangent: vec3<f32>,
     @location(4) bitangent: vec3<f32>,
+    @location(5) uv1: vec2<f32>,
+    @location(6) @interpolate(flat) override_mask: u32,
+    @location(7) @interpolate(flat) instance_params: vec4<f32>,
+    @location(8) edge_length: f32,  // model units, approximates the size of this triangle
 }
 
+struct CameraUniform {
+    view_proj: mat4x4<f32>,
+    view: mat4x4<f32>,
+    proj: mat4x4<f32>,
+    position: vec4<f32>,
+}
+
+const PI: f32 = 3.14159265359;
+
 // Uniforms
 @group(0) @binding(0)
-var<uniform> view_proj: mat4x4<f32>;
+var<uniform> camera: CameraUniform;
 
 @group(0) @binding(1)
 var<uniform> model: mat4x4<f32>;
@@ -40,30 +66,163 @@ var metallic_roughness_texture: texture_2d<f32>;
 @group(1) @binding(5)
 var metallic_roughness_sampler: sampler;
 
+@group(1) @binding(6)
+var ao_texture: texture_2d<f32>;
+@group(1) @binding(7)
+var ao_sampler: sampler;
+
+@group(1) @binding(8)
+var emissive_texture: texture_2d<f32>;
+@group(1) @binding(9)
+var emissive_sampler: sampler;
+
+@group(1) @binding(10)
+var height_texture: texture_2d<f32>;
+@group(1) @binding(11)
+var height_sampler: sampler;
+
+// Image-based lighting: irradiance / PI for diffuse, GGX-prefiltered radiance
+// (roughness mapped linearly onto the mips) for specular
+@group(1) @binding(12)
+var env_irradiance: texture_cube<f32>;
+@group(1) @binding(13)
+var env_specular: texture_cube<f32>;
+@group(1) @binding(14)
+var env_sampler: sampler;
+
+// Last mip of env_specular (environment::SPECULAR_MIP_LEVELS - 1)
+const ENV_SPECULAR_MAX_MIP: f32 = 5.0;
+
 @group(2) @binding(0)
 var<uniform> material_params: MaterialParams;
 
 struct MaterialParams {
     base_color_tint: vec3<f32>,
-    _padding0: f32,
+    emissive_strength: f32,
     metallic: f32,
     roughness: f32,
     normal_strength: f32,
     uv_scale: f32,
     view_mode: u32,
     texture_flags: u32,
+    material_flags: u32,
+    parallax_steps: u32,
     light_direction: vec3<f32>,
-    _padding1: f32,
+    parallax_depth: f32,  // UV units, 0 disables parallax
+    env_rotation: vec2<f32>,  // sin/cos of the environment yaw
+    normal_debug_space: u32,  // 0=tangent, 1=world
+    specular_aa_strength: f32,  // 0 disables geometric specular AA
+    light_color: vec3<f32>,  // Linear RGB
+    exposure: f32,  // Linear multiplier applied before tone mapping
+    ao_strength: f32,
+    shading_flags: u32,  // bit 0: AO also darkens direct light (debug comparison), bit 1: multi-scatter specular
+    overlay_flags: u32,  // bit 0: UV tile boundary lines
+    environment_flags: u32,  // bit 0: environment map loaded
+    uv_tile_line_color: vec3<f32>,
+    tone_mapping: u32,  // 0=none (clip), 1=Reinhard, 2=ACES filmic
+    wireframe_color: vec3<f32>,
+    uv_scale_v: f32,  // uv_scale is the U tile size
+    uv_offset: vec2<f32>,
+    uv_rotation: vec2<f32>,  // sin/cos of the UV rotation
+    displacement_scale: f32,
+    displacement_midpoint: f32,  // height value that leaves the surface in place
+    displacement_bias: f32,
+    displacement_enabled: u32,  // 1 = move vertices along the normal by the height map
+    displacement_clamp: vec2<f32>,  // min/max displacement
+    light_intensity: f32,  // radiance of the directional light
+    secondary_light_count: u32,  // used entries of secondary_lights
+    ambient_color: vec3<f32>,  // Linear RGB, used without an environment map
+    ambient_intensity: f32,
+    secondary_lights: array<SecondaryLight, 2>,  // fill and rim lights, unshadowed
+    tessellation_debug: u32,  // 0=off, 1=triangle density heatmap
+    tangent_frame_axis: u32,  // 0=tangent, 1=bitangent, 2=normal
+    displacement_distance_scale: f32,  // camera distance multiplier before the falloff curve
+    displacement_falloff: u32,  // 0=linear, 1=quadratic, 2=exponential
+}
+
+// Additional directional light (see MAX_SECONDARY_LIGHTS in state_wgpu.rs)
+struct SecondaryLight {
+    direction: vec3<f32>,  // towards the light, normalized
+    intensity: f32,
+    color: vec3<f32>,  // Linear RGB
+    _padding: f32,
+}
+
+// Directional light shadow map (see shadow.rs)
+struct ShadowUniform {
+    light_view_proj: mat4x4<f32>,
+    bias: f32,  // normal offset in world units
+    enabled: u32,
+    texel_size: f32,
+    _padding: f32,
+}
+
+@group(3) @binding(0)
+var<uniform> shadow: ShadowUniform;
+// Only bound in the color pass; the shadow pass renders into this texture
+@group(3) @binding(1)
+var shadow_map: texture_depth_2d;
+@group(3) @binding(2)
+var shadow_sampler: sampler_comparison;
+
+// Rotate a world-space direction into environment space (yaw around +Y).
+// Used for both IBL lookups and the skybox so they stay aligned.
+fn environment_direction(dir: vec3<f32>) -> vec3<f32> {
+    let s = material_params.env_rotation.x;
+    let c = material_params.env_rotation.y;
+    return vec3<f32>(c * dir.x + s * dir.z, dir.y, -s * dir.x + c * dir.z);
+}
+
+// UV tile size; the UI "scale" is tile size, so smaller = more repeats
+fn uv_tile_size() -> vec2<f32> {
+    let epsilon = 0.0001;
+    return max(vec2<f32>(material_params.uv_scale, material_params.uv_scale_v), vec2<f32>(epsilon));
+}
+
+// Apply UV tiling, rotation and offset to a mesh UV
+fn material_uv(uv: vec2<f32>) -> vec2<f32> {
+    // Scale around center (0.5, 0.5) to prevent drift
+    let tiling = 1.0 / uv_tile_size();
+    let pivot = vec2<f32>(0.5, 0.5);
+    let tiled = (uv - pivot) * tiling;
+    // Rotate around the same pivot, then shift
+    let uv_sin = material_params.uv_rotation.x;
+    let uv_cos = material_params.uv_rotation.y;
+    let rotated = vec2<f32>(uv_cos * tiled.x - uv_sin * tiled.y, uv_sin * tiled.x + uv_cos * tiled.y);
+    return rotated + pivot + material_params.uv_offset;
+}
+
+// Share of the displacement kept at a camera distance, following the falloff curve
+fn displacement_lod(camera_distance: f32) -> f32 {
+    let x = max(camera_distance * material_params.displacement_distance_scale, 0.0);
+    if material_params.displacement_falloff == 1u {
+        return 1.0 / (1.0 + x * x);
+    } else if material_params.displacement_falloff == 2u {
+        return exp(-x);
+    }
+    return 1.0 / (1.0 + x);
+}
+
+// Displace along the vertex normal by the height map, faded by `lod`; the detail
+// is limited by the mesh density (no tessellation stage)
+fn displaced_position(position: vec3<f32>, normal: vec3<f32>, uv: vec2<f32>, lod: f32) -> vec3<f32> {
+    let has_height = (material_params.texture_flags & (1u << 5u)) != 0u;
+    if !has_height || material_params.displacement_enabled == 0u {
+        return position;
+    }
+    let height = textureSampleLevel(height_texture, height_sampler, uv, 0.0).r;
+    let offset = (height - material_params.displacement_midpoint) * material_params.displacement_scale
+        + material_params.displacement_bias;
+    let clamped = clamp(offset, material_params.displacement_clamp.x, material_params.displacement_clamp.y);
+    return position + normalize(normal) * clamped * lod;
 }
 
 @vertex
-fn vs_main(in: VertexInput) -> VertexOutput {
+fn vs_main(in: VertexInput, instance: InstanceInput, edge: EdgeLengthInput) -> VertexOutput {
     var out: VertexOutput;
-    
-    // Transform position to world space
-    let world_pos = model * vec4<f32>(in.position, 1.0);
-    out.world_position = world_pos.xyz;
-    out.clip_position = view_proj * world_pos;
+    out.edge_length = edge.edge_length;
+    out.override_mask = instance.override_mask;
+    out.instance_params = instance.params;
     
     // Transform normal to world space
     // For rotation-only matrices, we simply multiply by the rotation matrix
@@ -78,81 +237,516 @@ fn vs_main(in: VertexInput) -> VertexOutput {
     // This makes the normal rotate WITH the model, keeping light fixed in world space
     out.world_normal = normalize(rotation * in.normal);
     
-    // Apply UV tiling with center pivot
-    // The UI "scale" is tile size: smaller scale = more repeats (finer pattern)
-    // So tiling = 1/scale. Scale around center (0.5, 0.5) to prevent drift
-    let epsilon = 0.0001;
-    let tiling = 1.0 / max(material_params.uv_scale, epsilon);
-    let pivot = vec2<f32>(0.5, 0.5);
-    out.uv = (in.uv - pivot) * tiling + pivot;
+    out.uv = material_uv(in.uv);
+    // UV1 holds baked data (AO/lightmaps) and is never tiled
+    out.uv1 = in.uv1;
+    
+    // Shading normals are left unchanged by the displacement (see displaced_normal)
+    let undisplaced = (model * vec4<f32>(in.position, 1.0)).xyz + instance.offset;
+    let lod = displacement_lod(distance(camera.position.xyz, undisplaced));
+    let position = displaced_position(in.position, in.normal, out.uv, lod);
+    
+    // Transform position to world space, then place the instance
+    let world_pos = model * vec4<f32>(position, 1.0) + vec4<f32>(instance.offset, 0.0);
+    out.world_position = world_pos.xyz;
+    out.clip_position = camera.view_proj * world_pos;
     
     // Transform tangent to world space
     let T = normalize(rotation * in.tangent.xyz);
     let N = out.world_normal;
     // Recalculate bitangent to ensure orthogonality
-    let B = cross(N, T) * in.tangent.w;
+    let B = normalize(cross(N, T) * in.tangent.w);
     
-    out.tangent = T;
-    out.bitangent = normalize(B);
+    // The tangent frame follows the scaled and rotated U and V directions so normal maps stay lit correctly
+    let tile_size = uv_tile_size();
+    let uv_sin = material_params.uv_rotation.x;
+    let uv_cos = material_params.uv_rotation.y;
+    out.tangent = normalize(uv_cos * tile_size.x * T - uv_sin * tile_size.y * B);
+    out.bitangent = normalize(uv_sin * tile_size.x * T + uv_cos * tile_size.y * B);
     
     return out;
 }
 
+// Shadow pass: the displaced mesh seen from the light
+@vertex
+fn vs_shadow(in: VertexInput, instance: InstanceInput) -> @builtin(position) vec4<f32> {
+    // Fade by the viewer's distance so the shadow matches the displaced surface
+    let undisplaced = (model * vec4<f32>(in.position, 1.0)).xyz + instance.offset;
+    let lod = displacement_lod(distance(camera.position.xyz, undisplaced));
+    let position = displaced_position(in.position, in.normal, material_uv(in.uv), lod);
+    let world_pos = model * vec4<f32>(position, 1.0) + vec4<f32>(instance.offset, 0.0);
+    return shadow.light_view_proj * world_pos;
+}
+
+// Fraction of the directional light reaching a point: 3x3 PCF over the shadow map.
+// The lookup is pushed along the geometric normal to avoid self-shadowing acne.
+fn shadow_visibility(world_position: vec3<f32>, normal: vec3<f32>) -> f32 {
+    if shadow.enabled == 0u {
+        return 1.0;
+    }
+    let light_pos = shadow.light_view_proj * vec4<f32>(world_position + normal * shadow.bias, 1.0);
+    let ndc = light_pos.xyz / light_pos.w;
+    // NDC to texture space (Y flipped)
+    let shadow_uv = vec2<f32>(ndc.x * 0.5 + 0.5, 0.5 - ndc.y * 0.5);
+    if any(shadow_uv < vec2<f32>(0.0)) || any(shadow_uv > vec2<f32>(1.0)) || ndc.z > 1.0 {
+        return 1.0;
+    }
+    var lit = 0.0;
+    for (var y = -1; y <= 1; y++) {
+        for (var x = -1; x <= 1; x++) {
+            let offset = vec2<f32>(f32(x), f32(y)) * shadow.texel_size;
+            lit += textureSampleCompareLevel(shadow_map, shadow_sampler, shadow_uv + offset, ndc.z);
+        }
+    }
+    return lit / 9.0;
+}
+
+// GGX / Trowbridge-Reitz normal distribution
+fn distribution_ggx(n_dot_h: f32, roughness: f32) -> f32 {
+    let a = roughness * roughness;
+    let a2 = a * a;
+    let d = n_dot_h * n_dot_h * (a2 - 1.0) + 1.0;
+    return a2 / (PI * d * d);
+}
+
+// Smith geometry term with Schlick-GGX for direct lighting
+fn geometry_smith(n_dot_v: f32, n_dot_l: f32, roughness: f32) -> f32 {
+    let r = roughness + 1.0;
+    let k = (r * r) / 8.0;
+    let g_v = n_dot_v / (n_dot_v * (1.0 - k) + k);
+    let g_l = n_dot_l / (n_dot_l * (1.0 - k) + k);
+    return g_v * g_l;
+}
+
+fn fresnel_schlick(cos_theta: f32, f0: vec3<f32>) -> vec3<f32> {
+    return f0 + (1.0 - f0) * pow(clamp(1.0 - cos_theta, 0.0, 1.0), 5.0);
+}
+
+// Transform a sampled tangent-space normal into the world-space shading normal
+fn shading_normal(in: VertexOutput, normal_sample: vec3<f32>) -> vec3<f32> {
+    var tangent_normal = normal_sample * 2.0 - 1.0;
+    // DirectX-convention normal maps store +Y pointing down the texture
+    if (material_params.material_flags & (1u << 1u)) != 0u {
+        tangent_normal.y = -tangent_normal.y;
+    }
+    tangent_normal = vec3<f32>(tangent_normal.xy * material_params.normal_strength, tangent_normal.z);
+    let T = normalize(in.tangent);
+    let B = normalize(in.bitangent);
+    let N = normalize(in.world_normal);
+    return normalize(T * tangent_normal.x + B * tangent_normal.y + N * tangent_normal.z);
+}
+
+// Normal of the displaced surface: tilt the interpolated normal by the height map
+// slope (surface gradient bump mapping), since the mesh is too coarse to shade from.
+// Called from uniform control flow because of the screen-space derivatives.
+fn displaced_normal(in: VertexOutput, uv: vec2<f32>) -> vec3<f32> {
+    let N = normalize(in.world_normal);
+    let texel = 1.0 / vec2<f32>(textureDimensions(height_texture));
+    let h_u0 = textureSampleLevel(height_texture, height_sampler, uv - vec2<f32>(texel.x, 0.0), 0.0).r;
+    let h_u1 = textureSampleLevel(height_texture, height_sampler, uv + vec2<f32>(texel.x, 0.0), 0.0).r;
+    let h_v0 = textureSampleLevel(height_texture, height_sampler, uv - vec2<f32>(0.0, texel.y), 0.0).r;
+    let h_v1 = textureSampleLevel(height_texture, height_sampler, uv + vec2<f32>(0.0, texel.y), 0.0).r;
+    let lod = displacement_lod(distance(camera.position.xyz, in.world_position));
+    let dh_duv = vec2<f32>(h_u1 - h_u0, h_v1 - h_v0) / (2.0 * texel) * material_params.displacement_scale * lod;
+    
+    // Height change per screen pixel, then projected onto the surface
+    let dh_dx = dot(dh_duv, dpdx(uv));
+    let dh_dy = dot(dh_duv, dpdy(uv));
+    let sigma_x = dpdx(in.world_position);
+    let sigma_y = dpdy(in.world_position);
+    let r1 = cross(sigma_y, N);
+    let r2 = cross(N, sigma_x);
+    let det = dot(sigma_x, r1);
+    let surface_gradient = sign(det) * (dh_dx * r1 + dh_dy * r2);
+    return normalize(abs(det) * N - surface_gradient);
+}
+
+// Parallax occlusion mapping: march the view ray through the height field in
+// tangent space and return the UV where it first passes below the surface.
+// Explicit gradients keep the data-dependent loop legal for texture sampling.
+fn parallax_uv(in: VertexOutput, uv: vec2<f32>, V: vec3<f32>) -> vec2<f32> {
+    let view_ts = vec3<f32>(
+        dot(V, normalize(in.tangent)),
+        dot(V, normalize(in.bitangent)),
+        dot(V, normalize(in.world_normal)),
+    );
+    let steps = max(material_params.parallax_steps, 1u);
+    let layer_depth = 1.0 / f32(steps);
+    // Clamp the divisor so grazing views don't shift the UV towards infinity
+    let delta_uv = view_ts.xy / max(view_ts.z, 0.1) * material_params.parallax_depth * layer_depth;
+    let ddx = dpdx(uv);
+    let ddy = dpdy(uv);
+    
+    // Height maps store white as the top of the surface
+    var current_uv = uv;
+    var current_layer = 0.0;
+    var surface_depth = 1.0 - textureSampleGrad(height_texture, height_sampler, uv, ddx, ddy).r;
+    var previous_uv = uv;
+    var previous_layer = 0.0;
+    var previous_surface_depth = surface_depth;
+    var found = current_layer >= surface_depth;
+    for (var i = 0u; i < steps; i++) {
+        if !found {
+            previous_uv = current_uv;
+            previous_layer = current_layer;
+            previous_surface_depth = surface_depth;
+            current_uv -= delta_uv;
+            current_layer += layer_depth;
+            surface_depth = 1.0 - textureSampleGrad(height_texture, height_sampler, current_uv, ddx, ddy).r;
+            found = current_layer >= surface_depth;
+        }
+    }
+    
+    // Intersect the ray with the height profile linearly between the last two layers
+    let depth_after = surface_depth - current_layer;
+    let depth_before = previous_surface_depth - previous_layer;
+    let weight = depth_after / min(depth_after - depth_before, -1e-5);
+    return mix(current_uv, previous_uv, clamp(weight, 0.0, 1.0));
+}
+
+// Analytic fit of the split-sum DFG term (Karis, "Physically Based Shading on Mobile").
+// Returns the scale and bias applied to F0 for the directional albedo of the GGX lobe.
+fn env_brdf_approx(roughness: f32, n_dot_v: f32) -> vec2<f32> {
+    let c0 = vec4<f32>(-1.0, -0.0275, -0.572, 0.022);
+    let c1 = vec4<f32>(1.0, 0.0425, 1.04, -0.04);
+    let r = roughness * c0 + c1;
+    let a004 = min(r.x * r.x, exp2(-9.28 * n_dot_v)) * r.x + r.y;
+    return vec2<f32>(-1.04, 1.04) * a004 + r.zw;
+}
+
+// Multi-scatter energy compensation (Fdez-Agüera, "A Multiple-Scattering Microfacet
+// Model for Real-Time Image-Based Lighting"). Single-scatter GGX loses the energy of
+// light bouncing between microfacets, so rough metals look too dark; scaling the
+// specular lobe by 1 + F0 * (1 / Ess - 1) restores it.
+fn multi_scatter_compensation(f0: vec3<f32>, roughness: f32, n_dot_v: f32) -> vec3<f32> {
+    let dfg = env_brdf_approx(roughness, n_dot_v);
+    let single_scatter_albedo = max(dfg.x + dfg.y, 1e-4);
+    return 1.0 + f0 * (1.0 / single_scatter_albedo - 1.0);
+}
+
+// Cook-Torrance BRDF times n·l for one directional light of unit radiance
+fn direct_lighting(
+    N: vec3<f32>,
+    V: vec3<f32>,
+    L: vec3<f32>,
+    n_dot_v: f32,
+    base_color: vec3<f32>,
+    metallic: f32,
+    f0: vec3<f32>,
+    roughness: f32,
+) -> vec3<f32> {
+    let H = normalize(V + L);
+    let n_dot_l = max(dot(N, L), 0.0);
+    let n_dot_h = max(dot(N, H), 0.0);
+    let v_dot_h = max(dot(V, H), 0.0);
+    
+    let F = fresnel_schlick(v_dot_h, f0);
+    let D = distribution_ggx(n_dot_h, roughness);
+    let G = geometry_smith(n_dot_v, n_dot_l, roughness);
+    var specular = (D * G * F) / max(4.0 * n_dot_v * n_dot_l, 0.0001);
+    if (material_params.shading_flags & (1u << 1u)) != 0u {
+        specular *= multi_scatter_compensation(f0, roughness, n_dot_v);
+    }
+    let k_d = (vec3<f32>(1.0) - F) * (1.0 - metallic);
+    let diffuse = k_d * base_color / PI;
+    return (diffuse + specular) * n_dot_l;
+}
+
+// Split-sum image-based lighting from the environment cubes
+fn environment_lighting(
+    N: vec3<f32>,
+    V: vec3<f32>,
+    n_dot_v: f32,
+    base_color: vec3<f32>,
+    metallic: f32,
+    f0: vec3<f32>,
+    roughness: f32,
+) -> vec3<f32> {
+    let R = reflect(-V, N);
+    let irradiance = textureSampleLevel(env_irradiance, env_sampler, environment_direction(N), 0.0).rgb;
+    let prefiltered = textureSampleLevel(env_specular, env_sampler, environment_direction(R), roughness * ENV_SPECULAR_MAX_MIP).rgb;
+    
+    let dfg = env_brdf_approx(roughness, n_dot_v);
+    let specular_albedo = f0 * dfg.x + dfg.y;
+    var specular = prefiltered * specular_albedo;
+    if (material_params.shading_flags & (1u << 1u)) != 0u {
+        specular *= multi_scatter_compensation(f0, roughness, n_dot_v);
+    }
+    // Energy reflected by the specular lobe is not available to the diffuse one
+    let k_d = (vec3<f32>(1.0) - specular_albedo) * (1.0 - metallic);
+    return k_d * base_color * irradiance + specular;
+}
+
+// Geometric specular anti-aliasing (Tokuyoshi & Kaplanyan): widen the GGX lobe
+// where the shading normal changes quickly across a pixel, so highlights on
+// high-frequency normal maps and curved surfaces don't shimmer.
+fn specular_aa_roughness(N: vec3<f32>, roughness: f32) -> f32 {
+    let dndu = dpdx(N);
+    let dndv = dpdy(N);
+    // Screen-space filter variance (0.25) scaled by the user strength, clamped
+    // by the kernel threshold (0.18) to avoid over-blurring
+    let variance = 0.25 * material_params.specular_aa_strength * (dot(dndu, dndu) + dot(dndv, dndv));
+    let kernel_roughness = min(2.0 * variance, 0.18);
+    let alpha = roughness * roughness;
+    let alpha2 = clamp(alpha * alpha + kernel_roughness, 0.0, 1.0);
+    return sqrt(sqrt(alpha2));
+}
+
+// Heatmap of UV stretch: compares the world-space length of one unit of U
+// against one unit of V. Equal densities are green, a 2x (or larger)
+// difference in either direction is red.
+fn uv_stretch_color(world_position: vec3<f32>, uv: vec2<f32>) -> vec3<f32> {
+    let dp_dx = dpdx(world_position);
+    let dp_dy = dpdy(world_position);
+    let duv_dx = dpdx(uv);
+    let duv_dy = dpdy(uv);
+    
+    // Invert the screen-to-UV Jacobian to get the surface tangents along U and V
+    let det = duv_dx.x * duv_dy.y - duv_dx.y * duv_dy.x;
+    if abs(det) < 1e-12 {
+        return vec3<f32>(1.0, 0.0, 1.0);  // Degenerate UVs (collapsed to a point or line)
+    }
+    let dp_du = (dp_dx * duv_dy.y - dp_dy * duv_dx.y) / det;
+    let dp_dv = (dp_dy * duv_dx.x - dp_dx * duv_dy.x) / det;
+    
+    let stretch = clamp(abs(log2(max(length(dp_du), 1e-8) / max(length(dp_dv), 1e-8))), 0.0, 1.0);
+    let green = vec3<f32>(0.1, 0.8, 0.2);
+    let yellow = vec3<f32>(0.95, 0.85, 0.1);
+    let red = vec3<f32>(0.9, 0.1, 0.1);
+    if stretch < 0.5 {
+        return mix(green, yellow, stretch * 2.0);
+    }
+    return mix(yellow, red, (stretch - 0.5) * 2.0);
+}
+
+// Checkerboard cells per UV unit in the UV checker view mode
+const UV_CHECKER_CELLS: f32 = 8.0;
+
+// Procedural UV checker: alternating cells show distortion, the red ramp
+// grows along U and the green ramp along V, so flipped or rotated islands
+// stand out. Tile boundaries are outlined in white.
+fn uv_checker_color(uv: vec2<f32>) -> vec3<f32> {
+    let cell = vec2<i32>(floor(uv * UV_CHECKER_CELLS));
+    let checker = select(0.25, 0.75, ((cell.x + cell.y) & 1) == 0);
+    let direction = vec3<f32>(fract(uv), 0.2);
+    let color = mix(vec3<f32>(checker), direction, 0.5);
+    return mix(color, vec3<f32>(1.0), uv_tile_line_mask(uv));
+}
+
+// Tangent frame check: the selected world-space tangent, bitangent or normal remapped
+// to 0-1 color. The bitangent is derived from tangent.w in the vertex shader, so
+// mirrored UV islands show it flipped. Tangents that are not perpendicular to the
+// normal (missing or broken) are magenta.
+fn tangent_frame_color(in: VertexOutput) -> vec3<f32> {
+    let T = normalize(in.tangent);
+    let B = normalize(in.bitangent);
+    let N = normalize(in.world_normal);
+    if abs(dot(T, N)) > 0.2 || abs(dot(B, N)) > 0.2 {
+        return vec3<f32>(1.0, 0.0, 1.0);
+    }
+    var axis = T;
+    if material_params.tangent_frame_axis == 1u {
+        axis = B;
+    } else if material_params.tangent_frame_axis == 2u {
+        axis = N;
+    }
+    return axis * 0.5 + 0.5;
+}
+
+// Coverage of a ~1px line at integer UV boundaries (where the texture repeats)
+fn uv_tile_line_mask(uv: vec2<f32>) -> f32 {
+    let distance = abs(fract(uv - 0.5) - 0.5) / max(fwidth(uv), vec2<f32>(1e-6));
+    return 1.0 - min(min(distance.x, distance.y), 1.0);
+}
+
+// Narkowicz's fit of the ACES filmic tone curve
+fn tone_map_aces(x: vec3<f32>) -> vec3<f32> {
+    return clamp((x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14), vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+// Compress linear HDR radiance into 0-1. This runs before the sRGB encode the
+// render target applies on write, so it operates on linear values.
+fn tone_map(color: vec3<f32>, exposure: f32, mode: u32) -> vec3<f32> {
+    let exposed = color * exposure;
+    if mode == 1u {
+        return exposed / (1.0 + exposed);
+    } else if mode == 2u {
+        return tone_map_aces(exposed);
+    }
+    return exposed;
+}
+
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    // Check which textures are available
-    let has_base_color = (material_params.texture_flags & (1u << 0u)) != 0u;
-    let has_normal = (material_params.texture_flags & (1u << 1u)) != 0u;
-    let has_metallic_roughness = (material_params.texture_flags & (1u << 2u)) != 0u;
-    let has_ao = (material_params.texture_flags & (1u << 3u)) != 0u;
-    let has_emissive = (material_params.texture_flags & (1u << 4u)) != 0u;
-    let has_height = (material_params.texture_flags & (1u << 5u)) != 0u;
+    var color = shade_fragment(in);
+    // Debug views show raw data, so only lit shading is tone mapped
+    if material_params.view_mode == 0u {
+        color = vec4<f32>(tone_map(color.rgb, material_params.exposure, material_params.tone_mapping), color.a);
+    }
+    
+    // Derivatives need uniform control flow, so the heatmap and the mask are computed unconditionally
+    let density = triangle_density_color(in);
+    if material_params.tessellation_debug == 1u {
+        color = vec4<f32>(density, 1.0);
+    }
+    let tile_line = uv_tile_line_mask(in.uv);
+    if (material_params.overlay_flags & (1u << 0u)) != 0u {
+        color = vec4<f32>(mix(color.rgb, material_params.uv_tile_line_color, tile_line), color.a);
+    }
+    return color;
+}
+
+// Blue (large triangles) to red (dense) by the triangle's edge length in screen pixels:
+// the interpolated per-vertex edge length divided by the world size of a pixel there
+fn triangle_density_color(in: VertexOutput) -> vec3<f32> {
+    let world_per_pixel = max(length(fwidth(in.world_position)) * 0.7071, 1e-6);
+    let edge_pixels = in.edge_length / world_per_pixel;
+    // 2 px edges map to red, 128 px edges to blue
+    let t = 1.0 - clamp((log2(max(edge_pixels, 1e-6)) - 1.0) / 6.0, 0.0, 1.0);
+    let heat = clamp(vec3<f32>(1.5) - abs(4.0 * t - vec3<f32>(3.0, 2.0, 1.0)), vec3<f32>(0.0), vec3<f32>(1.0));
+    // Keep the shape readable with a little view-facing shading
+    let V = normalize(camera.position.xyz - in.world_position);
+    let facing = abs(dot(normalize(in.world_normal), V));
+    return heat * (0.4 + 0.6 * facing);
+}
+
+fn shade_fragment(in: VertexOutput) -> vec4<f32> {
+    // Check which textures are available; the Lit view skips maps disabled for isolation (bits 8+)
+    let ignored_maps = select(0u, material_params.texture_flags >> 8u, material_params.view_mode == 0u);
+    let texture_flags = material_params.texture_flags & ~ignored_maps;
+    let has_base_color = (texture_flags & (1u << 0u)) != 0u;
+    let has_normal = (texture_flags & (1u << 1u)) != 0u;
+    let has_metallic_roughness = (texture_flags & (1u << 2u)) != 0u;
+    let has_ao = (texture_flags & (1u << 3u)) != 0u;
+    let has_emissive = (texture_flags & (1u << 4u)) != 0u;
+    let has_height = (texture_flags & (1u << 5u)) != 0u;
+    let has_orm_occlusion = (texture_flags & (1u << 6u)) != 0u;
+    let occlusion_uses_uv1 = (material_params.material_flags & (1u << 0u)) != 0u;
+    
+    // Parallax only shifts the lit view; the debug views show the textures unmodified
+    var uv = in.uv;
+    // (skipped while the vertices are displaced, which would apply the height map twice)
+    if material_params.view_mode == 0u && has_height && material_params.parallax_depth > 0.0
+        && material_params.displacement_enabled == 0u
+    {
+        uv = parallax_uv(in, in.uv, normalize(camera.position.xyz - in.world_position));
+    }
+    
+    // Displaced vertices keep their original normals, so shade with the height slope instead
+    var surface = in;
+    if has_height && material_params.displacement_enabled != 0u {
+        surface.world_normal = displaced_normal(in, uv);
+    }
+    
+    // Occlusion can live on the second UV set (common for glTF assets)
+    let occlusion_uv = select(uv, in.uv1, occlusion_uses_uv1);
     
     // Sample textures only if they exist, otherwise use defaults
     // For non-lit view modes, we'll sample raw textures without processing
     var base_color_sample: vec4<f32>;
     if has_base_color {
-        base_color_sample = textureSample(base_color_texture, base_color_sampler, in.uv);
+        base_color_sample = textureSample(base_color_texture, base_color_sampler, uv);
     } else {
         base_color_sample = vec4<f32>(material_params.base_color_tint, 1.0);
     }
     
     var normal_sample: vec4<f32>;
     if has_normal {
-        normal_sample = textureSample(normal_texture, normal_sampler, in.uv);
+        normal_sample = textureSample(normal_texture, normal_sampler, uv);
     } else {
         normal_sample = vec4<f32>(0.5, 0.5, 1.0, 1.0);  // Default flat normal
     }
     
     var metallic_roughness: vec4<f32>;
     if has_metallic_roughness {
-        metallic_roughness = textureSample(metallic_roughness_texture, metallic_roughness_sampler, in.uv);
+        metallic_roughness = textureSample(metallic_roughness_texture, metallic_roughness_sampler, uv);
     } else {
         metallic_roughness = vec4<f32>(0.0, 0.5, 0.0, 1.0);  // Default: no metallic, medium roughness
     }
+    // Glossiness maps store the inverse of roughness
+    if has_metallic_roughness && (material_params.material_flags & (1u << 2u)) != 0u {
+        metallic_roughness.g = 1.0 - metallic_roughness.g;
+    }
+    
+    // A dedicated AO map wins over the red channel of a packed ORM map
+    var occlusion_sample = 1.0;
+    if has_ao {
+        occlusion_sample = textureSample(ao_texture, ao_sampler, occlusion_uv).r;
+    } else if has_orm_occlusion {
+        occlusion_sample = textureSample(metallic_roughness_texture, metallic_roughness_sampler, occlusion_uv).r;
+    }
+    // Some bakers export cavity-style maps where white means occluded
+    if (has_ao || has_orm_occlusion) && (material_params.material_flags & (1u << 3u)) != 0u {
+        occlusion_sample = 1.0 - occlusion_sample;
+    }
+    
+    var emissive_sample = vec3<f32>(0.0);
+    if has_emissive {
+        emissive_sample = textureSample(emissive_texture, emissive_sampler, uv).rgb;
+    }
     
     // Handle different view modes
     let view_mode = material_params.view_mode;
     
     if view_mode == 0u {  // Lit
-        // Apply processing for lit mode
-        let base_color = base_color_sample.rgb * material_params.base_color_tint;
-        let metallic = metallic_roughness.b * material_params.metallic;
-        let roughness = metallic_roughness.g * material_params.roughness;
+        // Material matrix instances override individual multipliers
+        var metallic_param = material_params.metallic;
+        var roughness_param = material_params.roughness;
+        var tint_brightness = 1.0;
+        if (in.override_mask & (1u << 0u)) != 0u { metallic_param = in.instance_params.x; }
+        if (in.override_mask & (1u << 1u)) != 0u { roughness_param = in.instance_params.y; }
+        if (in.override_mask & (1u << 2u)) != 0u { tint_brightness = in.instance_params.z; }
+        
+        // Without a texture the multipliers are used directly
+        let metallic_tex = select(1.0, metallic_roughness.b, has_metallic_roughness);
+        let roughness_tex = select(1.0, metallic_roughness.g, has_metallic_roughness);
         
-        // Simple lighting (directional light)
-        let light_dir = normalize(material_params.light_direction);
-        let N = normalize(in.world_normal);
-        let NDotL = max(dot(N, light_dir), 0.0);
-        let color = base_color * (0.3 + 0.7 * NDotL);
-        return vec4<f32>(color, 1.0);
+        // Ambient occlusion scaled by the AO strength
+        let occlusion = clamp(mix(1.0, occlusion_sample, material_params.ao_strength), 0.0, 1.0);
+        let ao_affects_direct = (material_params.shading_flags & (1u << 0u)) != 0u;
+        
+        let base_color = base_color_sample.rgb * material_params.base_color_tint * tint_brightness;
+        let metallic = clamp(metallic_tex * metallic_param, 0.0, 1.0);
+        let roughness = clamp(roughness_tex * roughness_param, 0.04, 1.0);
+        
+        // Cook-Torrance BRDF with the key light plus the unshadowed secondary lights
+        let light_radiance = material_params.light_intensity * material_params.light_color;
+        let N = shading_normal(surface, normal_sample.rgb);
+        let specular_roughness = specular_aa_roughness(N, roughness);
+        let V = normalize(camera.position.xyz - in.world_position);
+        let L = normalize(material_params.light_direction);
+        let n_dot_v = max(dot(N, V), 0.0001);
+        let f0 = mix(vec3<f32>(0.04), base_color, metallic);
+        
+        // AO only attenuates indirect light; the direct lobes are already
+        // shadowed by n_dot_l (the toggle exists to compare with engines that get this wrong)
+        let shadowing = shadow_visibility(in.world_position, normalize(surface.world_normal));
+        var direct = direct_lighting(N, V, L, n_dot_v, base_color, metallic, f0, specular_roughness) * light_radiance * shadowing;
+        for (var i = 0u; i < min(material_params.secondary_light_count, 2u); i++) {
+            let secondary = material_params.secondary_lights[i];
+            direct += direct_lighting(N, V, secondary.direction, n_dot_v, base_color, metallic, f0, specular_roughness)
+                * secondary.intensity * secondary.color;
+        }
+        direct *= select(1.0, occlusion, ao_affects_direct);
+        var ambient = material_params.ambient_color * material_params.ambient_intensity * base_color * occlusion;
+        if (material_params.environment_flags & (1u << 0u)) != 0u {
+            ambient = environment_lighting(N, V, n_dot_v, base_color, metallic, f0, specular_roughness) * occlusion;
+        }
+        let emissive = emissive_sample * material_params.emissive_strength;
+        return vec4<f32>(ambient + direct + emissive, 1.0);
     } else if view_mode == 1u {  // BaseColor - show raw texture
         if has_base_color {
             return base_color_sample;  // Raw texture, no tint applied
         } else {
             return vec4<f32>(0.5, 0.5, 0.5, 1.0);  // Gray if no texture
         }
-    } else if view_mode == 2u {  // Normals - show raw texture
+    } else if view_mode == 2u {  // Normals
+        if material_params.normal_debug_space == 1u {
+            // Final world-space shading normal remapped to 0-1
+            let N = shading_normal(surface, normal_sample.rgb);
+            return vec4<f32>(N * 0.5 + 0.5, 1.0);
+        }
+        // Tangent space: show raw texture
         if has_normal {
             return normal_sample;  // Raw normal map texture
         } else {
@@ -174,39 +768,31 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
         } else {
             return vec4<f32>(0.0, 0.0, 0.0, 1.0);  // Black if no texture
         }
-    } else if view_mode == 5u {  // AO - show raw texture channel
-        if has_metallic_roughness {
-            // AO is typically in the alpha channel of metallic_roughness or ORM texture
-            // Show raw alpha channel as grayscale
-            return vec4<f32>(vec3<f32>(metallic_roughness.a), 1.0);
-        } else {
-            return vec4<f32>(1.0, 1.0, 1.0, 1.0);  // White if no texture
-        }
-    } else if view_mode == 6u {  // Emissive - show raw texture
-        // For now, emissive would need a separate texture binding
-        // If we had it, we'd show it here as raw texture
-        // For now, try to use base_color as fallback or show black
-        if has_base_color {
-            return base_color_sample;  // Fallback to base color
-        } else {
-            return vec4<f32>(0.0, 0.0, 0.0, 1.0);  // Black if no texture
-        }
-    } else if view_mode == 7u {  // Height - show raw texture
-        // Height would typically be in a separate texture
-        // For now, try to use metallic_roughness as fallback (some formats put height in red channel)
-        // Or we could use normal map as fallback
-        if has_metallic_roughness {
-            // Try red channel as height (common in some texture formats)
-            return vec4<f32>(vec3<f32>(metallic_roughness.r), 1.0);
-        } else if has_normal {
-            // Fallback to normal map (height maps are often similar to normal maps)
-            return vec4<f32>(normal_sample.rgb, 1.0);
+    } else if view_mode == 5u {  // AO - show raw texture channel (white if no texture)
+        return vec4<f32>(vec3<f32>(occlusion_sample), 1.0);
+    } else if view_mode == 6u {  // Emissive - show raw texture (black if no texture)
+        return vec4<f32>(emissive_sample, 1.0);
+    } else if view_mode == 7u {  // Height - show raw texture as grayscale
+        if has_height {
+            let height = textureSample(height_texture, height_sampler, in.uv).r;
+            return vec4<f32>(vec3<f32>(height), 1.0);
         } else {
             return vec4<f32>(0.5, 0.5, 0.5, 1.0);  // Gray if no texture
         }
+    } else if view_mode == 8u {  // UV Stretch heatmap
+        return vec4<f32>(uv_stretch_color(in.world_position, in.uv), 1.0);
+    } else if view_mode == 9u {  // UV checker (ignores loaded textures)
+        return vec4<f32>(uv_checker_color(in.uv), 1.0);
+    } else if view_mode == 10u {  // Tangent frame
+        return vec4<f32>(tangent_frame_color(in), 1.0);
     }
     
     // Fallback - return base color sample
     return base_color_sample;
 }
 
+// Flat color for the line-mode (wireframe) pipeline
+@fragment
+fn fs_wireframe(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(material_params.wireframe_color, 1.0);
+}
@@ -0,0 +1,66 @@
+//! Environment panorama drawn behind the scene
+
+struct SkyboxUniform {
+    // Inverse of projection * view with the camera translation removed
+    inv_view_proj: mat4x4<f32>,
+    env_rotation: vec2<f32>,  // sin/cos of the environment yaw
+    exposure: f32,
+    tone_mapping: u32,  // 0=none (clip), 1=Reinhard, 2=ACES filmic
+}
+
+@group(0) @binding(0)
+var<uniform> skybox: SkyboxUniform;
+@group(0) @binding(1)
+var environment_texture: texture_2d<f32>;
+@group(0) @binding(2)
+var environment_sampler: sampler;
+
+const PI: f32 = 3.14159265359;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) ndc: vec2<f32>,
+}
+
+// Fullscreen triangle on the far plane
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    var out: VertexOutput;
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    let ndc = uv * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0);
+    out.clip_position = vec4<f32>(ndc, 1.0, 1.0);
+    out.ndc = ndc;
+    return out;
+}
+
+// Same yaw rotation as environment_direction in pbr.wgsl, so reflections line up
+fn environment_direction(dir: vec3<f32>) -> vec3<f32> {
+    let s = skybox.env_rotation.x;
+    let c = skybox.env_rotation.y;
+    return vec3<f32>(c * dir.x + s * dir.z, dir.y, -s * dir.x + c * dir.z);
+}
+
+// Same tone curves as tone_map in pbr.wgsl, so the background matches lit surfaces
+fn tone_map(color: vec3<f32>) -> vec3<f32> {
+    let x = color * skybox.exposure;
+    if skybox.tone_mapping == 1u {
+        return x / (1.0 + x);
+    } else if skybox.tone_mapping == 2u {
+        return clamp((x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14), vec3<f32>(0.0), vec3<f32>(1.0));
+    }
+    return x;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Reconstruct the view ray through this pixel
+    let far_point = skybox.inv_view_proj * vec4<f32>(in.ndc, 1.0, 1.0);
+    let dir = environment_direction(normalize(far_point.xyz / far_point.w));
+    
+    // Equirectangular lookup (matches environment::sample_equirect: +Y up, image center at -Z)
+    let u = 0.5 + atan2(dir.x, -dir.z) / (2.0 * PI);
+    let v = 0.5 - asin(clamp(dir.y, -1.0, 1.0)) / PI;
+    // Explicit LOD: the u wrap would otherwise produce a seam from the derivative jump
+    let color = textureSampleLevel(environment_texture, environment_sampler, vec2<f32>(u, v), 0.0).rgb;
+    return vec4<f32>(tone_map(color), 1.0);
+}
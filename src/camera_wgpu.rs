@@ -1,6 +1,80 @@
 //! Camera system for wgpu renderer
 
-use glam::{Mat4, Vec3};
+use glam::{Mat4, Vec2, Vec3};
+
+/// Largest target shift of one cursor zoom step, as a fraction of the orbit distance
+const MAX_ZOOM_TARGET_SHIFT: f32 = 0.5;
+/// Duration of the animated move to a view preset (seconds)
+const VIEW_TRANSITION_SECONDS: f32 = 0.3;
+
+/// Canonical views looking at the orbit target from one side (+Z is the front)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewPreset {
+    Front,
+    Back,
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl ViewPreset {
+    pub fn all() -> &'static [ViewPreset] {
+        &[
+            ViewPreset::Front,
+            ViewPreset::Back,
+            ViewPreset::Left,
+            ViewPreset::Right,
+            ViewPreset::Top,
+            ViewPreset::Bottom,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ViewPreset::Front => "Front",
+            ViewPreset::Back => "Back",
+            ViewPreset::Left => "Left",
+            ViewPreset::Right => "Right",
+            ViewPreset::Top => "Top",
+            ViewPreset::Bottom => "Bottom",
+        }
+    }
+
+    /// Yaw and pitch of the orbit camera for this side (pitch is clamped by the camera)
+    fn yaw_pitch(&self) -> (f32, f32) {
+        use std::f32::consts::{FRAC_PI_2, PI};
+        match self {
+            ViewPreset::Front => (0.0, 0.0),
+            ViewPreset::Back => (PI, 0.0),
+            ViewPreset::Left => (-FRAC_PI_2, 0.0),
+            ViewPreset::Right => (FRAC_PI_2, 0.0),
+            ViewPreset::Top => (0.0, FRAC_PI_2),
+            ViewPreset::Bottom => (0.0, -FRAC_PI_2),
+        }
+    }
+}
+
+/// Orbit placement as displayed, trailing the requested one while smoothing
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct OrbitPose {
+    target: Vec3,
+    distance: f32,
+    yaw: f32,
+    pitch: f32,
+}
+
+/// Wrap an angle difference into -PI..PI so yaw changes take the short way around
+fn wrap_angle(angle: f32) -> f32 {
+    (angle + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI
+}
+
+/// Eased yaw/pitch animation towards a view preset
+struct ViewTransition {
+    from: (f32, f32),
+    to: (f32, f32),
+    elapsed: f32,
+}
 
 /// Camera controller with orbit behavior
 pub struct Camera {
@@ -37,9 +111,19 @@ impl Camera {
     pub fn view_proj_matrix(&self) -> Mat4 {
         self.projection_matrix() * self.view_matrix()
     }
+    
+    /// World-space ray (origin on the near plane, unit direction) through a point
+    /// of the viewport in normalized device coordinates (-1..1, +Y up)
+    pub fn screen_ray(&self, ndc: Vec2) -> (Vec3, Vec3) {
+        let inverse = self.view_proj_matrix().inverse();
+        let near = inverse.project_point3(ndc.extend(0.0));
+        let far = inverse.project_point3(ndc.extend(1.0));
+        (near, (far - near).normalize_or_zero())
+    }
 }
 
-/// Orbit camera controller
+/// Orbit camera controller. The public fields are the requested placement; with
+/// smoothing the displayed pose eases towards it (see `update_smoothing`).
 pub struct OrbitCamera {
     pub target: Vec3,
     pub distance: f32,
@@ -49,6 +133,9 @@ pub struct OrbitCamera {
     pub max_distance: f32,
     pub min_pitch: f32,
     pub max_pitch: f32,
+    transition: Option<ViewTransition>,
+    /// Displayed pose; `None` until the first smoothing update
+    shown: Option<OrbitPose>,
 }
 
 impl OrbitCamera {
@@ -62,38 +149,141 @@ impl OrbitCamera {
             max_distance: 50.0,
             min_pitch: -std::f32::consts::FRAC_PI_2 + 0.1,
             max_pitch: std::f32::consts::FRAC_PI_2 - 0.1,
+            transition: None,
+            shown: None,
         }
     }
     
+    fn requested_pose(&self) -> OrbitPose {
+        OrbitPose {
+            target: self.target,
+            distance: self.distance,
+            yaw: self.yaw,
+            pitch: self.pitch,
+        }
+    }
+    
+    fn shown_pose(&self) -> OrbitPose {
+        self.shown.unwrap_or_else(|| self.requested_pose())
+    }
+    
+    /// Displayed camera position
     pub fn calculate_position(&self) -> Vec3 {
-        let x = self.distance * self.pitch.cos() * self.yaw.sin();
-        let y = self.distance * self.pitch.sin();
-        let z = self.distance * self.pitch.cos() * self.yaw.cos();
-        self.target + Vec3::new(x, y, z)
+        let pose = self.shown_pose();
+        let x = pose.distance * pose.pitch.cos() * pose.yaw.sin();
+        let y = pose.distance * pose.pitch.sin();
+        let z = pose.distance * pose.pitch.cos() * pose.yaw.cos();
+        pose.target + Vec3::new(x, y, z)
     }
     
     #[allow(dead_code)]
     pub fn to_camera(&self) -> Camera {
         let position = self.calculate_position();
-        Camera::new(position, self.target)
+        Camera::new(position, self.shown_pose().target)
     }
     
     pub fn to_camera_with_aspect(&self, aspect: f32) -> Camera {
         let position = self.calculate_position();
-        let mut cam = Camera::new(position, self.target);
+        let mut cam = Camera::new(position, self.shown_pose().target);
         cam.aspect = aspect;
         cam
     }
     
-    pub fn rotate(&mut self, delta_yaw: f32, delta_pitch: f32) {
-        self.yaw += delta_yaw;
-        self.pitch = (self.pitch + delta_pitch)
-            .clamp(self.min_pitch, self.max_pitch);
+    /// Aim at `center` and move back until a sphere of `radius` fits the view.
+    /// `fov` is the vertical field of view in radians; the narrower of the
+    /// vertical and horizontal FOV is used so the sphere fits either way.
+    pub fn frame_bounds(&mut self, center: Vec3, radius: f32, fov: f32, aspect: f32) {
+        // Leave a little margin around the object
+        const MARGIN: f32 = 1.1;
+        let horizontal_fov = 2.0 * ((fov * 0.5).tan() * aspect).atan();
+        let half_fov = fov.min(horizontal_fov) * 0.5;
+        let distance = radius.max(1e-3) * MARGIN / half_fov.sin();
+        
+        self.target = center;
+        // Widen the zoom limits so tiny and huge models can both be framed
+        self.min_distance = self.min_distance.min(distance * 0.5);
+        self.max_distance = self.max_distance.max(distance * 2.0);
+        self.distance = distance;
+    }
+    
+    /// Snap to a view preset, keeping the target and distance
+    pub fn set_view(&mut self, preset: ViewPreset) {
+        let (yaw, pitch) = preset.yaw_pitch();
+        self.transition = None;
+        self.yaw = yaw;
+        self.pitch = pitch.clamp(self.min_pitch, self.max_pitch);
+    }
+    
+    /// Move to a view preset over a few frames (see `update_transition`)
+    pub fn animate_to_view(&mut self, preset: ViewPreset) {
+        let (yaw, pitch) = preset.yaw_pitch();
+        // Turn the short way around
+        let yaw_change = wrap_angle(yaw - self.yaw);
+        self.transition = Some(ViewTransition {
+            from: (self.yaw, self.pitch),
+            to: (self.yaw + yaw_change, pitch.clamp(self.min_pitch, self.max_pitch)),
+            elapsed: 0.0,
+        });
+    }
+    
+    /// Advance a running view preset animation; returns whether the camera moved
+    pub fn update_transition(&mut self, delta_time: f32) -> bool {
+        let Some(transition) = self.transition.as_mut() else {
+            return false;
+        };
+        transition.elapsed += delta_time;
+        let t = (transition.elapsed / VIEW_TRANSITION_SECONDS).min(1.0);
+        let eased = t * t * (3.0 - 2.0 * t);
+        self.yaw = transition.from.0 + (transition.to.0 - transition.from.0) * eased;
+        self.pitch = transition.from.1 + (transition.to.1 - transition.from.1) * eased;
+        if t >= 1.0 {
+            self.transition = None;
+        }
+        true
+    }
+    
+    /// Ease the displayed pose towards the requested one. `smoothing` is the time
+    /// constant in seconds (0 = follow instantly). Returns whether the camera moved.
+    pub fn update_smoothing(&mut self, delta_time: f32, smoothing: f32) -> bool {
+        let goal = self.requested_pose();
+        let shown = self.shown.get_or_insert(goal);
+        if *shown == goal {
+            return false;
+        }
+        if smoothing <= 0.0 {
+            *shown = goal;
+            return true;
+        }
+        let t = 1.0 - (-delta_time / smoothing).exp();
+        shown.target = shown.target.lerp(goal.target, t);
+        shown.distance += (goal.distance - shown.distance) * t;
+        shown.yaw += wrap_angle(goal.yaw - shown.yaw) * t;
+        shown.pitch += (goal.pitch - shown.pitch) * t;
+        // Settle once the remaining motion is imperceptible
+        let settled = shown.target.distance(goal.target) < goal.distance * 1e-4
+            && (goal.distance - shown.distance).abs() < goal.distance * 1e-4
+            && wrap_angle(goal.yaw - shown.yaw).abs() < 1e-4
+            && (goal.pitch - shown.pitch).abs() < 1e-4;
+        if settled {
+            *shown = goal;
+        }
+        true
     }
     
     pub fn zoom(&mut self, delta: f32) {
         self.distance = (self.distance + delta)
             .clamp(self.min_distance, self.max_distance);
     }
+    
+    /// Zoom while moving the target towards `point` by the same fraction the distance
+    /// changes, so the point under the cursor stays in place. The target shift is
+    /// clamped per step so a far-off point can't make the orbit center run away.
+    pub fn zoom_towards(&mut self, delta: f32, point: Vec3) {
+        let old_distance = self.distance;
+        self.zoom(delta);
+        let fraction = 1.0 - self.distance / old_distance;
+        let shift = ((point - self.target) * fraction).clamp_length_max(old_distance * MAX_ZOOM_TARGET_SHIFT);
+        self.target += shift;
+    }
 }
 
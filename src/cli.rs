@@ -0,0 +1,69 @@
+//! Command-line argument parsing
+
+use std::path::PathBuf;
+
+/// Printed for `--help`
+pub const USAGE: &str = "\
+Usage: pbr-viewer [OPTIONS] [PATH]...
+
+Each PATH is opened like a dropped file: a folder or .zip archive is loaded
+as the texture set, a model (.obj, .gltf, .glb) replaces the mesh and an
+.hdr/.exr panorama becomes the environment.
+
+Options:
+  --debug            Enable wgpu validation and the in-app debug log
+  --watch-shader     Reload assets/shaders/pbr.wgsl from disk when it changes
+  --backend <LIST>   Restrict the graphics APIs tried, e.g. vulkan, metal,
+                     dx12 or gl (comma separated; also WGPU_BACKEND)
+  -h, --help         Print this help and exit";
+
+/// Options passed on the command line
+#[derive(Debug, Clone, Default)]
+pub struct CliArgs {
+    /// Enable wgpu validation layers and the in-app debug log panel
+    pub debug: bool,
+    /// Load the PBR shader from the source tree and rebuild it whenever the file changes
+    pub watch_shader: bool,
+    /// Graphics backends to choose from (`None` tries every one)
+    pub backends: Option<wgpu::Backends>,
+    /// Print usage and exit
+    pub help: bool,
+    /// Texture folders, archives, models or environments to open on startup
+    pub paths: Vec<PathBuf>,
+}
+
+impl CliArgs {
+    /// Parse options from the process arguments
+    pub fn parse() -> Self {
+        let mut args = Self::default();
+        let mut raw_args = std::env::args().skip(1);
+        while let Some(arg) = raw_args.next() {
+            match arg.as_str() {
+                "--debug" => args.debug = true,
+                "--watch-shader" => args.watch_shader = true,
+                "--backend" => match raw_args.next() {
+                    Some(list) => args.backends = parse_backends(&list),
+                    None => eprintln!("--backend needs a value, e.g. --backend vulkan"),
+                },
+                other if other.starts_with("--backend=") => {
+                    args.backends = parse_backends(&other["--backend=".len()..]);
+                }
+                "-h" | "--help" => args.help = true,
+                other if other.starts_with('-') => eprintln!("Ignoring unknown argument: {}", other),
+                path => args.paths.push(PathBuf::from(path)),
+            }
+        }
+        args
+    }
+}
+
+/// Parse a comma-separated backend list, ignoring it if no name is recognized
+fn parse_backends(list: &str) -> Option<wgpu::Backends> {
+    let backends = wgpu::util::parse_backends_from_comma_list(list);
+    if backends.is_empty() {
+        eprintln!("Ignoring unknown backend list: {}", list);
+        None
+    } else {
+        Some(backends)
+    }
+}
@@ -0,0 +1,97 @@
+//! User configuration files (startup defaults)
+
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use crate::state_wgpu::{AppState, LightParams, MaterialParams, DEFAULT_BACKGROUND_COLOR};
+
+/// File name of the startup defaults inside the config directory
+const DEFAULT_CONFIG_FILE_NAME: &str = "default_config.json";
+
+/// Directory where the viewer stores its configuration files
+pub fn config_dir() -> Option<PathBuf> {
+    directories::ProjectDirs::from("", "", "pbr-viewer")
+        .map(|dirs| dirs.config_dir().to_path_buf())
+}
+
+/// Material, light and background settings applied on launch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ViewerConfig {
+    pub material_params: MaterialParams,
+    pub light_params: LightParams,
+    pub secondary_lights: Vec<LightParams>,
+    pub background_color: [f32; 3],
+}
+
+impl Default for ViewerConfig {
+    fn default() -> Self {
+        Self {
+            material_params: MaterialParams::default(),
+            light_params: LightParams::default(),
+            secondary_lights: Vec::new(),
+            background_color: DEFAULT_BACKGROUND_COLOR,
+        }
+    }
+}
+
+impl ViewerConfig {
+    /// Path of the startup defaults file
+    pub fn default_path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join(DEFAULT_CONFIG_FILE_NAME))
+    }
+
+    /// Load the startup defaults, returning `None` if no file exists or it can't be parsed
+    pub fn load_default() -> Option<Self> {
+        let path = Self::default_path()?;
+        if !path.exists() {
+            return None;
+        }
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                log::warn!("Failed to read default config {}: {}", path.display(), e);
+                return None;
+            }
+        };
+        match serde_json::from_str(&contents) {
+            Ok(config) => {
+                log::info!("Loaded default config from {}", path.display());
+                Some(config)
+            }
+            Err(e) => {
+                log::warn!("Ignoring invalid default config {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    /// Write this configuration as the startup defaults
+    pub fn save_as_default(&self) -> Result<PathBuf, anyhow::Error> {
+        let path = Self::default_path()
+            .ok_or_else(|| anyhow::anyhow!("Could not determine config directory"))?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+
+    /// Capture the current settings from the app state
+    pub fn from_state(state: &AppState) -> Self {
+        Self {
+            material_params: state.material_params.clone(),
+            light_params: state.light_params.clone(),
+            secondary_lights: state.secondary_lights.clone(),
+            background_color: state.background_color,
+        }
+    }
+
+    /// Apply these settings to the app state
+    pub fn apply_to(&self, state: &mut AppState) {
+        state.material_params = self.material_params.clone();
+        state.light_params = self.light_params.clone();
+        state.secondary_lights = self.secondary_lights.clone();
+        state.background_color = self.background_color;
+        state.material_changed = true;
+    }
+}
@@ -0,0 +1,225 @@
+//! In-app command console for scripting the viewer
+//!
+//! Commands are plain text (`set roughness 0.3`, `view normals`, `load /path`)
+//! and act on `AppState` fields plus the matching change flags, exactly as the
+//! UI controls do.
+
+use crate::mesh_wgpu::MeshType;
+use crate::state_wgpu::{AppState, ViewMode, MAX_FOV_DEGREES, MAX_RENDER_SCALE, MIN_FOV_DEGREES, MIN_RENDER_SCALE};
+
+/// Maximum number of lines kept in the console output
+const MAX_CONSOLE_LINES: usize = 200;
+
+const HELP_TEXT: &str = "\
+Commands:
+  set <name> <value>   set a parameter (see 'params')
+  view <mode>          lit, basecolor, normals, roughness, metallic, ao, emissive, height, uvstretch
+  mesh <type>          sphere, icosphere, cube, plane, roundedrect
+  load <path>          load a texture folder, .zip archive, model or .hdr/.exr environment
+  screenshot [path]    save the viewport to an image (PNG by default)
+  frame                fit the camera to the mesh
+  reset                reset all settings to defaults
+  params               list settable parameters
+  clear                clear the console";
+
+/// Numeric parameters settable with `set`: name and valid range
+const PARAMS: &[(&str, f32, f32)] = &[
+    ("metallic", 0.0, 1.0),
+    ("roughness", 0.0, 1.0),
+    ("normal_strength", 0.0, 2.0),
+    ("ao_strength", 0.0, 2.0),
+    ("emissive_strength", 0.0, 5.0),
+    ("parallax_depth", 0.0, 0.2),
+    ("uv_scale", 0.1, 5.0),
+    ("uv_rotation", -180.0, 180.0),
+    ("light_intensity", 0.0, 20.0),
+    ("ambient_intensity", 0.0, 2.0),
+    ("light_temperature", 1000.0, 12000.0),
+    ("render_scale", MIN_RENDER_SCALE, MAX_RENDER_SCALE),
+    ("fov", MIN_FOV_DEGREES, MAX_FOV_DEGREES),
+];
+
+/// A line of console output
+#[derive(Debug, Clone)]
+pub struct ConsoleLine {
+    pub text: String,
+    pub is_error: bool,
+}
+
+/// Console window state: visibility, input field and output history
+#[derive(Debug, Default)]
+pub struct Console {
+    pub open: bool,
+    /// Focus the input field on the next frame (set when the console opens)
+    pub focus_input: bool,
+    pub input: String,
+    pub lines: Vec<ConsoleLine>,
+}
+
+impl Console {
+    fn print(&mut self, text: impl Into<String>, is_error: bool) {
+        for line in text.into().lines() {
+            if self.lines.len() >= MAX_CONSOLE_LINES {
+                self.lines.remove(0);
+            }
+            self.lines.push(ConsoleLine {
+                text: line.to_string(),
+                is_error,
+            });
+        }
+    }
+}
+
+/// Run the console's current input against the app state and echo the result
+pub fn submit(state: &mut AppState) {
+    let line = std::mem::take(&mut state.console.input);
+    let line = line.trim();
+    if line.is_empty() {
+        return;
+    }
+    state.console.print(format!("> {}", line), false);
+
+    if line.eq_ignore_ascii_case("clear") {
+        state.console.lines.clear();
+        return;
+    }
+    match run_command(state, line) {
+        Ok(output) if output.is_empty() => {}
+        Ok(output) => state.console.print(output, false),
+        Err(e) => state.console.print(format!("error: {}", e), true),
+    }
+}
+
+/// Parse and execute one command
+pub fn run_command(state: &mut AppState, line: &str) -> Result<String, anyhow::Error> {
+    let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let rest = rest.trim();
+
+    match command.to_ascii_lowercase().as_str() {
+        "help" | "?" => Ok(HELP_TEXT.to_string()),
+        "params" => Ok(PARAMS
+            .iter()
+            .map(|(name, min, max)| format!("  {} ({} – {})", name, min, max))
+            .collect::<Vec<_>>()
+            .join("\n")),
+        "set" => {
+            let mut args = rest.split_whitespace();
+            match (args.next(), args.next(), args.next()) {
+                (Some(name), Some(value), None) => set_param(state, name, value),
+                _ => anyhow::bail!("usage: set <name> <value>"),
+            }
+        }
+        "view" => {
+            let wanted = normalize_name(rest);
+            let mode = ViewMode::all()
+                .iter()
+                .find(|mode| normalize_name(mode.name()) == wanted)
+                .ok_or_else(|| anyhow::anyhow!("unknown view mode '{}'", rest))?;
+            state.view_mode = *mode;
+            state.material_changed = true;
+            Ok(format!("view = {}", mode.name()))
+        }
+        "mesh" => {
+            let wanted = normalize_name(rest);
+            let mesh = MeshType::primitives()
+                .iter()
+                .find(|mesh| normalize_name(mesh.name()) == wanted)
+                .ok_or_else(|| anyhow::anyhow!("unknown mesh '{}'", rest))?;
+            state.current_mesh = *mesh;
+            state.mesh_changed = true;
+            Ok(format!("mesh = {}", mesh.name()))
+        }
+        "load" => {
+            if rest.is_empty() {
+                anyhow::bail!("usage: load <path>");
+            }
+            let path = std::path::Path::new(rest);
+            if !path.exists() {
+                anyhow::bail!("path does not exist: {}", rest);
+            }
+            if crate::model_loader::is_model_file(path) {
+                state.model_load_request = Some(path.to_path_buf());
+            } else if crate::environment::is_environment_file(path) {
+                state.environment_load_request = Some(path.to_path_buf());
+            } else {
+                state.texture_folder = Some(rest.to_string());
+                state.textures_need_reload = true;
+            }
+            Ok(format!("loading {}", rest))
+        }
+        "screenshot" => {
+            let path = if rest.is_empty() {
+                default_screenshot_path()
+            } else {
+                std::path::PathBuf::from(rest)
+            };
+            image::ImageFormat::from_path(&path)
+                .map_err(|_| anyhow::anyhow!("unsupported image format: {}", path.display()))?;
+            let message = format!("capturing to {}", path.display());
+            state.screenshot_request = Some(path);
+            Ok(message)
+        }
+        "frame" => {
+            state.frame_requested = true;
+            Ok("framing mesh".to_string())
+        }
+        "reset" => {
+            state.reset_all();
+            Ok("settings reset to defaults".to_string())
+        }
+        _ => anyhow::bail!("unknown command '{}' (try 'help')", command),
+    }
+}
+
+/// Set a numeric parameter, clamping it to its valid range
+fn set_param(state: &mut AppState, name: &str, value: &str) -> Result<String, anyhow::Error> {
+    let name = name.to_ascii_lowercase();
+    let (_, min, max) = PARAMS
+        .iter()
+        .find(|(param, _, _)| *param == name)
+        .ok_or_else(|| anyhow::anyhow!("unknown parameter '{}' (try 'params')", name))?;
+    let value: f32 = value
+        .parse()
+        .map_err(|_| anyhow::anyhow!("'{}' is not a number", value))?;
+    let value = value.clamp(*min, *max);
+
+    match name.as_str() {
+        "metallic" => state.material_params.metallic_multiplier = value,
+        "roughness" => state.material_params.roughness_multiplier = value,
+        "normal_strength" => state.material_params.normal_strength = value,
+        "ao_strength" => state.material_params.ao_strength = value,
+        "emissive_strength" => state.material_params.emissive_strength = value,
+        "parallax_depth" => state.material_params.displacement_strength = value,
+        "uv_scale" => state.material_params.uv_scale = value,
+        "uv_rotation" => state.material_params.uv_rotation_degrees = value,
+        "light_intensity" => state.light_params.intensity = value,
+        "ambient_intensity" => state.light_params.ambient_intensity = value,
+        "light_temperature" => {
+            state.light_params.color_mode = crate::state_wgpu::LightColorMode::Kelvin;
+            state.light_params.temperature_kelvin = value;
+            state.light_params.color = crate::state_wgpu::kelvin_to_rgb(value);
+        }
+        "render_scale" => state.render_scale = value,
+        "fov" => state.fov_degrees = value,
+        _ => unreachable!("parameter table and setters are out of sync"),
+    }
+    state.material_changed = true;
+    Ok(format!("{} = {}", name, value))
+}
+
+/// `screenshot_<unix time>.png` in the working directory
+pub fn default_screenshot_path() -> std::path::PathBuf {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    std::path::PathBuf::from(format!("screenshot_{}.png", timestamp))
+}
+
+/// Lowercase and strip separators so "Base Color", "base_color" and "basecolor" match
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
@@ -0,0 +1,73 @@
+//! Log capture for the in-app debug panel
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Maximum number of entries kept for the debug panel
+const MAX_LOG_ENTRIES: usize = 500;
+
+/// A captured log message
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub level: log::Level,
+    pub target: String,
+    pub message: String,
+}
+
+/// Shared buffer of recent log messages
+pub type LogBuffer = Arc<Mutex<VecDeque<LogEntry>>>;
+
+/// Logger that forwards to env_logger and keeps recent messages for the UI
+struct CaptureLogger {
+    inner: env_logger::Logger,
+    buffer: LogBuffer,
+}
+
+impl log::Log for CaptureLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::Level::Info || self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.inner.matches(record) {
+            self.inner.log(record);
+        }
+        if record.level() <= log::Level::Info {
+            if let Ok(mut buffer) = self.buffer.lock() {
+                if buffer.len() >= MAX_LOG_ENTRIES {
+                    buffer.pop_front();
+                }
+                buffer.push_back(LogEntry {
+                    level: record.level(),
+                    target: record.target().to_string(),
+                    message: record.args().to_string(),
+                });
+            }
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+/// Initialize logging. When `capture` is set, messages are also kept in the
+/// returned buffer so they can be shown in the debug panel.
+pub fn init(capture: bool) -> Option<LogBuffer> {
+    if !capture {
+        env_logger::init();
+        return None;
+    }
+
+    let inner = env_logger::Builder::from_default_env().build();
+    let max_level = inner.filter().max(log::LevelFilter::Info);
+    let buffer: LogBuffer = Arc::new(Mutex::new(VecDeque::new()));
+    let logger = CaptureLogger {
+        inner,
+        buffer: buffer.clone(),
+    };
+    if log::set_boxed_logger(Box::new(logger)).is_ok() {
+        log::set_max_level(max_level);
+    }
+    Some(buffer)
+}
@@ -0,0 +1,449 @@
+//! HDR environment maps for image-based lighting
+//!
+//! An equirectangular `.hdr`/`.exr` image is resampled into a radiance cubemap on
+//! the CPU, then turned into the two cubes the PBR shader reads:
+//! - a small irradiance cube for diffuse lighting (via 3rd order spherical harmonics)
+//! - a specular cube whose mip levels are GGX-prefiltered for increasing roughness
+//!
+//! - the panorama itself (downscaled) for drawing the skybox background
+//!
+//! All are uploaded as `Rgba16Float`, which is filterable on every backend.
+
+use std::path::Path;
+use glam::Vec3;
+use half::f16;
+use wgpu::*;
+
+/// Environment file extensions that can be loaded
+pub const ENVIRONMENT_EXTENSIONS: &[&str] = &["hdr", "exr"];
+
+/// Face size of the specular cube's first mip (mirror reflections)
+const SPECULAR_SIZE: u32 = 128;
+/// Number of specular mips; roughness 0..1 maps linearly onto them.
+/// Must match `ENV_SPECULAR_MAX_MIP` (+ 1) in pbr.wgsl.
+pub const SPECULAR_MIP_LEVELS: u32 = 6;
+/// Face size of the irradiance cube (irradiance is very low frequency)
+const IRRADIANCE_SIZE: u32 = 32;
+/// GGX samples per texel when prefiltering the specular mips
+const PREFILTER_SAMPLES: u32 = 64;
+/// Maximum width of the background panorama
+const BACKGROUND_MAX_WIDTH: u32 = 4096;
+
+/// Check if a path has a supported environment map extension
+pub fn is_environment_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| ENVIRONMENT_EXTENSIONS.iter().any(|ext| e.eq_ignore_ascii_case(ext)))
+        .unwrap_or(false)
+}
+
+/// GPU resources of an environment map
+pub struct EnvironmentMap {
+    pub irradiance: (Texture, TextureView),
+    pub specular: (Texture, TextureView),
+    pub sampler: Sampler,
+    /// Equirectangular panorama for the skybox
+    pub background: (Texture, TextureView),
+}
+
+impl EnvironmentMap {
+    /// Black 1x1 cubes, bound while no environment is loaded
+    /// (the shader falls back to the fixed ambient term then)
+    pub fn placeholder(device: &Device, queue: &Queue) -> Self {
+        let black = CubeImage::filled(1, Vec3::ZERO);
+        Self {
+            irradiance: upload_cube(device, queue, std::slice::from_ref(&black), "environment_irradiance_placeholder"),
+            specular: upload_cube(device, queue, &[black], "environment_specular_placeholder"),
+            sampler: create_environment_sampler(device),
+            background: upload_equirect(device, queue, &image::Rgb32FImage::new(1, 1), "environment_background_placeholder"),
+        }
+    }
+
+    /// Load an equirectangular HDR/EXR image and build the IBL cubes
+    pub fn load(device: &Device, queue: &Queue, path: &Path) -> Result<Self, anyhow::Error> {
+        let image = image::open(path)
+            .map_err(|e| anyhow::anyhow!("failed to read {}: {}", path.display(), e))?
+            .into_rgb32f();
+        if image.width() < 2 || image.height() < 2 {
+            anyhow::bail!("{} is too small for an environment map", path.display());
+        }
+        log::info!("Loaded environment {} ({}x{})", path.display(), image.width(), image.height());
+
+        let background = if image.width() > BACKGROUND_MAX_WIDTH {
+            let height = (BACKGROUND_MAX_WIDTH / 2).max(1);
+            image::imageops::resize(&image, BACKGROUND_MAX_WIDTH, height, image::imageops::FilterType::Triangle)
+        } else {
+            image.clone()
+        };
+
+        // Pre-shrink large panoramas with a proper filter so the point lookups
+        // into the equirect below don't alias (a 4:1 ratio of cube face to
+        // equirect width keeps roughly one equirect texel per cube texel)
+        let target_width = (SPECULAR_SIZE * 4).min(image.width());
+        let target_height = (target_width / 2).max(1);
+        let equirect = if image.width() > target_width {
+            image::imageops::resize(&image, target_width, target_height, image::imageops::FilterType::Triangle)
+        } else {
+            image
+        };
+
+        let radiance = CubeImage::from_fn(SPECULAR_SIZE, |dir| sample_equirect(&equirect, dir));
+        let specular_mips = prefilter_specular(&radiance);
+        let irradiance = irradiance_from_sh(&project_sh(&radiance.downsampled_to(IRRADIANCE_SIZE)));
+
+        Ok(Self {
+            irradiance: upload_cube(device, queue, &[irradiance], "environment_irradiance"),
+            specular: upload_cube(device, queue, &specular_mips, "environment_specular"),
+            sampler: create_environment_sampler(device),
+            background: upload_equirect(device, queue, &background, "environment_background"),
+        })
+    }
+}
+
+fn create_environment_sampler(device: &Device) -> Sampler {
+    device.create_sampler(&SamplerDescriptor {
+        label: Some("environment_sampler"),
+        address_mode_u: AddressMode::ClampToEdge,
+        address_mode_v: AddressMode::ClampToEdge,
+        address_mode_w: AddressMode::ClampToEdge,
+        mag_filter: FilterMode::Linear,
+        min_filter: FilterMode::Linear,
+        mipmap_filter: FilterMode::Linear,
+        ..Default::default()
+    })
+}
+
+/// Pack linear RGB into RGBA half floats
+fn to_rgba16f(texels: impl Iterator<Item = Vec3>) -> Vec<u16> {
+    texels
+        .flat_map(|c| [c.x, c.y, c.z, 1.0])
+        // Drops NaN/negative texels; bright suns would overflow half floats to infinity
+        .map(|v| f16::from_f32(v.max(0.0).min(f16::MAX.to_f32())).to_bits())
+        .collect()
+}
+
+/// Upload an equirectangular panorama as a single-mip 2D texture
+fn upload_equirect(device: &Device, queue: &Queue, image: &image::Rgb32FImage, label: &str) -> (Texture, TextureView) {
+    let (width, height) = image.dimensions();
+    let size = Extent3d {
+        width,
+        height,
+        depth_or_array_layers: 1,
+    };
+    let texture = device.create_texture(&TextureDescriptor {
+        label: Some(label),
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format: TextureFormat::Rgba16Float,
+        usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+    let data = to_rgba16f(image.pixels().map(|p| Vec3::new(p[0], p[1], p[2])));
+    queue.write_texture(
+        texture.as_image_copy(),
+        bytemuck::cast_slice(&data),
+        ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(8 * width),
+            rows_per_image: Some(height),
+        },
+        size,
+    );
+    let view = texture.create_view(&TextureViewDescriptor::default());
+    (texture, view)
+}
+
+/// Upload cube faces; each entry of `mips` is one mip level, largest first
+fn upload_cube(device: &Device, queue: &Queue, mips: &[CubeImage], label: &str) -> (Texture, TextureView) {
+    let size = mips[0].size;
+    let texture = device.create_texture(&TextureDescriptor {
+        label: Some(label),
+        size: Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 6,
+        },
+        mip_level_count: mips.len() as u32,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format: TextureFormat::Rgba16Float,
+        usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+
+    for (mip_level, mip) in mips.iter().enumerate() {
+        for (face, texels) in mip.faces.iter().enumerate() {
+            let data = to_rgba16f(texels.iter().copied());
+            queue.write_texture(
+                ImageCopyTexture {
+                    texture: &texture,
+                    mip_level: mip_level as u32,
+                    origin: Origin3d { x: 0, y: 0, z: face as u32 },
+                    aspect: TextureAspect::All,
+                },
+                bytemuck::cast_slice(&data),
+                ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(8 * mip.size),
+                    rows_per_image: Some(mip.size),
+                },
+                Extent3d {
+                    width: mip.size,
+                    height: mip.size,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+    }
+
+    let view = texture.create_view(&TextureViewDescriptor {
+        label: Some(label),
+        dimension: Some(TextureViewDimension::Cube),
+        ..Default::default()
+    });
+    (texture, view)
+}
+
+/// Linear RGB cubemap on the CPU, faces in wgpu order (+X, -X, +Y, -Y, +Z, -Z)
+#[derive(Clone)]
+struct CubeImage {
+    size: u32,
+    faces: [Vec<Vec3>; 6],
+}
+
+impl CubeImage {
+    fn filled(size: u32, color: Vec3) -> Self {
+        Self::from_fn(size, |_| color)
+    }
+
+    /// Evaluate `f` at the direction through the center of every texel
+    fn from_fn(size: u32, mut f: impl FnMut(Vec3) -> Vec3) -> Self {
+        let faces = std::array::from_fn(|face| {
+            let mut texels = Vec::with_capacity((size * size) as usize);
+            for y in 0..size {
+                for x in 0..size {
+                    texels.push(f(texel_direction(face, x, y, size)));
+                }
+            }
+            texels
+        });
+        Self { size, faces }
+    }
+
+    /// Box-filter down to `size` (must divide the current size)
+    fn downsampled_to(&self, size: u32) -> Self {
+        let factor = (self.size / size.max(1)).max(1);
+        let weight = 1.0 / (factor * factor) as f32;
+        let faces = std::array::from_fn(|face| {
+            let source = &self.faces[face];
+            let mut texels = Vec::with_capacity((size * size) as usize);
+            for y in 0..size {
+                for x in 0..size {
+                    let mut sum = Vec3::ZERO;
+                    for sy in 0..factor {
+                        for sx in 0..factor {
+                            sum += source[((y * factor + sy) * self.size + x * factor + sx) as usize];
+                        }
+                    }
+                    texels.push(sum * weight);
+                }
+            }
+            texels
+        });
+        Self { size, faces }
+    }
+
+    /// Bilinear lookup within the face `dir` points at (edges are clamped, not
+    /// blended across faces, which is invisible at the sizes used here)
+    fn sample(&self, dir: Vec3) -> Vec3 {
+        let (face, u, v) = direction_to_face_uv(dir);
+        let size = self.size as f32;
+        let x = (u * size - 0.5).clamp(0.0, size - 1.0);
+        let y = (v * size - 0.5).clamp(0.0, size - 1.0);
+        let (x0, y0) = (x.floor() as u32, y.floor() as u32);
+        let (x1, y1) = ((x0 + 1).min(self.size - 1), (y0 + 1).min(self.size - 1));
+        let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+        let texels = &self.faces[face];
+        let at = |x: u32, y: u32| texels[(y * self.size + x) as usize];
+        let top = at(x0, y0).lerp(at(x1, y0), fx);
+        let bottom = at(x0, y1).lerp(at(x1, y1), fx);
+        top.lerp(bottom, fy)
+    }
+}
+
+/// Direction through the center of texel (x, y) of a cube face
+fn texel_direction(face: usize, x: u32, y: u32, size: u32) -> Vec3 {
+    // Face coordinates in [-1, 1], u to the right and v down
+    let u = (x as f32 + 0.5) / size as f32 * 2.0 - 1.0;
+    let v = (y as f32 + 0.5) / size as f32 * 2.0 - 1.0;
+    let dir = match face {
+        0 => Vec3::new(1.0, -v, -u),
+        1 => Vec3::new(-1.0, -v, u),
+        2 => Vec3::new(u, 1.0, v),
+        3 => Vec3::new(u, -1.0, -v),
+        4 => Vec3::new(u, -v, 1.0),
+        _ => Vec3::new(-u, -v, -1.0),
+    };
+    dir.normalize()
+}
+
+/// Inverse of `texel_direction`: face index and face UV in [0, 1]
+fn direction_to_face_uv(dir: Vec3) -> (usize, f32, f32) {
+    let abs = dir.abs();
+    let (face, u, v, major) = if abs.x >= abs.y && abs.x >= abs.z {
+        if dir.x > 0.0 { (0, -dir.z, -dir.y, abs.x) } else { (1, dir.z, -dir.y, abs.x) }
+    } else if abs.y >= abs.z {
+        if dir.y > 0.0 { (2, dir.x, dir.z, abs.y) } else { (3, dir.x, -dir.z, abs.y) }
+    } else if dir.z > 0.0 {
+        (4, dir.x, -dir.y, abs.z)
+    } else {
+        (5, -dir.x, -dir.y, abs.z)
+    };
+    (face, (u / major + 1.0) * 0.5, (v / major + 1.0) * 0.5)
+}
+
+/// Bilinear lookup of an equirectangular panorama (+Y up, center of the image at -Z)
+fn sample_equirect(image: &image::Rgb32FImage, dir: Vec3) -> Vec3 {
+    let (width, height) = image.dimensions();
+    let u = 0.5 + dir.x.atan2(-dir.z) / std::f32::consts::TAU;
+    let v = 0.5 - dir.y.clamp(-1.0, 1.0).asin() / std::f32::consts::PI;
+    let x = u * width as f32 - 0.5;
+    let y = (v * height as f32 - 0.5).clamp(0.0, height as f32 - 1.0);
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+    let at = |x: f32, y: f32| {
+        // Wrap horizontally around the seam, clamp at the poles
+        let x = (x as i64).rem_euclid(width as i64) as u32;
+        let y = (y as u32).min(height - 1);
+        let p = image.get_pixel(x, y);
+        // Clamp NaN/negative texels some encoders produce
+        Vec3::new(p[0], p[1], p[2]).max(Vec3::ZERO)
+    };
+    let top = at(x0, y0).lerp(at(x0 + 1.0, y0), fx);
+    let bottom = at(x0, y0 + 1.0).lerp(at(x0 + 1.0, y0 + 1.0), fx);
+    top.lerp(bottom, fy)
+}
+
+/// Build the specular mip chain: mip 0 is the radiance itself, each following
+/// mip is convolved with the GGX lobe of roughness `mip / (levels - 1)`
+fn prefilter_specular(radiance: &CubeImage) -> Vec<CubeImage> {
+    // Source chain for filtered importance sampling: wide lobes read coarser mips
+    let mut sources = vec![radiance.clone()];
+    while let Some(last) = sources.last().filter(|mip| mip.size > 1) {
+        let next = last.downsampled_to(last.size / 2);
+        sources.push(next);
+    }
+
+    let mut mips = vec![radiance.clone()];
+    for mip in 1..SPECULAR_MIP_LEVELS {
+        let size = (SPECULAR_SIZE >> mip).max(1);
+        let roughness = mip as f32 / (SPECULAR_MIP_LEVELS - 1) as f32;
+        mips.push(CubeImage::from_fn(size, |n| prefilter_texel(&sources, n, roughness)));
+    }
+    mips
+}
+
+/// Split-sum prefiltered radiance around `n` (assuming N = V = R)
+fn prefilter_texel(sources: &[CubeImage], n: Vec3, roughness: f32) -> Vec3 {
+    let alpha = roughness * roughness;
+    let (tangent, bitangent) = orthonormal_basis(n);
+    // Solid angle of one texel of the full-resolution source
+    let texel_solid_angle = 4.0 * std::f32::consts::PI / (6.0 * (sources[0].size * sources[0].size) as f32);
+
+    let mut sum = Vec3::ZERO;
+    let mut total_weight = 0.0;
+    for i in 0..PREFILTER_SAMPLES {
+        let (xi_x, xi_y) = hammersley(i, PREFILTER_SAMPLES);
+        // GGX-distributed half vector around n
+        let phi = std::f32::consts::TAU * xi_x;
+        let cos_theta = ((1.0 - xi_y) / (1.0 + (alpha * alpha - 1.0) * xi_y)).sqrt();
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let h = (tangent * (phi.cos() * sin_theta) + bitangent * (phi.sin() * sin_theta) + n * cos_theta).normalize();
+        let l = 2.0 * n.dot(h) * h - n;
+        let n_dot_l = n.dot(l);
+        if n_dot_l <= 0.0 {
+            continue;
+        }
+
+        // Read a blurrier mip where samples are sparse (Karis 2013)
+        let n_dot_h = n.dot(h).max(0.0);
+        let a2 = alpha * alpha;
+        let d = n_dot_h * n_dot_h * (a2 - 1.0) + 1.0;
+        let distribution = a2 / (std::f32::consts::PI * d * d);
+        let pdf = distribution / 4.0 + 1e-4;
+        let sample_solid_angle = 1.0 / (PREFILTER_SAMPLES as f32 * pdf);
+        let lod = (0.5 * (sample_solid_angle / texel_solid_angle).log2() + 1.0)
+            .clamp(0.0, (sources.len() - 1) as f32);
+
+        sum += sources[lod.round() as usize].sample(l) * n_dot_l;
+        total_weight += n_dot_l;
+    }
+    if total_weight > 0.0 { sum / total_weight } else { sources[0].sample(n) }
+}
+
+fn orthonormal_basis(n: Vec3) -> (Vec3, Vec3) {
+    let up = if n.y.abs() < 0.999 { Vec3::Y } else { Vec3::X };
+    let tangent = up.cross(n).normalize();
+    (tangent, n.cross(tangent))
+}
+
+/// Low-discrepancy sample `i` of `count` in [0, 1)^2
+fn hammersley(i: u32, count: u32) -> (f32, f32) {
+    (i as f32 / count as f32, i.reverse_bits() as f32 * 2.328_306_4e-10)
+}
+
+/// Real spherical harmonics basis up to band 2
+fn sh_basis(d: Vec3) -> [f32; 9] {
+    [
+        0.282095,
+        0.488603 * d.y,
+        0.488603 * d.z,
+        0.488603 * d.x,
+        1.092548 * d.x * d.y,
+        1.092548 * d.y * d.z,
+        0.315392 * (3.0 * d.z * d.z - 1.0),
+        1.092548 * d.x * d.z,
+        0.546274 * (d.x * d.x - d.y * d.y),
+    ]
+}
+
+/// Project the radiance cube onto 9 SH coefficients, weighting texels by solid angle
+fn project_sh(cube: &CubeImage) -> [Vec3; 9] {
+    let mut coefficients = [Vec3::ZERO; 9];
+    let mut total_weight = 0.0;
+    for (face, texels) in cube.faces.iter().enumerate() {
+        for y in 0..cube.size {
+            for x in 0..cube.size {
+                let u = (x as f32 + 0.5) / cube.size as f32 * 2.0 - 1.0;
+                let v = (y as f32 + 0.5) / cube.size as f32 * 2.0 - 1.0;
+                // Texels near face corners cover less of the sphere
+                let weight = (1.0 + u * u + v * v).powf(-1.5);
+                let dir = texel_direction(face, x, y, cube.size);
+                let radiance = texels[(y * cube.size + x) as usize];
+                for (coefficient, basis) in coefficients.iter_mut().zip(sh_basis(dir)) {
+                    *coefficient += radiance * (basis * weight);
+                }
+                total_weight += weight;
+            }
+        }
+    }
+    let normalization = 4.0 * std::f32::consts::PI / total_weight;
+    coefficients.map(|c| c * normalization)
+}
+
+/// Cosine-convolve the SH radiance into a cube of irradiance / π, so the shader
+/// only multiplies by the albedo (Ramamoorthi & Hanrahan 2001)
+fn irradiance_from_sh(coefficients: &[Vec3; 9]) -> CubeImage {
+    // Clamped cosine lobe per band, divided by π
+    const BAND_SCALE: [f32; 9] = [1.0, 2.0 / 3.0, 2.0 / 3.0, 2.0 / 3.0, 0.25, 0.25, 0.25, 0.25, 0.25];
+    CubeImage::from_fn(IRRADIANCE_SIZE, |dir| {
+        let irradiance = sh_basis(dir)
+            .iter()
+            .zip(coefficients)
+            .zip(BAND_SCALE)
+            .fold(Vec3::ZERO, |sum, ((basis, coefficient), scale)| sum + *coefficient * (basis * scale));
+        irradiance.max(Vec3::ZERO)
+    })
+}
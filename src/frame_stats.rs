@@ -0,0 +1,116 @@
+//! Frame-time history and the FPS overlay drawn over the 3D viewport
+
+use std::collections::VecDeque;
+use egui::*;
+
+/// Number of recent frames averaged and plotted
+const FRAME_HISTORY: usize = 120;
+/// Size of the frame-time sparkline
+const SPARKLINE_SIZE: Vec2 = Vec2::new(160.0, 36.0);
+/// Reference line drawn at the 60 FPS frame budget (seconds)
+const TARGET_FRAME_TIME: f32 = 1.0 / 60.0;
+
+/// Ring buffer of recent frame times
+#[derive(Debug, Default)]
+pub struct FrameStats {
+    frame_times: VecDeque<f32>,
+    /// Setting switched last and the average frame time before the switch
+    comparison: Option<(String, f32)>,
+}
+
+impl FrameStats {
+    /// Record the duration of the last frame in seconds
+    pub fn push(&mut self, frame_time: f32) {
+        if self.frame_times.len() >= FRAME_HISTORY {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(frame_time);
+    }
+
+    /// Remember the current average and restart the history, so the overlay shows
+    /// how a render setting changed the frame time
+    pub fn start_comparison(&mut self, label: impl Into<String>) {
+        if let Some(baseline) = self.average_frame_time() {
+            self.comparison = Some((label.into(), baseline));
+        }
+        self.frame_times.clear();
+    }
+
+    /// Mean frame time over the history, in seconds
+    pub fn average_frame_time(&self) -> Option<f32> {
+        if self.frame_times.is_empty() {
+            return None;
+        }
+        Some(self.frame_times.iter().sum::<f32>() / self.frame_times.len() as f32)
+    }
+
+    /// Longest frame in the history, in seconds
+    pub fn max_frame_time(&self) -> f32 {
+        self.frame_times.iter().copied().fold(0.0, f32::max)
+    }
+
+    /// Draw the overlay anchored below the view mode bar; returns its bottom edge
+    pub fn show(&self, ctx: &Context) -> Option<f32> {
+        let average = self.average_frame_time()?;
+        let response = Area::new(Id::new("frame_stats"))
+            .anchor(Align2::RIGHT_TOP, [-12.0, 40.0])
+            .order(Order::Foreground)
+            .interactable(false)
+            .show(ctx, |ui| {
+                Frame::none()
+                    .fill(Color32::from_rgba_unmultiplied(25, 25, 32, 235))
+                    .rounding(6.0)
+                    .inner_margin(Margin::symmetric(10.0, 6.0))
+                    .show(ui, |ui| {
+                        ui.label(RichText::new(format!("{:.0} FPS", 1.0 / average.max(1e-6))).strong().monospace());
+                        ui.label(
+                            RichText::new(format!(
+                                "{:.2} ms avg, {:.2} ms max",
+                                average * 1000.0,
+                                self.max_frame_time() * 1000.0,
+                            ))
+                            .small()
+                            .monospace()
+                            .weak(),
+                        );
+                        if let Some((label, baseline)) = &self.comparison {
+                            ui.label(
+                                RichText::new(format!(
+                                    "{}: {:+.2} ms (was {:.2} ms)",
+                                    label,
+                                    (average - baseline) * 1000.0,
+                                    baseline * 1000.0,
+                                ))
+                                .small()
+                                .monospace(),
+                            );
+                        }
+                        self.sparkline(ui);
+                    });
+            });
+        Some(response.response.rect.bottom())
+    }
+
+    /// Frame times as a line, scaled so the slowest frame (or the 60 FPS budget) fits
+    fn sparkline(&self, ui: &mut Ui) {
+        let (rect, _) = ui.allocate_exact_size(SPARKLINE_SIZE, Sense::hover());
+        let painter = ui.painter_at(rect);
+        let scale = self.max_frame_time().max(TARGET_FRAME_TIME * 1.5);
+        let to_y = |frame_time: f32| rect.bottom() - (frame_time / scale).min(1.0) * rect.height();
+
+        let target_y = to_y(TARGET_FRAME_TIME);
+        painter.line_segment(
+            [pos2(rect.left(), target_y), pos2(rect.right(), target_y)],
+            Stroke::new(1.0, Color32::from_rgb(70, 70, 85)),
+        );
+
+        let step = rect.width() / (FRAME_HISTORY - 1) as f32;
+        let offset = FRAME_HISTORY - self.frame_times.len();
+        let points: Vec<Pos2> = self.frame_times
+            .iter()
+            .enumerate()
+            .map(|(i, &frame_time)| pos2(rect.left() + (offset + i) as f32 * step, to_y(frame_time)))
+            .collect();
+        painter.add(Shape::line(points, Stroke::new(1.0, Color32::from_rgb(100, 200, 255))));
+    }
+}
@@ -0,0 +1,138 @@
+//! Procedural reference grid drawn on the ground plane
+
+use wgpu::*;
+use wgpu::util::DeviceExt;
+use bytemuck::{Pod, Zeroable};
+use crate::render_target::SCENE_DEPTH_FORMAT;
+use crate::shader::load_shader_from_str;
+use crate::state_wgpu::GridParams;
+
+const GRID_SHADER: &str = include_str!("../assets/shaders/grid.wgsl");
+
+/// Grid uniform (matches `GridUniform` in grid.wgsl)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct GridUniform {
+    pub color: [f32; 3],
+    pub spacing: f32,
+    pub height: f32,
+    pub fade_distance: f32,
+    pub show_axes: u32,
+    pub _padding: f32,
+}
+
+/// Pipeline and uniform for the grid floor
+pub struct GridPipeline {
+    pub pipeline: wgpu::RenderPipeline,
+    uniform_buffer: Buffer,
+    bind_group: BindGroup,
+}
+
+impl GridPipeline {
+    /// `camera_bind_group_layout` is the PBR pipeline's group 0 (camera + model)
+    pub fn new(device: &Device, camera_bind_group_layout: &BindGroupLayout, format: TextureFormat, sample_count: u32) -> Self {
+        let shader = load_shader_from_str(device, GRID_SHADER, Some("grid_shader"));
+
+        let uniform_buffer = device.create_buffer_init(&util::BufferInitDescriptor {
+            label: Some("Grid Buffer"),
+            contents: bytemuck::cast_slice(&[GridUniform::zeroed()]),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::VERTEX | ShaderStages::FRAGMENT,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+            label: Some("grid_bind_group_layout"),
+        });
+
+        let bind_group = device.create_bind_group(&BindGroupDescriptor {
+            layout: &bind_group_layout,
+            entries: &[BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+            label: Some("grid_bind_group"),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("Grid Pipeline Layout"),
+            bind_group_layouts: &[camera_bind_group_layout, &bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("Grid Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(ColorTargetState {
+                    format,
+                    blend: Some(BlendState::ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            // Visible from above and below
+            primitive: PrimitiveState {
+                cull_mode: None,
+                ..Default::default()
+            },
+            // Hidden behind the model but transparent, so it never writes depth.
+            // The polygon offset keeps it behind a model base lying on the grid,
+            // on top of the layer separation in `AppState::ground_height`.
+            depth_stencil: Some(DepthStencilState {
+                format: SCENE_DEPTH_FORMAT,
+                depth_write_enabled: false,
+                depth_compare: CompareFunction::LessEqual,
+                stencil: StencilState::default(),
+                bias: DepthBiasState { constant: 2, slope_scale: 1.0, clamp: 0.0 },
+            }),
+            multisample: MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
+            multiview: None,
+        });
+
+        Self {
+            pipeline,
+            uniform_buffer,
+            bind_group,
+        }
+    }
+
+    /// Upload the grid settings; `height` is the world-space Y of the ground plane
+    pub fn update(&self, queue: &Queue, params: &GridParams, height: f32) {
+        let uniform = GridUniform {
+            color: params.color,
+            spacing: params.spacing,
+            height,
+            fade_distance: params.fade_distance,
+            show_axes: params.show_axes as u32,
+            _padding: 0.0,
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniform]));
+    }
+
+    /// Draw the grid; expects group 0 (camera + model) to be bound by the caller
+    pub fn draw<'a>(&'a self, render_pass: &mut RenderPass<'a>) {
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(1, &self.bind_group, &[]);
+        render_pass.draw(0..6, 0..1);
+    }
+}
@@ -1,15 +1,25 @@
 //! Input handling for camera and interaction
 
 use winit::event::{ElementState, MouseButton, WindowEvent};
-use glam::Vec2;
+use glam::{UVec2, Vec2};
+
+/// Pixels of touchpad scrolling that count as one mouse wheel line
+const PIXELS_PER_SCROLL_LINE: f32 = 100.0;
 
 /// Input state tracking
 pub struct InputState {
+    /// Cursor position in physical pixels (framebuffer space)
     pub mouse_position: Vec2,
+    /// Cursor position in logical points (egui space)
+    pub mouse_position_logical: Vec2,
+    /// Window scale factor (physical pixels per logical point)
+    pub scale_factor: f64,
+    /// Cursor movement since the last frame, summed over all events
     pub mouse_delta: Vec2,
     pub left_mouse_pressed: bool,
     pub right_mouse_pressed: bool,
     pub middle_mouse_pressed: bool,
+    /// Scroll since the last frame in wheel lines, summed over all events
     pub scroll_delta: f32,
 }
 
@@ -17,6 +27,8 @@ impl InputState {
     pub fn new() -> Self {
         Self {
             mouse_position: Vec2::ZERO,
+            mouse_position_logical: Vec2::ZERO,
+            scale_factor: 1.0,
             mouse_delta: Vec2::ZERO,
             left_mouse_pressed: false,
             right_mouse_pressed: false,
@@ -25,14 +37,25 @@ impl InputState {
         }
     }
 
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.scale_factor = scale_factor;
+        self.mouse_position_logical = self.mouse_position / scale_factor as f32;
+    }
+
     pub fn update_from_event(&mut self, event: &WindowEvent) -> bool {
         match event {
             WindowEvent::CursorMoved { position, .. } => {
                 let new_pos = Vec2::new(position.x as f32, position.y as f32);
-                self.mouse_delta = new_pos - self.mouse_position;
+                // Accumulate: several move events can arrive between two frames
+                self.mouse_delta += new_pos - self.mouse_position;
                 self.mouse_position = new_pos;
+                self.mouse_position_logical = new_pos / self.scale_factor as f32;
                 true
             }
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                self.set_scale_factor(*scale_factor);
+                false
+            }
             WindowEvent::MouseInput { button, state, .. } => {
                 match button {
                     MouseButton::Left => {
@@ -51,10 +74,10 @@ impl InputState {
             WindowEvent::MouseWheel { delta, .. } => {
                 match delta {
                     winit::event::MouseScrollDelta::LineDelta(_, y) => {
-                        self.scroll_delta = *y;
+                        self.scroll_delta += *y;
                     }
                     winit::event::MouseScrollDelta::PixelDelta(pos) => {
-                        self.scroll_delta = pos.y as f32 * 0.01;
+                        self.scroll_delta += pos.y as f32 / PIXELS_PER_SCROLL_LINE;
                     }
                 }
                 true
@@ -67,9 +90,54 @@ impl InputState {
         }
     }
 
+    /// Framebuffer texel under the cursor, or `None` if it is outside the framebuffer
+    pub fn cursor_texel(&self, framebuffer_size: UVec2) -> Option<UVec2> {
+        logical_to_texel(self.mouse_position_logical, self.scale_factor, framebuffer_size)
+    }
+
+    /// Clear the per-frame deltas; call once per frame after they were consumed
     pub fn reset_frame(&mut self) {
         self.mouse_delta = Vec2::ZERO;
         self.scroll_delta = 0.0;
     }
 }
 
+
+/// Convert a logical (point) position to framebuffer texel coordinates
+/// using the window scale factor. Returns `None` outside the framebuffer.
+pub fn logical_to_texel(logical: Vec2, scale_factor: f64, framebuffer_size: UVec2) -> Option<UVec2> {
+    let physical = logical * scale_factor as f32;
+    if physical.x < 0.0 || physical.y < 0.0 {
+        return None;
+    }
+    let texel = physical.floor().as_uvec2();
+    (texel.x < framebuffer_size.x && texel.y < framebuffer_size.y).then_some(texel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn logical_to_texel_scales_by_the_scale_factor() {
+        let framebuffer = UVec2::new(200, 100);
+        assert_eq!(logical_to_texel(Vec2::new(10.0, 10.0), 2.0, framebuffer), Some(UVec2::new(20, 20)));
+        assert_eq!(logical_to_texel(Vec2::new(10.25, 10.75), 2.0, framebuffer), Some(UVec2::new(20, 21)));
+    }
+
+    #[test]
+    fn logical_to_texel_rejects_positions_outside_the_framebuffer() {
+        let framebuffer = UVec2::new(200, 100);
+        assert_eq!(logical_to_texel(Vec2::new(-1.0, 10.0), 2.0, framebuffer), None);
+        assert_eq!(logical_to_texel(Vec2::new(50.0, 50.0), 2.0, framebuffer), None);
+    }
+
+    #[test]
+    fn cursor_texel_uses_the_logical_position() {
+        let mut input = InputState::new();
+        input.mouse_position = Vec2::new(20.0, 20.0);
+        input.set_scale_factor(2.0);
+        assert_eq!(input.mouse_position_logical, Vec2::new(10.0, 10.0));
+        assert_eq!(input.cursor_texel(UVec2::new(200, 100)), Some(UVec2::new(20, 20)));
+    }
+}
@@ -0,0 +1,119 @@
+//! Per-instance data for drawing the material matrix preview
+
+use wgpu::*;
+use wgpu::util::DeviceExt;
+use bytemuck::{Pod, Zeroable};
+use crate::state_wgpu::{MaterialMatrixParams, MatrixAxisVariable};
+
+/// Per-instance vertex data: world offset plus material overrides
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct InstanceRaw {
+    pub offset: [f32; 3],
+    pub override_mask: u32,  // Bit per MatrixAxisVariable::override_bit
+    pub params: [f32; 4],  // x=metallic, y=roughness, z=tint brightness, w=unused
+}
+
+impl InstanceRaw {
+    /// A single instance at the origin that uses the material uniform as-is
+    pub fn identity() -> Self {
+        Self {
+            offset: [0.0; 3],
+            override_mask: 0,
+            params: [0.0; 4],
+        }
+    }
+
+    fn set_variable(&mut self, variable: MatrixAxisVariable, value: f32) {
+        self.override_mask |= variable.override_bit();
+        self.params[variable.param_index()] = value;
+    }
+
+    pub fn desc() -> VertexBufferLayout<'static> {
+        VertexBufferLayout {
+            array_stride: std::mem::size_of::<InstanceRaw>() as BufferAddress,
+            step_mode: VertexStepMode::Instance,
+            attributes: &[
+                VertexAttribute {
+                    offset: 0,
+                    shader_location: 5,
+                    format: VertexFormat::Float32x3,
+                },
+                VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 3]>() as BufferAddress,
+                    shader_location: 6,
+                    format: VertexFormat::Uint32,
+                },
+                VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 4]>() as BufferAddress,
+                    shader_location: 7,
+                    format: VertexFormat::Float32x4,
+                },
+            ],
+        }
+    }
+}
+
+/// Build the instance list for the current material matrix settings.
+/// A disabled matrix yields a single identity instance.
+pub fn build_instances(matrix: &MaterialMatrixParams) -> Vec<InstanceRaw> {
+    if !matrix.enabled {
+        return vec![InstanceRaw::identity()];
+    }
+
+    let columns = matrix.columns.max(1);
+    let rows = matrix.rows.max(1);
+    let axis_t = |index: u32, count: u32| {
+        if count > 1 { index as f32 / (count - 1) as f32 } else { 0.5 }
+    };
+    // Center the grid on the origin
+    let half_width = (columns - 1) as f32 * matrix.spacing * 0.5;
+    let half_height = (rows - 1) as f32 * matrix.spacing * 0.5;
+
+    let mut instances = Vec::with_capacity((columns * rows) as usize);
+    for row in 0..rows {
+        for column in 0..columns {
+            let mut instance = InstanceRaw::identity();
+            instance.offset = [
+                column as f32 * matrix.spacing - half_width,
+                half_height - row as f32 * matrix.spacing,
+                0.0,
+            ];
+            instance.set_variable(matrix.x_axis, matrix.x_axis.value_at(axis_t(column, columns)));
+            instance.set_variable(matrix.y_axis, matrix.y_axis.value_at(axis_t(row, rows)));
+            instances.push(instance);
+        }
+    }
+    instances
+}
+
+/// Distance from the origin to the farthest instance of the material matrix
+pub fn max_offset(matrix: &MaterialMatrixParams) -> f32 {
+    if !matrix.enabled {
+        return 0.0;
+    }
+    let half_width = (matrix.columns.max(1) - 1) as f32 * matrix.spacing * 0.5;
+    let half_height = (matrix.rows.max(1) - 1) as f32 * matrix.spacing * 0.5;
+    half_width.hypot(half_height)
+}
+
+/// GPU instance buffer
+pub struct InstanceBuffer {
+    pub buffer: Buffer,
+    pub count: u32,
+}
+
+impl InstanceBuffer {
+    pub fn new(device: &Device, instances: &[InstanceRaw]) -> Self {
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Instance Buffer"),
+            contents: bytemuck::cast_slice(instances),
+            usage: BufferUsages::VERTEX,
+        });
+
+        Self {
+            buffer,
+            count: instances.len() as u32,
+        }
+    }
+}
@@ -0,0 +1,174 @@
+//! Line-list pipeline for debug overlays drawn in model space
+
+use wgpu::*;
+use wgpu::util::DeviceExt;
+use glam::Vec3;
+use bytemuck::{Pod, Zeroable};
+use crate::mesh_wgpu::Aabb;
+use crate::render_target::SCENE_DEPTH_FORMAT;
+use crate::shader::load_shader_from_str;
+
+const LINES_SHADER: &str = include_str!("../assets/shaders/lines.wgsl");
+
+/// Endpoint of a line segment
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct LineVertex {
+    pub position: [f32; 3],
+    pub color: [f32; 4],
+}
+
+impl LineVertex {
+    const ATTRIBUTES: [VertexAttribute; 2] = vertex_attr_array![0 => Float32x3, 1 => Float32x4];
+
+    pub fn desc() -> VertexBufferLayout<'static> {
+        VertexBufferLayout {
+            array_stride: std::mem::size_of::<LineVertex>() as BufferAddress,
+            step_mode: VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+/// Line segments for the edges of a bounding box
+pub fn aabb_lines(aabb: &Aabb, color: [f32; 4]) -> Vec<LineVertex> {
+    aabb.edges()
+        .iter()
+        .flat_map(|(a, b)| {
+            [
+                LineVertex { position: a.to_array(), color },
+                LineVertex { position: b.to_array(), color },
+            ]
+        })
+        .collect()
+}
+
+/// Sun gizmo for a directional light: a ring with short rays placed `radius` * 1.5
+/// from `center` along `direction` (pointing towards the light), and an arrow from
+/// there towards the edge of the sphere of `radius`
+pub fn light_gizmo_lines(center: Vec3, radius: f32, direction: Vec3, color: [f32; 4]) -> Vec<LineVertex> {
+    const RING_SEGMENTS: usize = 24;
+    const RAYS: usize = 8;
+    let direction = direction.normalize_or_zero();
+    if direction == Vec3::ZERO {
+        return Vec::new();
+    }
+    let (u, v) = direction.any_orthonormal_pair();
+    let sun = center + direction * radius * 1.5;
+    let tip = center + direction * radius * 1.05;
+    let sun_radius = radius * 0.12;
+    let around = |i: usize, count: usize| {
+        let angle = i as f32 / count as f32 * std::f32::consts::TAU;
+        u * angle.cos() + v * angle.sin()
+    };
+
+    let mut segments = Vec::new();
+    for i in 0..RING_SEGMENTS {
+        segments.push((sun + around(i, RING_SEGMENTS) * sun_radius, sun + around(i + 1, RING_SEGMENTS) * sun_radius));
+    }
+    for i in 0..RAYS {
+        let ray = around(i, RAYS);
+        segments.push((sun + ray * sun_radius * 1.4, sun + ray * sun_radius * 2.0));
+    }
+    // Shaft from the ring towards the scene, with a four-line head at the tip
+    segments.push((sun - direction * sun_radius, tip));
+    let head_base = tip + direction * sun_radius * 1.5;
+    for i in 0..4 {
+        segments.push((tip, head_base + around(i, 4) * sun_radius * 0.6));
+    }
+
+    segments
+        .into_iter()
+        .flat_map(|(a, b)| {
+            [
+                LineVertex { position: a.to_array(), color },
+                LineVertex { position: b.to_array(), color },
+            ]
+        })
+        .collect()
+}
+
+/// Pipeline plus the current set of lines to draw
+pub struct LinePipeline {
+    pub pipeline: wgpu::RenderPipeline,
+    vertex_buffer: Option<Buffer>,
+    vertex_count: u32,
+}
+
+impl LinePipeline {
+    /// `camera_bind_group_layout` is the PBR pipeline's group 0 (camera + model)
+    pub fn new(device: &Device, camera_bind_group_layout: &BindGroupLayout, format: TextureFormat, sample_count: u32) -> Self {
+        let shader = load_shader_from_str(device, LINES_SHADER, Some("lines_shader"));
+
+        let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("Line Pipeline Layout"),
+            bind_group_layouts: &[camera_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("Line Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[LineVertex::desc()],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(ColorTargetState {
+                    format,
+                    blend: Some(BlendState::ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: PrimitiveState {
+                topology: PrimitiveTopology::LineList,
+                ..Default::default()
+            },
+            // Depth-tested against the scene but never occluding it
+            depth_stencil: Some(DepthStencilState {
+                format: SCENE_DEPTH_FORMAT,
+                depth_write_enabled: false,
+                depth_compare: CompareFunction::LessEqual,
+                stencil: StencilState::default(),
+                bias: DepthBiasState::default(),
+            }),
+            multisample: MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
+            multiview: None,
+        });
+
+        Self {
+            pipeline,
+            vertex_buffer: None,
+            vertex_count: 0,
+        }
+    }
+
+    /// Replace the drawn lines (pairs of vertices form one segment)
+    pub fn set_lines(&mut self, device: &Device, vertices: &[LineVertex]) {
+        self.vertex_count = vertices.len() as u32;
+        self.vertex_buffer = (!vertices.is_empty()).then(|| {
+            device.create_buffer_init(&util::BufferInitDescriptor {
+                label: Some("Line Vertex Buffer"),
+                contents: bytemuck::cast_slice(vertices),
+                usage: BufferUsages::VERTEX,
+            })
+        });
+    }
+
+    /// Draw the lines; expects group 0 (camera + model) to be bound by the caller
+    pub fn draw<'a>(&'a self, render_pass: &mut RenderPass<'a>) {
+        if let Some(buffer) = &self.vertex_buffer {
+            render_pass.set_pipeline(&self.pipeline);
+            render_pass.set_vertex_buffer(0, buffer.slice(..));
+            render_pass.draw(0..self.vertex_count, 0..1);
+        }
+    }
+}
@@ -13,28 +13,64 @@ mod texture_loader;
 mod input;
 mod ui_wgpu;
 mod egui_integration;
+mod config;
+mod cli;
+mod debug_log;
+mod instance;
+mod render_target;
+mod tooltips;
+mod notifications;
+mod share_code;
+mod console;
+mod line_pipeline;
+mod readback;
+mod texture_worker;
+mod grid;
+mod model_loader;
+mod environment;
+mod skybox;
+mod shader_watch;
+mod frame_stats;
+mod shadow;
+mod session;
 
 // Re-export for convenience
 pub use mesh_wgpu::MeshType;
 pub use state_wgpu::{AppState, ViewMode, TessellationDebugMode};
 
 use winit::{
-    event::{Event, WindowEvent},
+    event::{ElementState, Event, WindowEvent},
+    keyboard::{Key, KeyCode, NamedKey, PhysicalKey},
     event_loop::{ControlFlow, EventLoop},
-    window::{Window, WindowBuilder},
+    window::{Fullscreen, Window, WindowBuilder},
 };
 use renderer::Renderer;
-use state_wgpu::{AppState as WgpuAppState, DEFAULT_UI_PANEL_WIDTH};
-use camera_wgpu::{OrbitCamera, Camera};
+use state_wgpu::{AppState as WgpuAppState, LoadedTextures, MaterialParams, TextureLoadReport, MeshStats, WireframeMode, DEFAULT_UI_PANEL_WIDTH, DEFAULT_MSAA_SAMPLES};
+use camera_wgpu::{OrbitCamera, Camera, ViewPreset};
 use pipeline::RenderPipeline;
-use mesh_wgpu::{create_sphere, create_cube};
+use mesh_wgpu::{create_sphere, create_icosphere, create_cube, create_plane, create_rounded_rect, icosphere_subdivisions, Aabb};
 use mesh_buffer::MeshBuffer;
+use instance::InstanceBuffer;
+use render_target::{CompositePipeline, SceneTarget};
+use line_pipeline::LinePipeline;
+use readback::ReadbackManager;
+use texture_worker::{TextureLoadJob, TextureLoadWorker};
+use grid::GridPipeline;
 use texture_manager::TextureSet;
+use environment::EnvironmentMap;
+use skybox::SkyboxPipeline;
+use texture::TextureLoadOptions;
 use shader::load_shader_from_str;
+use shader_watch::{ShaderWatcher, PBR_SHADER_PATH};
 use glam::Mat4;
+use std::time::Instant;
 use input::InputState;
 use egui_integration::EguiState;
 use ui_wgpu::build_ui;
+use config::ViewerConfig;
+use cli::CliArgs;
+use session::{Session, DEFAULT_WINDOW_SIZE};
+use notifications::NotificationLevel;
 
 // Embed shader source
 const PBR_SHADER: &str = include_str!("../assets/shaders/pbr.wgsl");
@@ -42,29 +78,53 @@ const PBR_SHADER: &str = include_str!("../assets/shaders/pbr.wgsl");
 // Store render state
 struct RenderState {
     render_pipeline: RenderPipeline,
+    texture_set: TextureSet,
     texture_bind_group: wgpu::BindGroup,
+    environment: EnvironmentMap,
     mesh_buffer: MeshBuffer,
+    instance_buffer: InstanceBuffer,
+    composite_pipeline: CompositePipeline,
+    scene_target: SceneTarget,
+    line_pipeline: LinePipeline,
+    light_gizmo: LinePipeline,
+    grid_pipeline: GridPipeline,
+    skybox_pipeline: SkyboxPipeline,
+    shader_watcher: Option<ShaderWatcher>,
+    readback: ReadbackManager,
+    texture_worker: TextureLoadWorker,
     orbit_camera: OrbitCamera,
     app_state: WgpuAppState,
     camera: Camera,
     input_state: InputState,
     egui_state: EguiState,
+    // Frame timing
+    last_frame: Instant,
+    delta_time: f32,
 }
 
 fn main() -> Result<(), anyhow::Error> {
-    env_logger::init();
+    let args = CliArgs::parse();
+    if args.help {
+        println!("{}", cli::USAGE);
+        return Ok(());
+    }
+    let log_buffer = debug_log::init(args.debug);
     log::info!("PBR Texture Viewer started!");
     
+    // Settings of the previous session (a missing or corrupt file means a fresh start)
+    let session = Session::load();
+    let [window_width, window_height] = session.as_ref().map_or(DEFAULT_WINDOW_SIZE, |session| session.window_size);
+    
     let event_loop = EventLoop::new()?;
     let window = WindowBuilder::new()
         .with_title("PBR Texture Viewer")
-        .with_inner_size(winit::dpi::LogicalSize::new(1600.0, 900.0))
+        .with_inner_size(winit::dpi::LogicalSize::new(window_width.max(320.0), window_height.max(240.0)))
         .build(&event_loop)?;
     
     let window_ref = &window; // Store reference for closure
     
     let mut renderer = pollster::block_on(async {
-        Renderer::new(window_ref).await
+        Renderer::new(window_ref, args.debug, args.backends).await
     })?;
     
     // Initialize egui
@@ -80,21 +140,26 @@ fn main() -> Result<(), anyhow::Error> {
     // Create render pipeline
     let mut render_pipeline = RenderPipeline::new(
         &renderer.device,
-        &shader,
+        shader,
         renderer.config.format,
+        DEFAULT_MSAA_SAMPLES,
     )?;
     
     // Create placeholder textures
     let texture_set = TextureSet::create_placeholder(&renderer.device, &renderer.queue);
+    let environment = EnvironmentMap::placeholder(&renderer.device, &renderer.queue);
     let texture_bind_group_layout = TextureSet::bind_group_layout(&renderer.device);
-    let texture_bind_group = texture_set.create_bind_group(&renderer.device, &texture_bind_group_layout);
+    let texture_bind_group = texture_set.create_bind_group(&renderer.device, &texture_bind_group_layout, &environment);
     
     // Create mesh
     let mesh_data = create_sphere(32);
     let mesh_buffer = MeshBuffer::new(&renderer.device, &mesh_data);
     
     // Camera setup (accounting for UI panel width)
-    let orbit_camera = OrbitCamera::new(glam::Vec3::ZERO, 3.0);
+    let mut orbit_camera = OrbitCamera::new(glam::Vec3::ZERO, 3.0);
+    if let Some(session) = &session {
+        session.apply_camera(&mut orbit_camera);
+    }
     let pixels_per_point = window_ref.scale_factor() as f32;
     let panel_width_pixels = DEFAULT_UI_PANEL_WIDTH * pixels_per_point;
     let viewport_width = (renderer.size.width as f32 - panel_width_pixels).max(1.0);
@@ -106,26 +171,127 @@ fn main() -> Result<(), anyhow::Error> {
     let model_matrix = Mat4::IDENTITY;
     render_pipeline.update_model(&renderer.queue, model_matrix);
     
-    // Material params
-    let app_state = WgpuAppState::default();
+    // Material params (user startup defaults override the built-in ones, the last session both)
+    let mut app_state = WgpuAppState::default();
+    if let Some(config) = ViewerConfig::load_default() {
+        config.apply_to(&mut app_state);
+    }
+    if let Some(session) = &session {
+        session.apply_to(&mut app_state);
+    }
+    // Paths given on the command line take precedence over the restored session
+    for path in args.paths.iter().cloned() {
+        open_path(&mut app_state, path);
+    }
+    app_state.debug_log = log_buffer;
+    app_state.device_max_texture_size = renderer.device.limits().max_texture_dimension_2d;
+    app_state.max_texture_size = app_state.max_texture_size.min(app_state.device_max_texture_size);
+    app_state.supported_msaa_samples = renderer.supported_sample_counts.clone();
+    if !app_state.supported_msaa_samples.contains(&app_state.msaa_samples) {
+        app_state.msaa_samples = DEFAULT_MSAA_SAMPLES;
+    }
+    if app_state.msaa_samples != DEFAULT_MSAA_SAMPLES {
+        render_pipeline.rebuild(&renderer.device, renderer.config.format, app_state.msaa_samples);
+    }
+    app_state.supported_present_modes = state_wgpu::PresentMode::all()
+        .iter()
+        .copied()
+        .filter(|mode| renderer.supported_present_modes.contains(&mode.to_wgpu()))
+        .collect();
+    app_state.wireframe_supported = render_pipeline.wireframe_pipeline.is_some();
+    app_state.max_anisotropy = if renderer.anisotropic_filtering { 16 } else { 1 };
+    app_state.texture_filter.anisotropy = app_state.texture_filter.anisotropy.min(app_state.max_anisotropy);
+    app_state.mesh_bounds = Aabb::from_vertices(&mesh_data.vertices);
+    app_state.mesh_stats = MeshStats {
+        vertices: mesh_buffer.vertex_count,
+        triangles: mesh_buffer.triangle_count(),
+        mean_edge_length: mesh_wgpu::mean_edge_length(&mesh_data),
+    };
+    let instance_buffer = InstanceBuffer::new(
+        &renderer.device,
+        &instance::build_instances(&app_state.material_matrix),
+    );
     render_pipeline.update_material(
         &renderer.queue,
         &app_state.material_params,
         app_state.view_mode,
+        app_state.normal_debug_space,
         &app_state.loaded_textures,
+        &app_state.enabled_maps,
+    );
+    render_pipeline.update_tangent_frame_axis(&renderer.queue, app_state.tangent_frame_axis);
+    // Initialize the lights
+    render_pipeline.update_light(&renderer.queue, &app_state.light_params, &app_state.secondary_lights);
+    render_pipeline.update_environment(&renderer.queue, &app_state.environment);
+    render_pipeline.update_shading(&renderer.queue, &app_state.shading);
+    render_pipeline.update_overlay(&renderer.queue, &app_state.overlay);
+    render_pipeline.update_displacement(&renderer.queue, &app_state.gpu_tessellation);
+    
+    // Offscreen scene target at the viewport size scaled by the render scale
+    let composite_pipeline = CompositePipeline::new(&renderer.device, renderer.config.format);
+    let (target_width, target_height) = SceneTarget::scaled_size(
+        viewport_width,
+        renderer.size.height as f32,
+        app_state.render_scale,
+        app_state.device_max_texture_size,
+    );
+    let scene_target = SceneTarget::new(
+        &renderer.device,
+        &composite_pipeline,
+        renderer.config.format,
+        target_width,
+        target_height,
+        app_state.msaa_samples,
     );
-    // Initialize light direction
-    render_pipeline.update_light_direction(&renderer.queue, app_state.light_params.direction);
+    
+    // Debug lines (mesh bounding box)
+    let mut line_pipeline = LinePipeline::new(
+        &renderer.device,
+        &render_pipeline.camera_bind_group_layout,
+        renderer.config.format,
+        app_state.msaa_samples,
+    );
+    line_pipeline.set_lines(&renderer.device, &bounds_lines(app_state.mesh_bounds.as_ref()));
+    let light_gizmo = LinePipeline::new(
+        &renderer.device,
+        &render_pipeline.camera_bind_group_layout,
+        renderer.config.format,
+        app_state.msaa_samples,
+    );
+    let grid_pipeline = GridPipeline::new(
+        &renderer.device,
+        &render_pipeline.camera_bind_group_layout,
+        renderer.config.format,
+        app_state.msaa_samples,
+    );
+    let skybox_pipeline = SkyboxPipeline::new(&renderer.device, &environment, renderer.config.format, app_state.msaa_samples);
+    
+    let mut input_state = InputState::new();
+    input_state.set_scale_factor(window_ref.scale_factor());
     
     let mut render_state = RenderState {
         render_pipeline,
+        texture_set,
         texture_bind_group,
+        environment,
         mesh_buffer,
+        instance_buffer,
+        composite_pipeline,
+        scene_target,
+        line_pipeline,
+        light_gizmo,
+        grid_pipeline,
+        skybox_pipeline,
+        shader_watcher: args.watch_shader.then(|| ShaderWatcher::new(PBR_SHADER_PATH)),
+        readback: ReadbackManager::default(),
+        texture_worker: TextureLoadWorker::default(),
         orbit_camera,
         app_state,
         camera,
-        input_state: InputState::new(),
+        input_state,
         egui_state,
+        last_frame: Instant::now(),
+        delta_time: 0.0,
     };
     
     event_loop.run(move |event, elwt| {
@@ -133,6 +299,16 @@ fn main() -> Result<(), anyhow::Error> {
         
         match event {
             Event::WindowEvent { event, .. } => {
+                // Numpad view presets are kept from egui, which can't tell them from
+                // the number row keys that switch the view mode
+                if !render_state.egui_state.context.wants_keyboard_input() {
+                    let modifiers = render_state.egui_state.context.input(|i| i.modifiers);
+                    if let Some(preset) = numpad_view_preset(&event, modifiers.ctrl) {
+                        render_state.app_state.view_preset_request = Some((preset, modifiers.shift));
+                        return;
+                    }
+                }
+                
                 // Pass events to egui FIRST - this is critical for UI interaction
                 let egui_consumed = render_state.egui_state.handle_event(&window, &event);
                 
@@ -143,24 +319,73 @@ fn main() -> Result<(), anyhow::Error> {
                 
                 match event {
                     WindowEvent::CloseRequested => {
+                        let window_size = window.inner_size().to_logical::<f64>(window.scale_factor());
+                        let session = Session::from_state(
+                            &render_state.app_state,
+                            &render_state.orbit_camera,
+                            [window_size.width, window_size.height],
+                        );
+                        if let Err(e) = session.save() {
+                            log::warn!("Failed to save session: {}", e);
+                        }
                         elwt.exit();
                     }
                     WindowEvent::Resized(physical_size) => {
-                        renderer.resize(physical_size);
-                        // Update camera aspect ratio (accounting for UI panel)
-                        let pixels_per_point = window.scale_factor() as f32;
-                        let panel_width_pixels = render_state.app_state.ui_panel_width * pixels_per_point;
-                        let viewport_width = (physical_size.width as f32 - panel_width_pixels).max(1.0);
-                        render_state.camera.aspect = viewport_width / physical_size.height as f32;
-                        render_state.render_pipeline.update_camera(&renderer.queue, &render_state.camera);
+                        resize_viewport(&mut renderer, &mut render_state, physical_size, window.scale_factor());
+                    }
+                    // Moved to a monitor with another DPI: egui-winit picks up the new
+                    // pixels-per-point from this event; the mouse mapping and the panel
+                    // width in pixels are updated here
+                    WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                        render_state.input_state.set_scale_factor(scale_factor);
+                        resize_viewport(&mut renderer, &mut render_state, window.inner_size(), scale_factor);
+                    }
+                    // F frames the model (ignored while egui has keyboard focus)
+                    WindowEvent::KeyboardInput { event: key_event, .. }
+                        if !egui_consumed
+                            && key_event.state == ElementState::Pressed
+                            && !key_event.repeat
+                            && key_event.logical_key == Key::Character("f".into()) =>
+                    {
+                        render_state.app_state.frame_requested = true;
+                    }
+                    // F12 saves a timestamped screenshot next to the working directory
+                    WindowEvent::KeyboardInput { event: key_event, .. }
+                        if !egui_consumed
+                            && key_event.state == ElementState::Pressed
+                            && !key_event.repeat
+                            && key_event.logical_key == Key::Named(NamedKey::F12) =>
+                    {
+                        render_state.app_state.screenshot_request = Some(console::default_screenshot_path());
+                    }
+                    // F11 toggles borderless fullscreen
+                    WindowEvent::KeyboardInput { event: key_event, .. }
+                        if !egui_consumed
+                            && key_event.state == ElementState::Pressed
+                            && !key_event.repeat
+                            && key_event.logical_key == Key::Named(NamedKey::F11) =>
+                    {
+                        render_state.app_state.fullscreen_toggle_requested = true;
+                    }
+                    WindowEvent::HoveredFile(path) => {
+                        render_state.app_state.drag_hover_path = Some(path.to_string_lossy().to_string());
+                    }
+                    WindowEvent::HoveredFileCancelled => {
+                        render_state.app_state.drag_hover_path = None;
+                    }
+                    WindowEvent::DroppedFile(path) => {
+                        render_state.app_state.drag_hover_path = None;
+                        open_path(&mut render_state.app_state, path);
                     }
                     WindowEvent::RedrawRequested => {
                         // Handle input for camera control (only if not over UI)
                         let over_ui = render_state.egui_state.context.wants_pointer_input() || 
                                      render_state.egui_state.context.is_pointer_over_area();
                         if !over_ui {
-                            handle_camera_input(&mut render_state, &renderer.queue);
+                            handle_camera_input(&mut render_state, &renderer.queue, renderer.size);
                         }
+                        // Always clear the accumulated deltas so motion over the UI doesn't pile up
+                        render_state.input_state.reset_frame();
                         render_frame(&mut renderer, &mut render_state, &window);
                     }
                     _ => {}
@@ -176,8 +401,52 @@ fn main() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
-fn handle_camera_input(render_state: &mut RenderState, queue: &wgpu::Queue) {
-    let input = &mut render_state.input_state;
+/// Queue a dropped or command-line path for loading, based on what it is
+fn open_path(state: &mut WgpuAppState, path: std::path::PathBuf) {
+    if !path.exists() {
+        state.notifications.push(NotificationLevel::Error, format!("Not found: {}", path.display()));
+    } else if path.is_dir() || texture_loader::is_zip_archive(&path) {
+        // Folders and zip archives both hold texture sets
+        state.texture_folder = Some(path.to_string_lossy().to_string());
+        state.textures_need_reload = true;
+    } else if model_loader::is_model_file(&path) {
+        state.model_load_request = Some(path);
+    } else if environment::is_environment_file(&path) {
+        state.environment_load_request = Some(path);
+    } else {
+        state.notifications.push(
+            NotificationLevel::Warning,
+            format!("Unsupported file: {}", path.display()),
+        );
+    }
+}
+
+/// Blender-style numpad views: 1 front, 3 right, 7 top; with Ctrl the opposite side
+fn numpad_view_preset(event: &WindowEvent, opposite: bool) -> Option<ViewPreset> {
+    let WindowEvent::KeyboardInput { event: key_event, .. } = event else {
+        return None;
+    };
+    if key_event.state != ElementState::Pressed || key_event.repeat {
+        return None;
+    }
+    let (preset, opposite_preset) = match key_event.physical_key {
+        PhysicalKey::Code(KeyCode::Numpad1) => (ViewPreset::Front, ViewPreset::Back),
+        PhysicalKey::Code(KeyCode::Numpad3) => (ViewPreset::Right, ViewPreset::Left),
+        PhysicalKey::Code(KeyCode::Numpad7) => (ViewPreset::Top, ViewPreset::Bottom),
+        _ => return None,
+    };
+    Some(if opposite { opposite_preset } else { preset })
+}
+
+/// Apply the mouse input accumulated since the last frame.
+/// Deltas are in pixels and wheel lines summed over all events, so the
+/// response doesn't depend on the frame rate and needs no delta-time scaling.
+fn handle_camera_input(
+    render_state: &mut RenderState,
+    queue: &wgpu::Queue,
+    window_size: winit::dpi::PhysicalSize<u32>,
+) {
+    let input = &render_state.input_state;
     
     // Track if model was rotated this frame
     let mut model_rotated = false;
@@ -218,14 +487,18 @@ fn handle_camera_input(render_state: &mut RenderState, queue: &wgpu::Queue) {
         // Model rotation is NOT updated - it stays fixed
     }
     
-    // Scroll zoom
+    // Scroll zoom towards whatever is under the cursor
     if input.scroll_delta.abs() > 0.0 {
         let zoom_speed = 0.1;
-        render_state.orbit_camera.zoom(-input.scroll_delta * zoom_speed);
+        let delta = -input.scroll_delta * zoom_speed;
+        match zoom_focus_point(render_state, window_size) {
+            Some(point) => render_state.orbit_camera.zoom_towards(delta, point),
+            None => render_state.orbit_camera.zoom(delta),
+        }
     }
     
     // Update camera
-    render_state.camera = render_state.orbit_camera.to_camera_with_aspect(render_state.camera.aspect);
+    render_state.camera = orbit_view_camera(render_state);
     render_state.render_pipeline.update_camera(queue, &render_state.camera);
     
     // Update model matrix from rotation (only if model was rotated this frame)
@@ -233,12 +506,122 @@ fn handle_camera_input(render_state: &mut RenderState, queue: &wgpu::Queue) {
         let model_matrix = Mat4::from_quat(render_state.app_state.model_rotation);
         render_state.render_pipeline.update_model(queue, model_matrix);
     }
+}
+
+/// World point under the cursor to zoom towards: the front of the scene's bounding
+/// sphere, or the point at the orbit target's depth when the cursor ray misses it.
+/// `None` if the cursor is outside the 3D viewport.
+fn zoom_focus_point(render_state: &RenderState, window_size: winit::dpi::PhysicalSize<u32>) -> Option<glam::Vec3> {
+    let input = &render_state.input_state;
+    let camera = &render_state.camera;
+    let left = render_state.app_state.ui_panel_width * input.scale_factor as f32;
+    let width = window_size.width as f32 - left;
+    let height = window_size.height as f32;
+    if width < 1.0 || height < 1.0 {
+        return None;
+    }
+    // Center of the framebuffer texel under the cursor
+    let texel = input.cursor_texel(glam::UVec2::new(window_size.width, window_size.height))?;
+    let cursor = texel.as_vec2() + 0.5;
+    let ndc = glam::Vec2::new(
+        (cursor.x - left) / width * 2.0 - 1.0,
+        1.0 - cursor.y / height * 2.0,
+    );
+    if ndc.abs().max_element() > 1.0 {
+        return None;
+    }
+    let (origin, direction) = camera.screen_ray(ndc);
+    
+    let (center, radius) = render_state.app_state.scene_bounding_sphere();
+    let to_origin = origin - center;
+    let b = to_origin.dot(direction);
+    let discriminant = b * b - (to_origin.length_squared() - radius * radius);
+    if discriminant >= 0.0 {
+        let t = -b - discriminant.sqrt();
+        if t > 0.0 {
+            return Some(origin + direction * t);
+        }
+    }
     
-    // Reset frame input
-    input.reset_frame();
+    let forward = (camera.target - camera.position).normalize_or_zero();
+    let facing = direction.dot(forward);
+    if facing < 1e-4 {
+        return None;
+    }
+    let t = (camera.target - origin).dot(forward) / facing;
+    Some(origin + direction * t)
+}
+
+/// Camera for the orbit controller, keeping the current aspect ratio and applying
+/// the FOV and clip planes from the settings
+fn orbit_view_camera(render_state: &RenderState) -> Camera {
+    let mut camera = render_state.orbit_camera.to_camera_with_aspect(render_state.camera.aspect);
+    camera.fov = render_state.app_state.fov_degrees.to_radians();
+    (camera.near, camera.far) = render_state.app_state.clip_distances(camera.position);
+    camera
+}
+
+/// Resize the surface and update the camera aspect ratio (accounting for the UI panel)
+fn resize_viewport(
+    renderer: &mut Renderer,
+    render_state: &mut RenderState,
+    physical_size: winit::dpi::PhysicalSize<u32>,
+    scale_factor: f64,
+) {
+    renderer.resize(physical_size);
+    if physical_size.width == 0 || physical_size.height == 0 {
+        return;
+    }
+    let panel_width_pixels = render_state.app_state.ui_panel_width * scale_factor as f32;
+    let viewport_width = (physical_size.width as f32 - panel_width_pixels).max(1.0);
+    render_state.camera.aspect = viewport_width / physical_size.height as f32;
+    render_state.render_pipeline.update_camera(&renderer.queue, &render_state.camera);
 }
 
 fn render_frame(renderer: &mut Renderer, render_state: &mut RenderState, window: &Window) {
+    // Frame delta time (clamped so a stall doesn't cause a large jump)
+    let now = Instant::now();
+    let frame_time = (now - render_state.last_frame).as_secs_f32();
+    render_state.app_state.frame_stats.push(frame_time);
+    render_state.delta_time = frame_time.min(0.1);
+    render_state.last_frame = now;
+    
+    // Development shader hot-reload (--watch-shader)
+    if let Some(source) = render_state.shader_watcher.as_mut().and_then(ShaderWatcher::poll) {
+        reload_pbr_shader(renderer, render_state, &source);
+    }
+    
+    // Animate the light orbit; secondary lights turn along so a studio setup keeps its shape
+    if render_state.app_state.light_orbit.playing {
+        let rotation = render_state.app_state.light_orbit.step(render_state.delta_time);
+        let app_state = &mut render_state.app_state;
+        app_state.light_params.direction = (rotation * app_state.light_params.direction).normalize();
+        for light in &mut app_state.secondary_lights {
+            light.direction = (rotation * light.direction).normalize();
+        }
+        render_state.render_pipeline.update_light(&renderer.queue, &app_state.light_params, &app_state.secondary_lights);
+    }
+    
+    // Spin the model on the turntable; a manual drag takes over while the button is held
+    if render_state.app_state.turntable.auto_rotate && !render_state.input_state.left_mouse_pressed {
+        let rotation = render_state.app_state.turntable.step(render_state.delta_time);
+        render_state.app_state.model_rotation = rotation * render_state.app_state.model_rotation;
+    }
+    
+    // Enter or leave fullscreen; the Resized event that follows reconfigures the surface
+    if std::mem::take(&mut render_state.app_state.fullscreen_toggle_requested) {
+        let fullscreen = window.fullscreen().is_none().then_some(Fullscreen::Borderless(None));
+        window.set_fullscreen(fullscreen);
+    }
+    // The window manager may also leave fullscreen on its own
+    render_state.app_state.fullscreen = window.fullscreen().is_some();
+    
+    // Apply a VSync change before acquiring the next frame
+    let present_mode = render_state.app_state.present_mode.to_wgpu();
+    if renderer.config.present_mode != present_mode && renderer.set_present_mode(present_mode) != present_mode {
+        render_state.app_state.present_mode = state_wgpu::PresentMode::Fifo;
+    }
+    
     match renderer.get_current_texture() {
         Ok(frame) => {
             let view = frame.texture.create_view(&wgpu::TextureViewDescriptor::default());
@@ -249,133 +632,298 @@ fn render_frame(renderer: &mut Renderer, render_state: &mut RenderState, window:
             // Build UI and get current panel width
             let panel_width = build_ui(&render_state.egui_state.context, &mut render_state.app_state);
             
-            // Update camera aspect ratio if panel width changed
-            let pixels_per_point = window.scale_factor() as f32;
+            // Update camera aspect ratio if panel width changed, and the FOV and clip planes if edited
+            let pixels_per_point = render_state.egui_state.context.pixels_per_point();
             let panel_width_pixels = panel_width * pixels_per_point;
             let viewport_width = (renderer.size.width as f32 - panel_width_pixels).max(1.0);
             let new_aspect = viewport_width / renderer.size.height as f32;
-            if (render_state.camera.aspect - new_aspect).abs() > 0.001 {
+            let new_fov = render_state.app_state.fov_degrees.to_radians();
+            let (new_near, new_far) = render_state.app_state.clip_distances(render_state.camera.position);
+            if (render_state.camera.aspect - new_aspect).abs() > 0.001
+                || render_state.camera.fov != new_fov
+                || render_state.camera.near != new_near
+                || render_state.camera.far != new_far
+            {
                 render_state.camera.aspect = new_aspect;
+                render_state.camera.fov = new_fov;
+                render_state.camera.near = new_near;
+                render_state.camera.far = new_far;
+                render_state.render_pipeline.update_camera(&renderer.queue, &render_state.camera);
+            }
+            
+            // Recreate the offscreen scene target if the viewport size or render scale changed
+            let viewport_height = renderer.size.height as f32;
+            let (target_width, target_height) = SceneTarget::scaled_size(
+                viewport_width,
+                viewport_height,
+                render_state.app_state.render_scale,
+                render_state.app_state.device_max_texture_size,
+            );
+            let sample_count = render_state.app_state.msaa_samples;
+            if render_state.scene_target.sample_count != sample_count {
+                rebuild_scene_pipelines(renderer, render_state, sample_count);
+            }
+            if render_state.scene_target.width != target_width
+                || render_state.scene_target.height != target_height
+                || render_state.scene_target.sample_count != sample_count
+            {
+                render_state.scene_target = SceneTarget::new(
+                    &renderer.device,
+                    &render_state.composite_pipeline,
+                    renderer.config.format,
+                    target_width,
+                    target_height,
+                    sample_count,
+                );
+            }
+            
+            // Reset the camera if requested from the UI
+            if render_state.app_state.camera_reset_requested {
+                render_state.orbit_camera = OrbitCamera::new(glam::Vec3::ZERO, 3.0);
+                render_state.camera = orbit_view_camera(render_state);
+                render_state.render_pipeline.update_camera(&renderer.queue, &render_state.camera);
+                render_state.app_state.camera_reset_requested = false;
+            }
+            
+            // Turn to a canonical view if requested, then advance its animation
+            if let Some((preset, instant)) = render_state.app_state.view_preset_request.take() {
+                if instant {
+                    render_state.orbit_camera.set_view(preset);
+                } else {
+                    render_state.orbit_camera.animate_to_view(preset);
+                }
+                render_state.camera = orbit_view_camera(render_state);
                 render_state.render_pipeline.update_camera(&renderer.queue, &render_state.camera);
             }
+            // Ease the displayed camera towards the requested placement
+            let transition_moved = render_state.orbit_camera.update_transition(render_state.delta_time);
+            let smoothing_moved = render_state.orbit_camera.update_smoothing(
+                render_state.delta_time,
+                render_state.app_state.camera_smoothing,
+            );
+            if transition_moved || smoothing_moved {
+                render_state.camera = orbit_view_camera(render_state);
+                render_state.render_pipeline.update_camera(&renderer.queue, &render_state.camera);
+            }
+            
+            // Frame the mesh bounds (rotated with the model) if requested
+            if render_state.app_state.frame_requested {
+                if let Some(bounds) = render_state.app_state.mesh_bounds {
+                    let center = render_state.app_state.model_rotation * bounds.center();
+                    let radius = bounds.size().length() * 0.5;
+                    render_state.orbit_camera.frame_bounds(
+                        center,
+                        radius,
+                        render_state.camera.fov,
+                        render_state.camera.aspect,
+                    );
+                    render_state.camera = orbit_view_camera(render_state);
+                    render_state.render_pipeline.update_camera(&renderer.queue, &render_state.camera);
+                }
+                render_state.app_state.frame_requested = false;
+            }
             
-            // Handle texture loading if needed
+            // A slot assigned while a load is running would be replaced when the load lands
+            if render_state.app_state.texture_slot_reload.is_some() && render_state.texture_worker.is_loading() {
+                render_state.app_state.textures_need_reload = true;
+            }
+            
+            // Detect and decode the texture set on a worker thread
             if render_state.app_state.textures_need_reload {
-                use crate::texture_loader::{TextureLoader, TexturePaths, detect_textures_in_directory};
+                render_state.texture_worker.start(TextureLoadJob {
+                    folder: render_state.app_state.texture_folder.clone(),
+                    search_subfolders: render_state.app_state.search_subfolders,
+                    handles: render_state.app_state.texture_handles.clone(),
+                    max_size: render_state.app_state.max_texture_size,
+                });
+                render_state.app_state.textures_need_reload = false;
+                render_state.app_state.texture_slot_reload = None;
+            }
+            
+            // Upload the texture set once the worker has decoded it
+            if let Some(result) = render_state.texture_worker.poll() {
+                for error in result.errors {
+                    render_state.app_state.notifications.push(NotificationLevel::Error, error);
+                }
+                render_state.app_state.texture_conflicts = result.detection.conflicts;
                 
-                // Build texture paths from folder detection and individual selections
-                let mut texture_paths = TexturePaths::default();
+                // List the folder's images so they can be dragged onto individual slots
+                render_state.app_state.texture_folder_files = result.folder_files
+                    .iter()
+                    .map(|path| path.to_string_lossy().to_string())
+                    .collect();
                 
-                // First, detect textures from folder if provided
-                if let Some(ref folder_path) = render_state.app_state.texture_folder {
-                    if let Ok(detected) = detect_textures_in_directory(std::path::Path::new(folder_path)) {
-                        // Use detected paths, but individual selections override folder detection
-                        texture_paths = detected;
-                    }
-                }
+                let options = TextureLoadOptions {
+                    max_size: render_state.app_state.max_texture_size,
+                    filter: render_state.app_state.texture_filter,
+                    ..Default::default()
+                };
+                let uploaded = result.textures.upload(
+                    &renderer.device,
+                    &renderer.queue,
+                    &options,
+                    &render_state.app_state.slot_samplers,
+                );
                 
-                // Override with individually selected textures
-                if let Some(ref path) = render_state.app_state.texture_handles.base_color {
-                    texture_paths.base_color = Some(std::path::PathBuf::from(path));
-                }
-                if let Some(ref path) = render_state.app_state.texture_handles.normal {
-                    texture_paths.normal = Some(std::path::PathBuf::from(path));
-                }
-                if let Some(ref path) = render_state.app_state.texture_handles.metallic {
-                    texture_paths.metallic = Some(std::path::PathBuf::from(path));
-                }
-                if let Some(ref path) = render_state.app_state.texture_handles.roughness {
-                    texture_paths.roughness = Some(std::path::PathBuf::from(path));
-                }
-                if let Some(ref path) = render_state.app_state.texture_handles.orm {
-                    texture_paths.orm = Some(std::path::PathBuf::from(path));
+                // Update texture bind group
+                let texture_bind_group_layout = TextureSet::bind_group_layout(&renderer.device);
+                render_state.texture_bind_group = uploaded.texture_set.create_bind_group(
+                    &renderer.device,
+                    &texture_bind_group_layout,
+                    &render_state.environment,
+                );
+                render_state.texture_set = uploaded.texture_set;
+                
+                // Update loaded texture status based on what we actually loaded
+                render_state.app_state.texture_info = render_state.texture_set.info;
+                render_state.app_state.loaded_textures = LoadedTextures::from_paths(&uploaded.paths);
+                
+                // List the detected files in the rows that weren't assigned manually
+                render_state.app_state.texture_handles.set_detected(&result.detection.paths);
+                
+                if let Some(folder) = render_state.app_state.texture_folder.clone() {
+                    render_state.app_state.remember_folder(&folder);
                 }
-                if let Some(ref path) = render_state.app_state.texture_handles.ao {
-                    texture_paths.ao = Some(std::path::PathBuf::from(path));
+                
+                // Trigger material update to refresh view mode
+                render_state.app_state.material_changed = true;
+                
+                let loaded = render_state.texture_set.info.loaded_count();
+                if uploaded.failures.is_empty() {
+                    let message = if let Some(ref archive_name) = result.archive_name {
+                        format!("Textures loaded from archive {}", archive_name)
+                    } else {
+                        "Textures loaded".to_string()
+                    };
+                    render_state.app_state.notifications.push(NotificationLevel::Success, message);
+                } else {
+                    render_state.app_state.notifications.push(
+                        NotificationLevel::Error,
+                        format!("{} of {} textures failed to load (see the Textures tab)", uploaded.failures.len(), loaded + uploaded.failures.len()),
+                    );
                 }
-                if let Some(ref path) = render_state.app_state.texture_handles.emissive {
-                    texture_paths.emissive = Some(std::path::PathBuf::from(path));
+                render_state.app_state.last_texture_load = Some(TextureLoadReport { loaded, failures: uploaded.failures });
+                // The new set was created with the current filtering
+                render_state.app_state.samplers_changed = false;
+            }
+            render_state.app_state.texture_load_progress = render_state.texture_worker.progress();
+            
+            // Rebuild samplers if only the filtering changed
+            if render_state.app_state.samplers_changed {
+                render_state.texture_set.rebuild_samplers(
+                    &renderer.device,
+                    &render_state.app_state.texture_filter,
+                    &render_state.app_state.slot_samplers,
+                );
+                let texture_bind_group_layout = TextureSet::bind_group_layout(&renderer.device);
+                render_state.texture_bind_group = render_state.texture_set.create_bind_group(
+                    &renderer.device,
+                    &texture_bind_group_layout,
+                    &render_state.environment,
+                );
+                render_state.app_state.samplers_changed = false;
+            }
+            
+            // Reload a single explicitly assigned texture, keeping the others
+            if let Some(slot) = render_state.app_state.texture_slot_reload.take() {
+                reload_texture_slot(renderer, render_state, slot);
+            }
+            
+            // Handle mesh switching if needed
+            if render_state.app_state.mesh_changed {
+                let mesh_data = match render_state.app_state.current_mesh {
+                    mesh_wgpu::MeshType::Sphere => Some(create_sphere(render_state.app_state.tessellation_level)),
+                    mesh_wgpu::MeshType::Icosphere => Some(create_icosphere(
+                        icosphere_subdivisions(render_state.app_state.tessellation_level),
+                    )),
+                    mesh_wgpu::MeshType::Cube => Some(create_cube()),
+                    mesh_wgpu::MeshType::Plane => Some(create_plane(render_state.app_state.tessellation_level)),
+                    mesh_wgpu::MeshType::RoundedRect => Some(create_rounded_rect(
+                        render_state.app_state.tessellation_level,
+                        render_state.app_state.corner_radius,
+                    )),
+                    // A loaded model is only replaced by loading another one
+                    mesh_wgpu::MeshType::Custom => None,
+                };
+                if let Some(mesh_data) = mesh_data {
+                    set_mesh(renderer, render_state, &mesh_data);
                 }
-                if let Some(ref path) = render_state.app_state.texture_handles.height {
-                    texture_paths.height = Some(std::path::PathBuf::from(path));
+                render_state.app_state.mesh_changed = false;
+            }
+            
+            // Load a custom model file
+            if let Some(path) = render_state.app_state.model_load_request.take() {
+                match model_loader::load_model(&path) {
+                    Ok(mut model) => {
+                        if render_state.app_state.regenerate_model_tangents {
+                            mesh_wgpu::compute_tangents(&mut model.mesh);
+                        }
+                        let mesh_data = &model.mesh;
+                        set_mesh(renderer, render_state, mesh_data);
+                        apply_model_textures(renderer, render_state, &model.textures);
+                        let name = path.file_name()
+                            .map(|n| n.to_string_lossy().to_string())
+                            .unwrap_or_else(|| path.display().to_string());
+                        render_state.app_state.notifications.push(
+                            NotificationLevel::Success,
+                            format!(
+                                "Loaded model {} ({} vertices, {} triangles)",
+                                name,
+                                mesh_data.vertices.len(),
+                                mesh_data.indices.len() / 3,
+                            ),
+                        );
+                        render_state.app_state.current_mesh = mesh_wgpu::MeshType::Custom;
+                        let model_path = path.to_string_lossy().to_string();
+                        render_state.app_state.remember_model(&model_path);
+                        render_state.app_state.model_path = Some(model_path);
+                        render_state.app_state.frame_requested = true;
+                    }
+                    Err(e) => {
+                        render_state.app_state.notifications.push(
+                            NotificationLevel::Error,
+                            format!("Failed to load model: {}", e),
+                        );
+                    }
                 }
-                
-                // Load textures from paths
-                match TextureLoader::load_from_paths(
-                    &renderer.device,
-                    &renderer.queue,
-                    &texture_paths,
-                ) {
-                    Ok(new_texture_set) => {
-                        // Update texture bind group
+            }
+            
+            // Load an HDR environment map for image-based lighting
+            if let Some(path) = render_state.app_state.environment_load_request.take() {
+                match EnvironmentMap::load(&renderer.device, &renderer.queue, &path) {
+                    Ok(environment) => {
+                        render_state.skybox_pipeline.set_environment(&renderer.device, &environment);
+                        render_state.environment = environment;
                         let texture_bind_group_layout = TextureSet::bind_group_layout(&renderer.device);
-                        render_state.texture_bind_group = new_texture_set.create_bind_group(
+                        render_state.texture_bind_group = render_state.texture_set.create_bind_group(
                             &renderer.device,
                             &texture_bind_group_layout,
+                            &render_state.environment,
                         );
-                        
-                        // Update loaded texture status based on what we actually loaded
-                        render_state.app_state.loaded_textures.reset();
-                        render_state.app_state.loaded_textures.base_color = texture_paths.base_color.is_some();
-                        render_state.app_state.loaded_textures.normal = texture_paths.normal.is_some();
-                        render_state.app_state.loaded_textures.metallic = texture_paths.metallic.is_some();
-                        render_state.app_state.loaded_textures.roughness = texture_paths.roughness.is_some();
-                        render_state.app_state.loaded_textures.orm = texture_paths.orm.is_some();
-                        render_state.app_state.loaded_textures.ao = texture_paths.ao.is_some();
-                        render_state.app_state.loaded_textures.emissive = texture_paths.emissive.is_some();
-                        render_state.app_state.loaded_textures.height = texture_paths.height.is_some();
-                        
-                        // Also update texture_handles with detected paths from folder
-                        if let Some(ref folder_path) = render_state.app_state.texture_folder {
-                            if let Ok(detected) = detect_textures_in_directory(std::path::Path::new(folder_path)) {
-                                if detected.base_color.is_some() && render_state.app_state.texture_handles.base_color.is_none() {
-                                    render_state.app_state.texture_handles.base_color = detected.base_color.as_ref().and_then(|p| p.to_str().map(|s| s.to_string()));
-                                }
-                                if detected.normal.is_some() && render_state.app_state.texture_handles.normal.is_none() {
-                                    render_state.app_state.texture_handles.normal = detected.normal.as_ref().and_then(|p| p.to_str().map(|s| s.to_string()));
-                                }
-                                if detected.metallic.is_some() && render_state.app_state.texture_handles.metallic.is_none() {
-                                    render_state.app_state.texture_handles.metallic = detected.metallic.as_ref().and_then(|p| p.to_str().map(|s| s.to_string()));
-                                }
-                                if detected.roughness.is_some() && render_state.app_state.texture_handles.roughness.is_none() {
-                                    render_state.app_state.texture_handles.roughness = detected.roughness.as_ref().and_then(|p| p.to_str().map(|s| s.to_string()));
-                                }
-                                if detected.orm.is_some() && render_state.app_state.texture_handles.orm.is_none() {
-                                    render_state.app_state.texture_handles.orm = detected.orm.as_ref().and_then(|p| p.to_str().map(|s| s.to_string()));
-                                }
-                                if detected.ao.is_some() && render_state.app_state.texture_handles.ao.is_none() {
-                                    render_state.app_state.texture_handles.ao = detected.ao.as_ref().and_then(|p| p.to_str().map(|s| s.to_string()));
-                                }
-                                if detected.emissive.is_some() && render_state.app_state.texture_handles.emissive.is_none() {
-                                    render_state.app_state.texture_handles.emissive = detected.emissive.as_ref().and_then(|p| p.to_str().map(|s| s.to_string()));
-                                }
-                                if detected.height.is_some() && render_state.app_state.texture_handles.height.is_none() {
-                                    render_state.app_state.texture_handles.height = detected.height.as_ref().and_then(|p| p.to_str().map(|s| s.to_string()));
-                                }
-                            }
-                        }
-                        
-                        // Trigger material update to refresh view mode
+                        let name = path.file_name()
+                            .map(|n| n.to_string_lossy().to_string())
+                            .unwrap_or_else(|| path.display().to_string());
+                        render_state.app_state.notifications.push(
+                            NotificationLevel::Success,
+                            format!("Loaded environment {}", name),
+                        );
+                        render_state.app_state.environment.path = Some(path.to_string_lossy().to_string());
                         render_state.app_state.material_changed = true;
-                        
-                        log::info!("Textures loaded successfully");
                     }
                     Err(e) => {
-                        log::error!("Failed to load textures: {}", e);
+                        render_state.app_state.notifications.push(
+                            NotificationLevel::Error,
+                            format!("Failed to load environment: {}", e),
+                        );
                     }
                 }
-                render_state.app_state.textures_need_reload = false;
             }
             
-            // Handle mesh switching if needed
-            if render_state.app_state.mesh_changed {
-                let mesh_data = match render_state.app_state.current_mesh {
-                    mesh_wgpu::MeshType::Sphere => create_sphere(render_state.app_state.tessellation_level),
-                    mesh_wgpu::MeshType::Cube => create_cube(),
-                    _ => create_sphere(32), // Fallback to sphere
-                };
-                render_state.mesh_buffer = MeshBuffer::new(&renderer.device, &mesh_data);
-                render_state.app_state.mesh_changed = false;
+            // Rebuild per-instance data if the material matrix changed
+            if render_state.app_state.instances_changed {
+                let instances = instance::build_instances(&render_state.app_state.material_matrix);
+                render_state.instance_buffer = InstanceBuffer::new(&renderer.device, &instances);
+                render_state.app_state.instances_changed = false;
             }
             
             // Update material if changed
@@ -384,12 +932,32 @@ fn render_frame(renderer: &mut Renderer, render_state: &mut RenderState, window:
                     &renderer.queue,
                     &render_state.app_state.material_params,
                     render_state.app_state.view_mode,
+                    render_state.app_state.normal_debug_space,
                     &render_state.app_state.loaded_textures,
+                    &render_state.app_state.enabled_maps,
+                );
+                render_state.render_pipeline.update_tangent_frame_axis(&renderer.queue, render_state.app_state.tangent_frame_axis);
+                // Also update the lights when material changes (in case they were changed via UI sliders)
+                render_state.render_pipeline.update_light(
+                    &renderer.queue,
+                    &render_state.app_state.light_params,
+                    &render_state.app_state.secondary_lights,
+                );
+                render_state.render_pipeline.update_environment(
+                    &renderer.queue,
+                    &render_state.app_state.environment,
                 );
-                // Also update light direction when material changes (in case it was changed via UI sliders)
-                render_state.render_pipeline.update_light_direction(
+                render_state.render_pipeline.update_shading(
                     &renderer.queue,
-                    render_state.app_state.light_params.direction,
+                    &render_state.app_state.shading,
+                );
+                render_state.render_pipeline.update_overlay(
+                    &renderer.queue,
+                    &render_state.app_state.overlay,
+                );
+                render_state.render_pipeline.update_displacement(
+                    &renderer.queue,
+                    &render_state.app_state.gpu_tessellation,
                 );
                 render_state.app_state.material_changed = false;
             }
@@ -399,12 +967,56 @@ fn render_frame(renderer: &mut Renderer, render_state: &mut RenderState, window:
             let model_matrix = Mat4::from_quat(render_state.app_state.model_rotation);
             render_state.render_pipeline.update_model(&renderer.queue, model_matrix);
             
+            // Fit the light's shadow frustum around the rotated, displaced mesh and every matrix instance
+            {
+                let app_state = &render_state.app_state;
+                let (center, radius) = app_state.scene_bounding_sphere();
+                let light_view_proj = shadow::light_view_proj(app_state.light_params.direction, center, radius);
+                render_state.render_pipeline.shadow_map.update(&renderer.queue, light_view_proj, &app_state.shadows);
+            }
+            
+            // Sun gizmo along the light direction, outside the scene. The line shader applies
+            // the model matrix, so the world-space placement is rotated back into model space.
+            if render_state.app_state.show_light_gizmo {
+                const LIGHT_GIZMO_COLOR: [f32; 4] = [1.0, 0.85, 0.2, 1.0];
+                let app_state = &render_state.app_state;
+                let (center, radius) = app_state.scene_bounding_sphere();
+                let to_model = app_state.model_rotation.inverse();
+                let lines = line_pipeline::light_gizmo_lines(
+                    to_model * center,
+                    radius,
+                    to_model * app_state.light_params.direction,
+                    LIGHT_GIZMO_COLOR,
+                );
+                render_state.light_gizmo.set_lines(&renderer.device, &lines);
+            }
+            
+            if render_state.app_state.grid.enabled {
+                render_state.grid_pipeline.update(
+                    &renderer.queue,
+                    &render_state.app_state.grid,
+                    render_state.app_state.ground_height(),
+                );
+            }
+            
+            // The skybox replaces the clear color once an environment is loaded
+            let draw_skybox = render_state.app_state.environment.show_background
+                && render_state.app_state.environment.path.is_some();
+            if draw_skybox {
+                render_state.skybox_pipeline.update(
+                    &renderer.queue,
+                    &render_state.camera,
+                    &render_state.app_state.environment,
+                    &render_state.app_state.shading,
+                );
+            }
+            
             // End egui frame and get output
             let egui_output = render_state.egui_state.end_frame(window);
             let textures_delta = &egui_output.textures_delta;
             
-            // Screen descriptor for egui rendering
-            let pixels_per_point = window.scale_factor() as f32;
+            // Screen descriptor for egui rendering, at the scale the UI was laid out with
+            let pixels_per_point = egui_output.pixels_per_point;
             let screen_descriptor = egui_wgpu::ScreenDescriptor {
                 size_in_pixels: [renderer.size.width, renderer.size.height],
                 pixels_per_point,
@@ -417,6 +1029,13 @@ fn render_frame(renderer: &mut Renderer, render_state: &mut RenderState, window:
                 label: Some("Render Encoder"),
             });
             
+            let background_color = wgpu::Color {
+                r: render_state.app_state.background_color[0] as f64,
+                g: render_state.app_state.background_color[1] as f64,
+                b: render_state.app_state.background_color[2] as f64,
+                a: 1.0,
+            };
+            
             if !textures_delta.is_empty() {
                 render_state.egui_state.update_texture(
                     &renderer.device,
@@ -425,27 +1044,87 @@ fn render_frame(renderer: &mut Renderer, render_state: &mut RenderState, window:
                 );
             }
             
-            // Render 3D scene
+            // The filled surface is skipped in the wireframe-only mode
+            let wireframe = render_state.app_state.overlay.wireframe;
+            let wireframe_pipeline = render_state.render_pipeline.wireframe_pipeline.as_ref()
+                .filter(|_| wireframe != WireframeMode::Off);
+            let draw_surface = wireframe_pipeline.is_none() || wireframe == WireframeMode::Overlay;
+            let depth_prepass = render_state.app_state.depth_prepass && draw_surface;
+            
+            // Shadow map from the directional light
+            if render_state.app_state.shadows.enabled {
+                let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("Shadow Pass"),
+                    color_attachments: &[],
+                    depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                        view: &render_state.render_pipeline.shadow_map.view,
+                        depth_ops: Some(wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(1.0),
+                            store: wgpu::StoreOp::Store,
+                        }),
+                        stencil_ops: None,
+                    }),
+                    occlusion_query_set: None,
+                    timestamp_writes: None,
+                });
+                render_pass.set_pipeline(&render_state.render_pipeline.shadow_pipeline);
+                render_pass.set_bind_group(0, &render_state.render_pipeline.camera_bind_group, &[]);
+                render_pass.set_bind_group(1, &render_state.texture_bind_group, &[]);
+                render_pass.set_bind_group(2, &render_state.render_pipeline.material_bind_group, &[]);
+                render_pass.set_bind_group(3, &render_state.render_pipeline.shadow_map.pass_bind_group, &[]);
+                render_pass.set_vertex_buffer(0, render_state.mesh_buffer.vertex_buffer.slice(..));
+                render_pass.set_vertex_buffer(1, render_state.instance_buffer.buffer.slice(..));
+                render_pass.set_index_buffer(render_state.mesh_buffer.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                render_pass.draw_indexed(0..render_state.mesh_buffer.index_count, 0, 0..render_state.instance_buffer.count);
+            }
+            
+            // Optional depth pre-pass: lay down the mesh depth first so the expensive
+            // fragment shader only runs for the visible surface
+            if depth_prepass {
+                let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("Depth Pre-pass"),
+                    color_attachments: &[],
+                    depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                        view: &render_state.scene_target.depth_view,
+                        depth_ops: Some(wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(1.0),
+                            store: wgpu::StoreOp::Store,
+                        }),
+                        stencil_ops: None,
+                    }),
+                    occlusion_query_set: None,
+                    timestamp_writes: None,
+                });
+                render_pass.set_pipeline(&render_state.render_pipeline.depth_prepass_pipeline);
+                render_pass.set_bind_group(0, &render_state.render_pipeline.camera_bind_group, &[]);
+                render_pass.set_bind_group(1, &render_state.texture_bind_group, &[]);
+                render_pass.set_bind_group(2, &render_state.render_pipeline.material_bind_group, &[]);
+                render_pass.set_bind_group(3, &render_state.render_pipeline.shadow_map.bind_group, &[]);
+                render_pass.set_vertex_buffer(0, render_state.mesh_buffer.vertex_buffer.slice(..));
+                render_pass.set_vertex_buffer(1, render_state.instance_buffer.buffer.slice(..));
+                render_pass.set_vertex_buffer(2, render_state.mesh_buffer.edge_length_buffer.slice(..));
+                render_pass.set_index_buffer(render_state.mesh_buffer.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                render_pass.draw_indexed(0..render_state.mesh_buffer.index_count, 0, 0..render_state.instance_buffer.count);
+            }
+            
+            // Render 3D scene into the offscreen target
             {
+                // With MSAA the multisampled target resolves into the scene color texture
+                let (color_view, resolve_target) = render_state.scene_target.color_attachment();
                 let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                    label: Some("Render Pass"),
+                    label: Some("Scene Render Pass"),
                     color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                        view: &view,
-                        resolve_target: None,
+                        view: color_view,
+                        resolve_target,
                         ops: wgpu::Operations {
-                            load: wgpu::LoadOp::Clear(wgpu::Color {
-                                r: 0.1,
-                                g: 0.1,
-                                b: 0.1,
-                                a: 1.0,
-                            }),
+                            load: wgpu::LoadOp::Clear(background_color),
                             store: wgpu::StoreOp::Store,
                         },
                     })],
                     depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
-                        view: &renderer.depth_texture_view,
+                        view: &render_state.scene_target.depth_view,
                         depth_ops: Some(wgpu::Operations {
-                            load: wgpu::LoadOp::Clear(1.0),
+                            load: if depth_prepass { wgpu::LoadOp::Load } else { wgpu::LoadOp::Clear(1.0) },
                             store: wgpu::StoreOp::Store,
                         }),
                         stencil_ops: None,
@@ -454,26 +1133,69 @@ fn render_frame(renderer: &mut Renderer, render_state: &mut RenderState, window:
                     timestamp_writes: None,
                 });
                 
-                // Set viewport to exclude UI panel area (render 3D to the right of the panel)
-                // Use the dynamic panel width from the UI
-                let viewport_x = panel_width_pixels;
-                let viewport_height = renderer.size.height as f32;
-                render_pass.set_viewport(viewport_x, 0.0, viewport_width, viewport_height, 0.0, 1.0);
-                
-                // Set render pipeline
-                render_pass.set_pipeline(&render_state.render_pipeline.pipeline);
+                // Environment background first; it doesn't write depth
+                if draw_skybox {
+                    render_state.skybox_pipeline.draw(&mut render_pass);
+                }
                 
                 // Set bind groups
                 render_pass.set_bind_group(0, &render_state.render_pipeline.camera_bind_group, &[]);
                 render_pass.set_bind_group(1, &render_state.texture_bind_group, &[]);
                 render_pass.set_bind_group(2, &render_state.render_pipeline.material_bind_group, &[]);
+                render_pass.set_bind_group(3, &render_state.render_pipeline.shadow_map.bind_group, &[]);
                 
-                // Set vertex buffer
+                // Set vertex and instance buffers
                 render_pass.set_vertex_buffer(0, render_state.mesh_buffer.vertex_buffer.slice(..));
+                render_pass.set_vertex_buffer(1, render_state.instance_buffer.buffer.slice(..));
+                render_pass.set_vertex_buffer(2, render_state.mesh_buffer.edge_length_buffer.slice(..));
                 
-                // Set index buffer and draw
+                // Set index buffer and draw: the filled surface, then the triangle
+                // edges on top (or alone) when a wireframe mode is active
                 render_pass.set_index_buffer(render_state.mesh_buffer.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
-                render_pass.draw_indexed(0..render_state.mesh_buffer.index_count, 0, 0..1);
+                if draw_surface {
+                    let surface_pipeline = if depth_prepass {
+                        &render_state.render_pipeline.depth_equal_pipeline
+                    } else {
+                        &render_state.render_pipeline.pipeline
+                    };
+                    render_pass.set_pipeline(surface_pipeline);
+                    render_pass.draw_indexed(0..render_state.mesh_buffer.index_count, 0, 0..render_state.instance_buffer.count);
+                }
+                if let Some(pipeline) = wireframe_pipeline {
+                    render_pass.set_pipeline(pipeline);
+                    render_pass.draw_indexed(0..render_state.mesh_buffer.index_count, 0, 0..render_state.instance_buffer.count);
+                }
+                
+                // Transparent grid floor after the opaque mesh (camera bind group is still bound)
+                if render_state.app_state.grid.enabled {
+                    render_state.grid_pipeline.draw(&mut render_pass);
+                }
+                
+                // Wire bounding box and light gizmo (camera bind group is still bound)
+                if render_state.app_state.show_bounds {
+                    render_state.line_pipeline.draw(&mut render_pass);
+                }
+                if render_state.app_state.show_light_gizmo {
+                    render_state.light_gizmo.draw(&mut render_pass);
+                }
+            }
+            
+            // Requested screenshot: the scene target alone is copied here, a capture
+            // with the UI is drawn into its own window-sized texture below
+            let screenshot = render_state.app_state.screenshot_request.take();
+            let include_ui = render_state.app_state.screenshot_include_ui;
+            if let Some(path) = screenshot.clone().filter(|_| !include_ui) {
+                if let Err(e) = render_state.readback.capture(
+                    &renderer.device,
+                    &mut encoder,
+                    &render_state.scene_target.color_texture,
+                    path,
+                ) {
+                    render_state.app_state.notifications.push(
+                        NotificationLevel::Error,
+                        format!("Screenshot failed: {}", e),
+                    );
+                }
             }
             
             // Update egui buffers
@@ -485,32 +1207,57 @@ fn render_frame(renderer: &mut Renderer, render_state: &mut RenderState, window:
                 &egui_primitives,
             );
             
-            // Render egui UI
-            {
-                let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                    label: Some("UI Render Pass"),
-                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                        view: &view,
-                        resolve_target: None,
-                        ops: wgpu::Operations {
-                            load: wgpu::LoadOp::Load,
-                            store: wgpu::StoreOp::Store,
-                        },
-                    })],
-                    depth_stencil_attachment: None,
-                    occlusion_query_set: None,
-                    timestamp_writes: None,
+            let viewport = [panel_width_pixels, 0.0, viewport_width, viewport_height];
+            draw_window(
+                &mut encoder,
+                &view,
+                render_state,
+                viewport,
+                background_color,
+                &egui_primitives,
+                &screen_descriptor,
+            );
+            
+            // The swapchain can't be copied from, so the whole window is drawn again offscreen
+            if let Some(path) = screenshot.filter(|_| include_ui) {
+                let window_texture = renderer.device.create_texture(&wgpu::TextureDescriptor {
+                    label: Some("screenshot_window_texture"),
+                    size: wgpu::Extent3d {
+                        width: renderer.size.width,
+                        height: renderer.size.height,
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    // Same format as the swapchain, which the egui renderer was built for
+                    format: renderer.config.format,
+                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+                    view_formats: &[],
                 });
-                
-                render_state.egui_state.render(
-                    &mut render_pass,
+                let window_view = window_texture.create_view(&wgpu::TextureViewDescriptor::default());
+                draw_window(
+                    &mut encoder,
+                    &window_view,
+                    render_state,
+                    viewport,
+                    background_color,
                     &egui_primitives,
                     &screen_descriptor,
                 );
+                if let Err(e) = render_state.readback.capture(&renderer.device, &mut encoder, &window_texture, path) {
+                    render_state.app_state.notifications.push(
+                        NotificationLevel::Error,
+                        format!("Screenshot failed: {}", e),
+                    );
+                }
             }
             
             renderer.queue.submit(std::iter::once(encoder.finish()));
             frame.present();
+            
+            render_state.readback.after_submit();
+            render_state.readback.poll(&renderer.device, &mut render_state.app_state.notifications);
         }
         Err(wgpu::SurfaceError::Lost) => {
             renderer.resize(renderer.size);
@@ -521,3 +1268,280 @@ fn render_frame(renderer: &mut Renderer, render_state: &mut RenderState, window:
         Err(e) => eprintln!("Surface error: {:?}", e),
     }
 }
+
+/// Composite the scene target into the 3D viewport of `target` (x, y, width,
+/// height in pixels) and draw the egui UI over the window
+fn draw_window(
+    encoder: &mut wgpu::CommandEncoder,
+    target: &wgpu::TextureView,
+    render_state: &mut RenderState,
+    viewport: [f32; 4],
+    background_color: wgpu::Color,
+    egui_primitives: &[epaint::ClippedPrimitive],
+    screen_descriptor: &egui_wgpu::ScreenDescriptor,
+) {
+    // Composite the scene target into the viewport, resampling to native resolution
+    {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Composite Render Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(background_color),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+        
+        // Set viewport to exclude UI panel area (render 3D to the right of the panel)
+        let [x, y, width, height] = viewport;
+        render_pass.set_viewport(x, y, width, height, 0.0, 1.0);
+        
+        render_pass.set_pipeline(&render_state.composite_pipeline.pipeline);
+        render_pass.set_bind_group(0, &render_state.scene_target.composite_bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+    
+    // Render egui UI
+    {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("UI Render Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+        
+        render_state.egui_state.render(
+            &mut render_pass,
+            egui_primitives,
+            screen_descriptor,
+        );
+    }
+}
+
+/// Upload a new mesh and update everything derived from it
+fn set_mesh(renderer: &Renderer, render_state: &mut RenderState, mesh_data: &mesh_wgpu::MeshData) {
+    render_state.mesh_buffer = MeshBuffer::new(&renderer.device, mesh_data);
+    render_state.app_state.mesh_bounds = Aabb::from_vertices(&mesh_data.vertices);
+    render_state.app_state.mesh_stats = MeshStats {
+        vertices: render_state.mesh_buffer.vertex_count,
+        triangles: render_state.mesh_buffer.triangle_count(),
+        mean_edge_length: mesh_wgpu::mean_edge_length(mesh_data),
+    };
+    render_state.line_pipeline.set_lines(
+        &renderer.device,
+        &bounds_lines(render_state.app_state.mesh_bounds.as_ref()),
+    );
+}
+
+/// Line segments for the mesh bounding box (empty when there is no mesh)
+fn bounds_lines(bounds: Option<&Aabb>) -> Vec<line_pipeline::LineVertex> {
+    const BOUNDS_COLOR: [f32; 4] = [1.0, 0.6, 0.1, 1.0];
+    bounds
+        .map(|aabb| line_pipeline::aabb_lines(aabb, BOUNDS_COLOR))
+        .unwrap_or_default()
+}
+
+/// Reload the texture of one slot after an explicit assignment.
+/// Slots without their own GPU texture fall back to a full reload.
+fn reload_texture_slot(renderer: &Renderer, render_state: &mut RenderState, slot: &'static str) {
+    use crate::texture::ColorSpace;
+    use crate::texture_loader::{TextureLoader, TextureArchive, TextureLoadFailure, is_zip_archive};
+    
+    let state = &mut render_state.app_state;
+    let color_space = match slot {
+        "base_color" | "emissive" => ColorSpace::Srgb,
+        "normal" | "orm" | "ao" | "height" => ColorSpace::Linear,
+        _ => {
+            state.textures_need_reload = true;
+            return;
+        }
+    };
+    let Some(path) = state.texture_handles.get(slot).map(std::path::PathBuf::from) else {
+        clear_texture_slot(renderer, render_state, slot);
+        return;
+    };
+    
+    // Paths from a zip listing are archive entries
+    let archive = state.texture_folder.as_ref()
+        .map(std::path::Path::new)
+        .filter(|folder| is_zip_archive(folder))
+        .and_then(|folder| TextureArchive::open(folder).ok());
+    let options = TextureLoadOptions {
+        max_size: state.max_texture_size,
+        color_space,
+        filter: state.texture_filter.for_slot(state.slot_samplers.get(slot)),
+    };
+    
+    match TextureLoader::load_texture_entry(&renderer.device, &renderer.queue, &path, archive.as_ref(), Some(slot), &options) {
+        Ok(texture) => {
+            install_texture(renderer, render_state, slot, texture);
+            render_state.app_state.last_texture_load = Some(TextureLoadReport { loaded: 1, failures: Vec::new() });
+            render_state.app_state.notifications.push(
+                NotificationLevel::Success,
+                format!("Reloaded {} texture from {}", slot, path.display()),
+            );
+        }
+        Err(e) => {
+            log::error!("Failed to load {} texture {}: {}", slot, path.display(), e);
+            render_state.app_state.notifications.push(
+                NotificationLevel::Error,
+                format!("Failed to load {} texture {}: {}", slot, path.display(), e),
+            );
+            render_state.app_state.last_texture_load = Some(TextureLoadReport {
+                loaded: 0,
+                failures: vec![TextureLoadFailure { path, error: e.to_string() }],
+            });
+        }
+    }
+}
+
+/// Recreate every pipeline drawn into the scene target for a new MSAA sample count
+fn rebuild_scene_pipelines(renderer: &Renderer, render_state: &mut RenderState, sample_count: u32) {
+    let device = &renderer.device;
+    let format = renderer.config.format;
+    render_state.render_pipeline.rebuild(device, format, sample_count);
+    let camera_layout = &render_state.render_pipeline.camera_bind_group_layout;
+    render_state.line_pipeline = LinePipeline::new(device, camera_layout, format, sample_count);
+    render_state.line_pipeline.set_lines(device, &bounds_lines(render_state.app_state.mesh_bounds.as_ref()));
+    render_state.light_gizmo = LinePipeline::new(device, camera_layout, format, sample_count);
+    render_state.grid_pipeline = GridPipeline::new(device, camera_layout, format, sample_count);
+    render_state.skybox_pipeline = SkyboxPipeline::new(device, &render_state.environment, format, sample_count);
+}
+
+/// Rebuild the PBR pipeline from new shader source, keeping the old one on errors
+fn reload_pbr_shader(renderer: &Renderer, render_state: &mut RenderState, source: &str) {
+    let file_name = render_state.shader_watcher.as_ref().map(ShaderWatcher::file_name).unwrap_or_default();
+    let result = shader::load_shader_checked(&renderer.device, source, Some("pbr_shader"))
+        .and_then(|shader| render_state.render_pipeline.recreate(&renderer.device, shader, renderer.config.format));
+    match result {
+        Ok(()) => {
+            log::info!("Reloaded {}", file_name);
+            render_state.app_state.notifications.push(NotificationLevel::Success, format!("Reloaded {}", file_name));
+        }
+        Err(e) => {
+            log::error!("Failed to compile {}: {}", file_name, e);
+            render_state.app_state.notifications.push(
+                NotificationLevel::Error,
+                format!("Failed to compile {} (see log), keeping the previous shader", file_name),
+            );
+        }
+    }
+}
+
+/// Put a loaded texture into a slot of the current set and rebind it
+fn install_texture(
+    renderer: &Renderer,
+    render_state: &mut RenderState,
+    slot: &str,
+    texture: (wgpu::Texture, wgpu::TextureView, wgpu::Sampler),
+) {
+    let state = &mut render_state.app_state;
+    let texture_set = &mut render_state.texture_set;
+    texture_set.set_slot(slot, texture);
+    match slot {
+        "base_color" => state.loaded_textures.base_color = true,
+        "normal" => state.loaded_textures.normal = true,
+        "ao" => state.loaded_textures.ao = true,
+        "emissive" => state.loaded_textures.emissive = true,
+        "height" => state.loaded_textures.height = true,
+        _ => state.loaded_textures.orm = true,
+    }
+    state.texture_info = texture_set.info;
+    
+    let texture_bind_group_layout = TextureSet::bind_group_layout(&renderer.device);
+    render_state.texture_bind_group = texture_set.create_bind_group(
+        &renderer.device,
+        &texture_bind_group_layout,
+        &render_state.environment,
+    );
+    state.material_changed = true;
+}
+
+/// Swap a slot of the current set back to its placeholder and rebind it
+fn clear_texture_slot(renderer: &Renderer, render_state: &mut RenderState, slot: &str) {
+    let state = &mut render_state.app_state;
+    let texture_set = &mut render_state.texture_set;
+    texture_set.clear_slot(&renderer.device, &renderer.queue, slot);
+    match slot {
+        "base_color" => state.loaded_textures.base_color = false,
+        "normal" => state.loaded_textures.normal = false,
+        "ao" => state.loaded_textures.ao = false,
+        "emissive" => state.loaded_textures.emissive = false,
+        "height" => state.loaded_textures.height = false,
+        _ => {
+            state.loaded_textures.orm = false;
+            state.loaded_textures.metallic = false;
+            state.loaded_textures.roughness = false;
+        }
+    }
+    state.texture_info = texture_set.info;
+    
+    let texture_bind_group_layout = TextureSet::bind_group_layout(&renderer.device);
+    render_state.texture_bind_group = texture_set.create_bind_group(
+        &renderer.device,
+        &texture_bind_group_layout,
+        &render_state.environment,
+    );
+    state.material_changed = true;
+    state.notifications.push(NotificationLevel::Info, format!("Cleared {} texture", slot));
+}
+
+/// Upload the material textures that came with a model (glTF) into their slots
+/// and take over the AO UV set, emissive strength and texture transform of its material
+fn apply_model_textures(renderer: &Renderer, render_state: &mut RenderState, textures: &model_loader::ModelTextures) {
+    use crate::texture::ColorSpace;
+    
+    for (slot, image, color_space) in [
+        ("base_color", &textures.base_color, ColorSpace::Srgb),
+        ("normal", &textures.normal, ColorSpace::Linear),
+        ("orm", &textures.metallic_roughness, ColorSpace::Linear),
+        ("emissive", &textures.emissive, ColorSpace::Srgb),
+    ] {
+        let Some(image) = image else {
+            continue;
+        };
+        let options = TextureLoadOptions {
+            max_size: render_state.app_state.max_texture_size,
+            color_space,
+            filter: render_state.app_state.texture_filter.for_slot(render_state.app_state.slot_samplers.get(slot)),
+        };
+        match texture::load_texture_from_image(&renderer.device, &renderer.queue, image, Some(slot), &options) {
+            Ok(texture) => install_texture(renderer, render_state, slot, texture),
+            Err(e) => render_state.app_state.notifications.push(
+                NotificationLevel::Error,
+                format!("Failed to upload the model's {} texture: {}", slot, e),
+            ),
+        }
+    }
+    
+    // Fields the model does not set go back to their defaults, so nothing carries
+    // over from the previously loaded model
+    let defaults = MaterialParams::default();
+    let material = &mut render_state.app_state.material_params;
+    material.occlusion_uses_uv1 = textures.occlusion_tex_coord == Some(1);
+    material.emissive_strength = textures.emissive_strength.unwrap_or(defaults.emissive_strength);
+    let transform = textures.uv_transform.unwrap_or(model_loader::UvTransform::IDENTITY);
+    let [tile_u, tile_v] = transform.tile_size;
+    material.uv_scale = tile_u;
+    material.uv_scale_v = (tile_v != tile_u).then_some(tile_v);
+    material.uv_offset = transform.offset;
+    material.uv_rotation_degrees = transform.rotation_degrees;
+    render_state.app_state.material_changed = true;
+    
+    for warning in &textures.warnings {
+        render_state.app_state.notifications.push(NotificationLevel::Warning, warning.clone());
+    }
+}
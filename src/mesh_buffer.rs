@@ -2,13 +2,16 @@
 
 use wgpu::*;
 use wgpu::util::DeviceExt;
-use crate::mesh_wgpu::MeshData;
+use crate::mesh_wgpu::{vertex_edge_lengths, MeshData};
 
 /// GPU mesh buffers
 pub struct MeshBuffer {
     pub vertex_buffer: Buffer,
+    /// Average edge length per vertex, for the triangle density heatmap (vertex buffer slot 2)
+    pub edge_length_buffer: Buffer,
     pub index_buffer: Buffer,
     pub index_count: u32,
+    pub vertex_count: u32,
 }
 
 impl MeshBuffer {
@@ -19,6 +22,12 @@ impl MeshBuffer {
             usage: BufferUsages::VERTEX,
         });
 
+        let edge_length_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Edge Length Buffer"),
+            contents: bytemuck::cast_slice(&vertex_edge_lengths(mesh_data)),
+            usage: BufferUsages::VERTEX,
+        });
+
         let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Index Buffer"),
             contents: bytemuck::cast_slice(&mesh_data.indices),
@@ -27,9 +36,25 @@ impl MeshBuffer {
 
         Self {
             vertex_buffer,
+            edge_length_buffer,
             index_buffer,
             index_count: mesh_data.indices.len() as u32,
+            vertex_count: mesh_data.vertices.len() as u32,
+        }
+    }
+
+    /// Layout of `edge_length_buffer` (shader location 8)
+    pub fn edge_length_desc() -> VertexBufferLayout<'static> {
+        const ATTRIBUTES: [VertexAttribute; 1] = vertex_attr_array![8 => Float32];
+        VertexBufferLayout {
+            array_stride: std::mem::size_of::<f32>() as BufferAddress,
+            step_mode: VertexStepMode::Vertex,
+            attributes: &ATTRIBUTES,
         }
     }
+
+    pub fn triangle_count(&self) -> u32 {
+        self.index_count / 3
+    }
 }
 
@@ -1,5 +1,7 @@
 //! Mesh generation for wgpu renderer
 
+use std::collections::HashMap;
+use glam::Vec3;
 
 /// Vertex data structure for wgpu
 #[repr(C)]
@@ -9,6 +11,8 @@ pub struct Vertex {
     pub normal: [f32; 3],
     pub uv: [f32; 2],
     pub tangent: [f32; 4],
+    /// Second UV set (AO/lightmaps). Meshes without one duplicate `uv`.
+    pub uv1: [f32; 2],
 }
 
 impl Vertex {
@@ -37,6 +41,11 @@ impl Vertex {
                     shader_location: 3,
                     format: wgpu::VertexFormat::Float32x4,
                 },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 12]>() as wgpu::BufferAddress,
+                    shader_location: 4,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
             ],
         }
     }
@@ -48,11 +57,249 @@ pub struct MeshData {
     pub indices: Vec<u32>,
 }
 
+/// Raw triangle attributes as read from a model file.
+/// `into_mesh` fills in whatever the file didn't provide.
+#[derive(Debug, Default)]
+pub struct MeshAttributes {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Option<Vec<[f32; 3]>>,
+    pub uvs: Option<Vec<[f32; 2]>>,
+    /// Second UV set; defaults to `uvs`
+    pub uvs1: Option<Vec<[f32; 2]>>,
+    pub tangents: Option<Vec<[f32; 4]>>,
+    pub indices: Vec<u32>,
+}
+
+impl MeshAttributes {
+    /// Build the vertex/index buffers. Missing normals become flat per-face
+    /// normals (which requires unwelding the vertices), missing UVs a planar
+    /// projection along the thinnest axis, and missing tangents are generated
+    /// from the UVs.
+    pub fn into_mesh(mut self) -> MeshData {
+        if self.normals.is_none() {
+            self.unweld();
+            self.normals = Some(flat_normals(&self.positions));
+        }
+        let normals = self.normals.take().unwrap_or_default();
+        let uvs = self.uvs.take().unwrap_or_else(|| planar_uvs(&self.positions));
+        let tangents = self.tangents.take()
+            .unwrap_or_else(|| generate_tangents(&self.positions, &normals, &uvs, &self.indices));
+        let uvs1 = self.uvs1.take().unwrap_or_else(|| uvs.clone());
+        
+        let vertices = (0..self.positions.len())
+            .map(|i| Vertex {
+                position: self.positions[i],
+                normal: normals[i],
+                uv: uvs[i],
+                tangent: tangents[i],
+                uv1: uvs1[i],
+            })
+            .collect();
+        MeshData { vertices, indices: self.indices }
+    }
+    
+    /// Give every triangle corner its own vertex
+    fn unweld(&mut self) {
+        let indices = std::mem::take(&mut self.indices);
+        self.positions = indices.iter().map(|&i| self.positions[i as usize]).collect();
+        self.uvs = self.uvs.take().map(|uvs| indices.iter().map(|&i| uvs[i as usize]).collect());
+        self.uvs1 = self.uvs1.take().map(|uvs| indices.iter().map(|&i| uvs[i as usize]).collect());
+        self.tangents = self.tangents.take().map(|t| indices.iter().map(|&i| t[i as usize]).collect());
+        self.indices = (0..indices.len() as u32).collect();
+    }
+}
+
+/// Per-face normals for an unwelded triangle list
+fn flat_normals(positions: &[[f32; 3]]) -> Vec<[f32; 3]> {
+    positions
+        .chunks_exact(3)
+        .flat_map(|triangle| {
+            let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(Vec3::from);
+            let normal = (b - a).cross(c - a).try_normalize().unwrap_or(Vec3::Y);
+            [normal.to_array(); 3]
+        })
+        .collect()
+}
+
+/// Project positions onto the plane of their two largest extents, scaled to 0–1
+fn planar_uvs(positions: &[[f32; 3]]) -> Vec<[f32; 2]> {
+    let Some(bounds) = Aabb::from_points(positions) else {
+        return Vec::new();
+    };
+    let size = bounds.size();
+    // Drop the thinnest axis
+    let (u_axis, v_axis) = if size.x <= size.y && size.x <= size.z {
+        (2, 1)
+    } else if size.y <= size.z {
+        (0, 2)
+    } else {
+        (0, 1)
+    };
+    let extent = size.max_element().max(1e-6);
+    positions
+        .iter()
+        .map(|p| {
+            let u = (p[u_axis] - bounds.min[u_axis]) / extent;
+            let v = (p[v_axis] - bounds.min[v_axis]) / extent;
+            [u, 1.0 - v]
+        })
+        .collect()
+}
+
+/// Replace the tangents of a mesh with ones generated from its positions, normals and UVs,
+/// for models whose own tangents are missing or broken
+pub fn compute_tangents(mesh: &mut MeshData) {
+    let positions: Vec<[f32; 3]> = mesh.vertices.iter().map(|v| v.position).collect();
+    let normals: Vec<[f32; 3]> = mesh.vertices.iter().map(|v| v.normal).collect();
+    let uvs: Vec<[f32; 2]> = mesh.vertices.iter().map(|v| v.uv).collect();
+    let tangents = generate_tangents(&positions, &normals, &uvs, &mesh.indices);
+    for (vertex, tangent) in mesh.vertices.iter_mut().zip(tangents) {
+        vertex.tangent = tangent;
+    }
+}
+
+/// Per-vertex tangents (xyz) and bitangent handedness (w) from the UV layout (Lengyel's
+/// method: per-face tangents summed per vertex, then orthonormalized against the normal)
+fn generate_tangents(positions: &[[f32; 3]], normals: &[[f32; 3]], uvs: &[[f32; 2]], indices: &[u32]) -> Vec<[f32; 4]> {
+    let mut tangents = vec![Vec3::ZERO; positions.len()];
+    let mut bitangents = vec![Vec3::ZERO; positions.len()];
+    
+    for triangle in indices.chunks_exact(3) {
+        let [i0, i1, i2] = [triangle[0], triangle[1], triangle[2]].map(|i| i as usize);
+        let edge1 = Vec3::from(positions[i1]) - Vec3::from(positions[i0]);
+        let edge2 = Vec3::from(positions[i2]) - Vec3::from(positions[i0]);
+        let duv1 = glam::Vec2::from(uvs[i1]) - glam::Vec2::from(uvs[i0]);
+        let duv2 = glam::Vec2::from(uvs[i2]) - glam::Vec2::from(uvs[i0]);
+        let det = duv1.x * duv2.y - duv2.x * duv1.y;
+        if det.abs() < 1e-12 {
+            continue;
+        }
+        let r = 1.0 / det;
+        let tangent = (edge1 * duv2.y - edge2 * duv1.y) * r;
+        let bitangent = (edge2 * duv1.x - edge1 * duv2.x) * r;
+        for i in [i0, i1, i2] {
+            tangents[i] += tangent;
+            bitangents[i] += bitangent;
+        }
+    }
+    
+    (0..positions.len())
+        .map(|i| {
+            let normal = Vec3::from(normals[i]);
+            // Gram-Schmidt against the normal; any perpendicular works for degenerate UVs
+            let tangent = (tangents[i] - normal * normal.dot(tangents[i]))
+                .try_normalize()
+                .unwrap_or_else(|| normal.any_orthonormal_vector());
+            let handedness = if normal.cross(tangent).dot(bitangents[i]) < 0.0 { -1.0 } else { 1.0 };
+            tangent.extend(handedness).to_array()
+        })
+        .collect()
+}
+
+/// Average length of the triangle edges in model space (0 for an empty mesh).
+/// Edges shared by two triangles are counted twice, which doesn't change the mean much.
+pub fn mean_edge_length(mesh: &MeshData) -> f32 {
+    let position = |i: u32| Vec3::from(mesh.vertices[i as usize].position);
+    let (total, count) = mesh.indices
+        .chunks_exact(3)
+        .flat_map(|triangle| [(triangle[0], triangle[1]), (triangle[1], triangle[2]), (triangle[2], triangle[0])])
+        .fold((0.0f64, 0u64), |(total, count), (a, b)| {
+            (total + position(a).distance(position(b)) as f64, count + 1)
+        });
+    if count == 0 { 0.0 } else { (total / count as f64) as f32 }
+}
+
+/// Per vertex, the average length of the triangle edges meeting there (model units).
+/// Interpolated across a triangle this approximates the size of that triangle.
+pub fn vertex_edge_lengths(mesh: &MeshData) -> Vec<f32> {
+    let mut totals = vec![(0.0f32, 0u32); mesh.vertices.len()];
+    for triangle in mesh.indices.chunks_exact(3) {
+        for (a, b) in [(triangle[0], triangle[1]), (triangle[1], triangle[2]), (triangle[2], triangle[0])] {
+            let length = Vec3::from(mesh.vertices[a as usize].position)
+                .distance(Vec3::from(mesh.vertices[b as usize].position));
+            for i in [a, b] {
+                totals[i as usize].0 += length;
+                totals[i as usize].1 += 1;
+            }
+        }
+    }
+    totals
+        .into_iter()
+        .map(|(total, count)| if count == 0 { 0.0 } else { total / count as f32 })
+        .collect()
+}
+
+/// Axis-aligned bounding box in model space
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    /// Bounds of the given vertices (`None` for an empty mesh)
+    pub fn from_vertices(vertices: &[Vertex]) -> Option<Self> {
+        let first = Vec3::from(vertices.first()?.position);
+        let (min, max) = vertices.iter().fold((first, first), |(min, max), v| {
+            let p = Vec3::from(v.position);
+            (min.min(p), max.max(p))
+        });
+        Some(Self { min, max })
+    }
+
+    /// Bounds of the given points (`None` if there are none)
+    pub fn from_points(points: &[[f32; 3]]) -> Option<Self> {
+        let first = Vec3::from(*points.first()?);
+        let (min, max) = points.iter().fold((first, first), |(min, max), &p| {
+            let p = Vec3::from(p);
+            (min.min(p), max.max(p))
+        });
+        Some(Self { min, max })
+    }
+
+    pub fn size(&self) -> Vec3 {
+        self.max - self.min
+    }
+
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// The 12 box edges as pairs of endpoints
+    pub fn edges(&self) -> [(Vec3, Vec3); 12] {
+        let corner = |x: bool, y: bool, z: bool| {
+            Vec3::new(
+                if x { self.max.x } else { self.min.x },
+                if y { self.max.y } else { self.min.y },
+                if z { self.max.z } else { self.min.z },
+            )
+        };
+        [
+            // Bottom face
+            (corner(false, false, false), corner(true, false, false)),
+            (corner(true, false, false), corner(true, false, true)),
+            (corner(true, false, true), corner(false, false, true)),
+            (corner(false, false, true), corner(false, false, false)),
+            // Top face
+            (corner(false, true, false), corner(true, true, false)),
+            (corner(true, true, false), corner(true, true, true)),
+            (corner(true, true, true), corner(false, true, true)),
+            (corner(false, true, true), corner(false, true, false)),
+            // Verticals
+            (corner(false, false, false), corner(false, true, false)),
+            (corner(true, false, false), corner(true, true, false)),
+            (corner(true, false, true), corner(true, true, true)),
+            (corner(false, false, true), corner(false, true, true)),
+        ]
+    }
+}
+
 /// Types of meshes available
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum MeshType {
     #[default]
     Sphere,
+    Icosphere,
     Cube,
     Plane,
     RoundedRect,
@@ -61,12 +308,13 @@ pub enum MeshType {
 
 impl MeshType {
     pub fn primitives() -> &'static [MeshType] {
-        &[MeshType::Sphere, MeshType::Cube]
+        &[MeshType::Sphere, MeshType::Icosphere, MeshType::Cube, MeshType::Plane, MeshType::RoundedRect]
     }
 
     pub fn name(&self) -> &'static str {
         match self {
             MeshType::Sphere => "Sphere",
+            MeshType::Icosphere => "Icosphere",
             MeshType::Cube => "Cube",
             MeshType::Plane => "Plane",
             MeshType::RoundedRect => "Rounded Rect",
@@ -118,16 +366,17 @@ pub fn create_sphere(subdivisions: u32) -> MeshData {
                 normal,
                 uv,
                 tangent,
+                uv1: uv,
             });
         }
     }
     
     // Generate indices
     for i in 0..stacks {
-        let mut k1 = i * (sectors + 1);
-        let mut k2 = k1 + sectors + 1;
-        
-        for _j in 0..sectors {
+        for j in 0..sectors {
+            let k1 = i * (sectors + 1) + j;
+            let k2 = k1 + sectors + 1;
+            
             if i != 0 {
                 indices.push(k1 as u32);
                 indices.push(k2 as u32);
@@ -139,49 +388,159 @@ pub fn create_sphere(subdivisions: u32) -> MeshData {
                 indices.push(k2 as u32);
                 indices.push((k2 + 1) as u32);
             }
-            
-            k1 += 1;
-            k2 += 1;
         }
     }
     
     MeshData { vertices, indices }
 }
 
+/// Maximum icosphere subdivision level (level 7 is ~330k triangles)
+pub const MAX_ICOSPHERE_SUBDIVISIONS: u32 = 7;
+
+/// Icosphere subdivision level with roughly the detail of a UV sphere with `segments` segments
+pub fn icosphere_subdivisions(segments: u32) -> u32 {
+    // Each level doubles the edge count around the sphere, starting at ~4 segments
+    (segments.max(4) as f32 / 4.0).log2().round() as u32
+}
+
+/// Create a unit sphere by subdividing an icosahedron (Y up). Unlike the UV
+/// sphere its triangles are nearly uniform, without pinched poles.
+///
+/// UVs are spherical. Vertices on the wrap seam are duplicated with u + 1 and
+/// pole vertices get one copy per triangle, so textures don't smear across
+/// the seam or swirl at the poles.
+pub fn create_icosphere(subdivisions: u32) -> MeshData {
+    let t = (1.0 + 5.0f32.sqrt()) / 2.0;
+    let mut positions: Vec<Vec3> = [
+        [-1.0, t, 0.0], [1.0, t, 0.0], [-1.0, -t, 0.0], [1.0, -t, 0.0],
+        [0.0, -1.0, t], [0.0, 1.0, t], [0.0, -1.0, -t], [0.0, 1.0, -t],
+        [t, 0.0, -1.0], [t, 0.0, 1.0], [-t, 0.0, -1.0], [-t, 0.0, 1.0],
+    ]
+    .iter()
+    .map(|p| Vec3::from(*p).normalize())
+    .collect();
+    
+    let mut faces: Vec<[u32; 3]> = vec![
+        [0, 11, 5], [0, 5, 1], [0, 1, 7], [0, 7, 10], [0, 10, 11],
+        [1, 5, 9], [5, 11, 4], [11, 10, 2], [10, 7, 6], [7, 1, 8],
+        [3, 9, 4], [3, 4, 2], [3, 2, 6], [3, 6, 8], [3, 8, 9],
+        [4, 9, 5], [2, 4, 11], [6, 2, 10], [8, 6, 7], [9, 8, 1],
+    ];
+    
+    // Split every triangle into four, sharing edge midpoints between neighbours
+    for _ in 0..subdivisions.min(MAX_ICOSPHERE_SUBDIVISIONS) {
+        let mut midpoints = HashMap::new();
+        let mut midpoint = |a: u32, b: u32, positions: &mut Vec<Vec3>| {
+            *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                let p = ((positions[a as usize] + positions[b as usize]) * 0.5).normalize();
+                positions.push(p);
+                positions.len() as u32 - 1
+            })
+        };
+        faces = faces
+            .iter()
+            .flat_map(|&[a, b, c]| {
+                let ab = midpoint(a, b, &mut positions);
+                let bc = midpoint(b, c, &mut positions);
+                let ca = midpoint(c, a, &mut positions);
+                [[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]
+            })
+            .collect();
+    }
+    
+    let mut uvs: Vec<[f32; 2]> = positions
+        .iter()
+        .map(|p| {
+            let u = 0.5 + p.x.atan2(p.z) / std::f32::consts::TAU;
+            let v = 0.5 - p.y.asin() / std::f32::consts::PI;
+            [u, v]
+        })
+        .collect();
+    
+    // Triangles crossing the seam get copies of their low-u vertices shifted by one
+    let mut wrapped: HashMap<u32, u32> = HashMap::new();
+    for face in faces.iter_mut() {
+        let us = face.map(|i| uvs[i as usize][0]);
+        let min_u = us.iter().copied().fold(f32::INFINITY, f32::min);
+        let max_u = us.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        if max_u - min_u <= 0.5 {
+            continue;
+        }
+        for index in face.iter_mut() {
+            let original = *index;
+            if uvs[original as usize][0] < 0.5 {
+                *index = *wrapped.entry(original).or_insert_with(|| {
+                    let [u, v] = uvs[original as usize];
+                    positions.push(positions[original as usize]);
+                    uvs.push([u + 1.0, v]);
+                    positions.len() as u32 - 1
+                });
+            }
+        }
+    }
+    
+    // Pole vertices have no meaningful u: give each triangle its own copy
+    // centered between its other two vertices
+    for face in faces.iter_mut() {
+        for corner in 0..3 {
+            let index = face[corner] as usize;
+            if positions[index].y.abs() < 0.9999 {
+                continue;
+            }
+            let others = [face[(corner + 1) % 3], face[(corner + 2) % 3]];
+            let u = (uvs[others[0] as usize][0] + uvs[others[1] as usize][0]) * 0.5;
+            positions.push(positions[index]);
+            uvs.push([u, uvs[index][1]]);
+            face[corner] = positions.len() as u32 - 1;
+        }
+    }
+    
+    let positions: Vec<[f32; 3]> = positions.iter().map(|p| p.to_array()).collect();
+    MeshAttributes {
+        // Normals of a unit sphere are its positions
+        normals: Some(positions.clone()),
+        positions,
+        uvs: Some(uvs),
+        indices: faces.into_iter().flatten().collect(),
+        ..Default::default()
+    }
+    .into_mesh()
+}
+
 /// Create a cube mesh
 pub fn create_cube() -> MeshData {
     let size = 1.0;
     let vertices = vec![
         // Front face
-        Vertex { position: [-size, -size, size], normal: [0.0, 0.0, 1.0], uv: [0.0, 1.0], tangent: [1.0, 0.0, 0.0, 1.0] },
-        Vertex { position: [size, -size, size], normal: [0.0, 0.0, 1.0], uv: [1.0, 1.0], tangent: [1.0, 0.0, 0.0, 1.0] },
-        Vertex { position: [size, size, size], normal: [0.0, 0.0, 1.0], uv: [1.0, 0.0], tangent: [1.0, 0.0, 0.0, 1.0] },
-        Vertex { position: [-size, size, size], normal: [0.0, 0.0, 1.0], uv: [0.0, 0.0], tangent: [1.0, 0.0, 0.0, 1.0] },
+        Vertex { position: [-size, -size, size], normal: [0.0, 0.0, 1.0], uv: [0.0, 1.0], tangent: [1.0, 0.0, 0.0, 1.0], uv1: [0.0, 1.0] },
+        Vertex { position: [size, -size, size], normal: [0.0, 0.0, 1.0], uv: [1.0, 1.0], tangent: [1.0, 0.0, 0.0, 1.0], uv1: [1.0, 1.0] },
+        Vertex { position: [size, size, size], normal: [0.0, 0.0, 1.0], uv: [1.0, 0.0], tangent: [1.0, 0.0, 0.0, 1.0], uv1: [1.0, 0.0] },
+        Vertex { position: [-size, size, size], normal: [0.0, 0.0, 1.0], uv: [0.0, 0.0], tangent: [1.0, 0.0, 0.0, 1.0], uv1: [0.0, 0.0] },
         // Back face
-        Vertex { position: [-size, -size, -size], normal: [0.0, 0.0, -1.0], uv: [1.0, 1.0], tangent: [-1.0, 0.0, 0.0, 1.0] },
-        Vertex { position: [-size, size, -size], normal: [0.0, 0.0, -1.0], uv: [1.0, 0.0], tangent: [-1.0, 0.0, 0.0, 1.0] },
-        Vertex { position: [size, size, -size], normal: [0.0, 0.0, -1.0], uv: [0.0, 0.0], tangent: [-1.0, 0.0, 0.0, 1.0] },
-        Vertex { position: [size, -size, -size], normal: [0.0, 0.0, -1.0], uv: [0.0, 1.0], tangent: [-1.0, 0.0, 0.0, 1.0] },
+        Vertex { position: [-size, -size, -size], normal: [0.0, 0.0, -1.0], uv: [1.0, 1.0], tangent: [-1.0, 0.0, 0.0, 1.0], uv1: [1.0, 1.0] },
+        Vertex { position: [-size, size, -size], normal: [0.0, 0.0, -1.0], uv: [1.0, 0.0], tangent: [-1.0, 0.0, 0.0, 1.0], uv1: [1.0, 0.0] },
+        Vertex { position: [size, size, -size], normal: [0.0, 0.0, -1.0], uv: [0.0, 0.0], tangent: [-1.0, 0.0, 0.0, 1.0], uv1: [0.0, 0.0] },
+        Vertex { position: [size, -size, -size], normal: [0.0, 0.0, -1.0], uv: [0.0, 1.0], tangent: [-1.0, 0.0, 0.0, 1.0], uv1: [0.0, 1.0] },
         // Top face
-        Vertex { position: [-size, size, -size], normal: [0.0, 1.0, 0.0], uv: [0.0, 1.0], tangent: [1.0, 0.0, 0.0, 1.0] },
-        Vertex { position: [-size, size, size], normal: [0.0, 1.0, 0.0], uv: [0.0, 0.0], tangent: [1.0, 0.0, 0.0, 1.0] },
-        Vertex { position: [size, size, size], normal: [0.0, 1.0, 0.0], uv: [1.0, 0.0], tangent: [1.0, 0.0, 0.0, 1.0] },
-        Vertex { position: [size, size, -size], normal: [0.0, 1.0, 0.0], uv: [1.0, 1.0], tangent: [1.0, 0.0, 0.0, 1.0] },
+        Vertex { position: [-size, size, -size], normal: [0.0, 1.0, 0.0], uv: [0.0, 1.0], tangent: [1.0, 0.0, 0.0, 1.0], uv1: [0.0, 1.0] },
+        Vertex { position: [-size, size, size], normal: [0.0, 1.0, 0.0], uv: [0.0, 0.0], tangent: [1.0, 0.0, 0.0, 1.0], uv1: [0.0, 0.0] },
+        Vertex { position: [size, size, size], normal: [0.0, 1.0, 0.0], uv: [1.0, 0.0], tangent: [1.0, 0.0, 0.0, 1.0], uv1: [1.0, 0.0] },
+        Vertex { position: [size, size, -size], normal: [0.0, 1.0, 0.0], uv: [1.0, 1.0], tangent: [1.0, 0.0, 0.0, 1.0], uv1: [1.0, 1.0] },
         // Bottom face
-        Vertex { position: [-size, -size, -size], normal: [0.0, -1.0, 0.0], uv: [1.0, 1.0], tangent: [1.0, 0.0, 0.0, 1.0] },
-        Vertex { position: [size, -size, -size], normal: [0.0, -1.0, 0.0], uv: [0.0, 1.0], tangent: [1.0, 0.0, 0.0, 1.0] },
-        Vertex { position: [size, -size, size], normal: [0.0, -1.0, 0.0], uv: [0.0, 0.0], tangent: [1.0, 0.0, 0.0, 1.0] },
-        Vertex { position: [-size, -size, size], normal: [0.0, -1.0, 0.0], uv: [1.0, 0.0], tangent: [1.0, 0.0, 0.0, 1.0] },
+        Vertex { position: [-size, -size, -size], normal: [0.0, -1.0, 0.0], uv: [1.0, 1.0], tangent: [1.0, 0.0, 0.0, 1.0], uv1: [1.0, 1.0] },
+        Vertex { position: [size, -size, -size], normal: [0.0, -1.0, 0.0], uv: [0.0, 1.0], tangent: [1.0, 0.0, 0.0, 1.0], uv1: [0.0, 1.0] },
+        Vertex { position: [size, -size, size], normal: [0.0, -1.0, 0.0], uv: [0.0, 0.0], tangent: [1.0, 0.0, 0.0, 1.0], uv1: [0.0, 0.0] },
+        Vertex { position: [-size, -size, size], normal: [0.0, -1.0, 0.0], uv: [1.0, 0.0], tangent: [1.0, 0.0, 0.0, 1.0], uv1: [1.0, 0.0] },
         // Right face
-        Vertex { position: [size, -size, -size], normal: [1.0, 0.0, 0.0], uv: [1.0, 1.0], tangent: [0.0, 0.0, 1.0, 1.0] },
-        Vertex { position: [size, size, -size], normal: [1.0, 0.0, 0.0], uv: [1.0, 0.0], tangent: [0.0, 0.0, 1.0, 1.0] },
-        Vertex { position: [size, size, size], normal: [1.0, 0.0, 0.0], uv: [0.0, 0.0], tangent: [0.0, 0.0, 1.0, 1.0] },
-        Vertex { position: [size, -size, size], normal: [1.0, 0.0, 0.0], uv: [0.0, 1.0], tangent: [0.0, 0.0, 1.0, 1.0] },
+        Vertex { position: [size, -size, -size], normal: [1.0, 0.0, 0.0], uv: [1.0, 1.0], tangent: [0.0, 0.0, 1.0, 1.0], uv1: [1.0, 1.0] },
+        Vertex { position: [size, size, -size], normal: [1.0, 0.0, 0.0], uv: [1.0, 0.0], tangent: [0.0, 0.0, 1.0, 1.0], uv1: [1.0, 0.0] },
+        Vertex { position: [size, size, size], normal: [1.0, 0.0, 0.0], uv: [0.0, 0.0], tangent: [0.0, 0.0, 1.0, 1.0], uv1: [0.0, 0.0] },
+        Vertex { position: [size, -size, size], normal: [1.0, 0.0, 0.0], uv: [0.0, 1.0], tangent: [0.0, 0.0, 1.0, 1.0], uv1: [0.0, 1.0] },
         // Left face
-        Vertex { position: [-size, -size, -size], normal: [-1.0, 0.0, 0.0], uv: [0.0, 1.0], tangent: [0.0, 0.0, -1.0, 1.0] },
-        Vertex { position: [-size, -size, size], normal: [-1.0, 0.0, 0.0], uv: [1.0, 1.0], tangent: [0.0, 0.0, -1.0, 1.0] },
-        Vertex { position: [-size, size, size], normal: [-1.0, 0.0, 0.0], uv: [1.0, 0.0], tangent: [0.0, 0.0, -1.0, 1.0] },
-        Vertex { position: [-size, size, -size], normal: [-1.0, 0.0, 0.0], uv: [0.0, 0.0], tangent: [0.0, 0.0, -1.0, 1.0] },
+        Vertex { position: [-size, -size, -size], normal: [-1.0, 0.0, 0.0], uv: [0.0, 1.0], tangent: [0.0, 0.0, -1.0, 1.0], uv1: [0.0, 1.0] },
+        Vertex { position: [-size, -size, size], normal: [-1.0, 0.0, 0.0], uv: [1.0, 1.0], tangent: [0.0, 0.0, -1.0, 1.0], uv1: [1.0, 1.0] },
+        Vertex { position: [-size, size, size], normal: [-1.0, 0.0, 0.0], uv: [1.0, 0.0], tangent: [0.0, 0.0, -1.0, 1.0], uv1: [1.0, 0.0] },
+        Vertex { position: [-size, size, -size], normal: [-1.0, 0.0, 0.0], uv: [0.0, 0.0], tangent: [0.0, 0.0, -1.0, 1.0], uv1: [0.0, 0.0] },
     ];
     
     let indices = vec![
@@ -197,7 +556,6 @@ pub fn create_cube() -> MeshData {
 }
 
 /// Create a plane mesh
-#[allow(dead_code)]
 pub fn create_plane(subdivisions: u32) -> MeshData {
     let subdivs = subdivisions.clamp(1, 256);
     
@@ -220,6 +578,7 @@ pub fn create_plane(subdivisions: u32) -> MeshData {
                 normal: [0.0, 1.0, 0.0],
                 uv: [u, v],
                 tangent: [1.0, 0.0, 0.0, 1.0],
+                uv1: [u, v],
             });
         }
     }
@@ -232,13 +591,13 @@ pub fn create_plane(subdivisions: u32) -> MeshData {
             let bottom_left = top_left + subdivs + 1;
             let bottom_right = bottom_left + 1;
             
-            indices.push(top_left as u32);
-            indices.push(bottom_left as u32);
-            indices.push(top_right as u32);
+            indices.push(top_left);
+            indices.push(bottom_left);
+            indices.push(top_right);
             
-            indices.push(top_right as u32);
-            indices.push(bottom_left as u32);
-            indices.push(bottom_right as u32);
+            indices.push(top_right);
+            indices.push(bottom_left);
+            indices.push(bottom_right);
         }
     }
     
@@ -246,7 +605,6 @@ pub fn create_plane(subdivisions: u32) -> MeshData {
 }
 
 /// Create a rounded rectangle mesh
-#[allow(dead_code)]
 pub fn create_rounded_rect(subdivisions: u32, corner_radius: f32) -> MeshData {
     let subdivs = subdivisions.clamp(8, 256);
     let radius = corner_radius.clamp(0.0, 0.45);
@@ -286,6 +644,7 @@ pub fn create_rounded_rect(subdivisions: u32, corner_radius: f32) -> MeshData {
                 normal: [0.0, 1.0, 0.0],
                 uv: [u, 1.0 - v],
                 tangent: [1.0, 0.0, 0.0, 1.0],
+                uv1: [u, 1.0 - v],
             });
         }
     }
@@ -297,16 +656,57 @@ pub fn create_rounded_rect(subdivisions: u32, corner_radius: f32) -> MeshData {
             let bottom_left = top_left + subdivs + 1;
             let bottom_right = bottom_left + 1;
             
-            indices.push(top_left as u32);
-            indices.push(bottom_left as u32);
-            indices.push(top_right as u32);
+            indices.push(top_left);
+            indices.push(bottom_left);
+            indices.push(top_right);
             
-            indices.push(top_right as u32);
-            indices.push(bottom_left as u32);
-            indices.push(bottom_right as u32);
+            indices.push(top_right);
+            indices.push(bottom_left);
+            indices.push(bottom_right);
         }
     }
     
     MeshData { vertices, indices }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Unit quad in the XY plane facing +Z, with U taken from `u_of_x` and V along +Y
+    fn xy_quad(u_of_x: fn(f32) -> f32) -> MeshData {
+        let vertices = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]]
+            .map(|[x, y]| Vertex {
+                position: [x, y, 0.0],
+                normal: [0.0, 0.0, 1.0],
+                uv: [u_of_x(x), y],
+                tangent: [0.0; 4],
+                uv1: [u_of_x(x), y],
+            })
+            .to_vec();
+        MeshData { vertices, indices: vec![0, 1, 2, 0, 2, 3] }
+    }
+
+    fn assert_tangents(mesh: &MeshData, expected: Vec3, handedness: f32) {
+        for vertex in &mesh.vertices {
+            let tangent = Vec3::from_slice(&vertex.tangent[..3]);
+            assert!(tangent.abs_diff_eq(expected, 1e-5), "tangent {:?}, expected {:?}", tangent, expected);
+            assert_eq!(vertex.tangent[3], handedness);
+        }
+    }
+
+    #[test]
+    fn compute_tangents_follows_u_on_a_quad() {
+        let mut mesh = xy_quad(|x| x);
+        compute_tangents(&mut mesh);
+        assert_tangents(&mesh, Vec3::X, 1.0);
+    }
+
+    #[test]
+    fn compute_tangents_flips_handedness_for_mirrored_u() {
+        let mut mesh = xy_quad(|x| 1.0 - x);
+        compute_tangents(&mut mesh);
+        assert_tangents(&mesh, Vec3::NEG_X, -1.0);
+    }
+}
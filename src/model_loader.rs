@@ -0,0 +1,514 @@
+//! Loading custom models from disk into `MeshData`
+
+use std::path::Path;
+use glam::{Mat2, Mat3, Mat4, Vec2, Vec3};
+use image::DynamicImage;
+use crate::mesh_wgpu::{MeshAttributes, MeshData};
+
+/// Model file extensions that can be loaded
+pub const MODEL_EXTENSIONS: &[&str] = &["obj", "gltf", "glb"];
+
+/// Textures embedded in or referenced by a model's material
+#[derive(Default)]
+pub struct ModelTextures {
+    pub base_color: Option<DynamicImage>,
+    pub normal: Option<DynamicImage>,
+    /// Packed occlusion (R), roughness (G) and metallic (B), like the ORM slot
+    pub metallic_roughness: Option<DynamicImage>,
+    /// UV set (`TEXCOORD_n`) the material's occlusion texture is mapped with
+    pub occlusion_tex_coord: Option<u32>,
+    /// Emissive texture with the material's emissive factor baked in
+    pub emissive: Option<DynamicImage>,
+    /// `KHR_materials_emissive_strength` multiplier
+    pub emissive_strength: Option<f32>,
+    /// `KHR_texture_transform` shared by the maps sampled from UV0
+    pub uv_transform: Option<UvTransform>,
+    /// Parts of the material mapping the viewer cannot reproduce
+    pub warnings: Vec<String>,
+}
+
+/// A glTF texture transform expressed in the viewer's UV controls
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UvTransform {
+    /// Tile size along U and V
+    pub tile_size: [f32; 2],
+    pub offset: [f32; 2],
+    pub rotation_degrees: f32,
+}
+
+impl UvTransform {
+    /// The viewer's default UV state
+    pub const IDENTITY: Self = Self {
+        tile_size: [1.0, 1.0],
+        offset: [0.0, 0.0],
+        rotation_degrees: 0.0,
+    };
+
+    /// glTF applies `offset + rotation * scale * uv` around the UV origin, with the
+    /// rotation turning the image clockwise; the viewer scales and rotates around the
+    /// UV center instead, so the offset absorbs the pivot difference.
+    fn from_gltf(offset: [f32; 2], rotation: f32, scale: [f32; 2]) -> Self {
+        let (sin, cos) = rotation.sin_cos();
+        let rotate_scale = Mat2::from_cols(Vec2::new(cos, -sin), Vec2::new(sin, cos))
+            * Mat2::from_diagonal(Vec2::from(scale));
+        let pivot = Vec2::splat(0.5);
+        let offset = Vec2::from(offset) + rotate_scale * pivot - pivot;
+        Self {
+            tile_size: scale.map(|s| if s == 0.0 { 1.0 } else { 1.0 / s }),
+            offset: offset.to_array(),
+            rotation_degrees: -rotation.to_degrees(),
+        }
+    }
+}
+
+/// UV set and transform one material texture is sampled with
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TextureMapping {
+    /// `TEXCOORD_n` set, after the transform's texCoord override
+    tex_coord: u32,
+    transform: UvTransform,
+}
+
+impl TextureMapping {
+    fn from_info(info: &gltf::texture::Info) -> Self {
+        match info.texture_transform() {
+            Some(transform) => Self {
+                tex_coord: transform.tex_coord().unwrap_or(info.tex_coord()),
+                transform: UvTransform::from_gltf(transform.offset(), transform.rotation(), transform.scale()),
+            },
+            None => Self { tex_coord: info.tex_coord(), transform: UvTransform::IDENTITY },
+        }
+    }
+
+    /// Normal and occlusion textures only expose their extensions as JSON
+    fn from_json(tex_coord: u32, extension: Option<&gltf::json::Value>) -> Self {
+        use gltf::json::extensions::texture::TextureTransform;
+        let transform = extension
+            .and_then(|value| gltf::json::deserialize::from_value::<TextureTransform>(value.clone()).ok());
+        match transform {
+            Some(transform) => Self {
+                tex_coord: transform.tex_coord.unwrap_or(tex_coord),
+                transform: UvTransform::from_gltf(transform.offset.0, transform.rotation.0, transform.scale.0),
+            },
+            None => Self { tex_coord, transform: UvTransform::IDENTITY },
+        }
+    }
+}
+
+/// A loaded model: geometry plus any material textures it carries
+pub struct LoadedModel {
+    pub mesh: MeshData,
+    pub textures: ModelTextures,
+}
+
+/// Check if a path has a supported model extension
+pub fn is_model_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| MODEL_EXTENSIONS.iter().any(|ext| e.eq_ignore_ascii_case(ext)))
+        .unwrap_or(false)
+}
+
+/// Load a model file, picking the loader from the extension
+pub fn load_model(path: &Path) -> Result<LoadedModel, anyhow::Error> {
+    let extension = path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "obj" => Ok(LoadedModel {
+            mesh: load_obj(path)?,
+            textures: ModelTextures::default(),
+        }),
+        "gltf" | "glb" => load_gltf(path),
+        _ => anyhow::bail!("unsupported model format: {}", path.display()),
+    }
+}
+
+/// Load a Wavefront OBJ file. All objects/groups are merged into one mesh;
+/// polygons are triangulated and materials are ignored.
+pub fn load_obj(path: &Path) -> Result<MeshData, anyhow::Error> {
+    let (models, _materials) = tobj::load_obj(
+        path,
+        &tobj::LoadOptions {
+            single_index: true,
+            triangulate: true,
+            ignore_points: true,
+            ignore_lines: true,
+        },
+    )
+    .map_err(|e| anyhow::anyhow!("failed to parse {}: {}", path.display(), e))?;
+
+    let mut attributes = MeshAttributes::default();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+    // A single object without normals/UVs means the merged mesh has none
+    let mut has_normals = true;
+    let mut has_uvs = true;
+
+    for model in &models {
+        let mesh = &model.mesh;
+        let vertex_count = mesh.positions.len() / 3;
+        let base = attributes.positions.len() as u32;
+
+        attributes.positions.extend(mesh.positions.chunks_exact(3).map(|p| [p[0], p[1], p[2]]));
+        if mesh.normals.len() == vertex_count * 3 {
+            normals.extend(mesh.normals.chunks_exact(3).map(|n| [n[0], n[1], n[2]]));
+        } else {
+            has_normals = false;
+        }
+        if mesh.texcoords.len() == vertex_count * 2 {
+            // OBJ puts v = 0 at the bottom of the image
+            uvs.extend(mesh.texcoords.chunks_exact(2).map(|t| [t[0], 1.0 - t[1]]));
+        } else {
+            has_uvs = false;
+        }
+        attributes.indices.extend(mesh.indices.iter().map(|i| base + i));
+    }
+
+    if attributes.indices.is_empty() {
+        anyhow::bail!("{} contains no triangles", path.display());
+    }
+    if !has_normals {
+        log::info!("{} has no normals, using flat face normals", path.display());
+    }
+    if !has_uvs {
+        log::info!("{} has no texture coordinates, using planar UVs", path.display());
+    }
+    attributes.normals = has_normals.then_some(normals);
+    attributes.uvs = has_uvs.then_some(uvs);
+
+    Ok(attributes.into_mesh())
+}
+
+/// Load a glTF/GLB file. Triangle primitives of all meshes in the default scene
+/// are merged with their node transforms applied. Textures of the first
+/// primitive's material are returned for the texture slots.
+pub fn load_gltf(path: &Path) -> Result<LoadedModel, anyhow::Error> {
+    let (document, buffers, images) = gltf::import(path)
+        .map_err(|e| anyhow::anyhow!("failed to parse {}: {}", path.display(), e))?;
+
+    let mut builder = GltfMeshBuilder::default();
+    match document.default_scene().or_else(|| document.scenes().next()) {
+        Some(scene) => {
+            for node in scene.nodes() {
+                builder.add_node(&node, Mat4::IDENTITY, &buffers);
+            }
+        }
+        // Files without scenes: take the meshes as they are
+        None => {
+            for mesh in document.meshes() {
+                builder.add_mesh(&mesh, Mat4::IDENTITY, &buffers);
+            }
+        }
+    }
+
+    let textures = builder.material
+        .as_ref()
+        .map(|material| gltf_material_textures(material, &images))
+        .unwrap_or_default();
+    let mesh = builder.finish()
+        .ok_or_else(|| anyhow::anyhow!("{} contains no triangle meshes", path.display()))?;
+    Ok(LoadedModel { mesh, textures })
+}
+
+/// Accumulates glTF primitives into one set of mesh attributes
+#[derive(Default)]
+struct GltfMeshBuilder<'a> {
+    attributes: MeshAttributes,
+    normals: Vec<[f32; 3]>,
+    uvs: Vec<[f32; 2]>,
+    uvs1: Vec<[f32; 2]>,
+    tangents: Vec<[f32; 4]>,
+    // Merged attributes are only kept if every primitive provides them
+    has_normals: Option<bool>,
+    has_uvs: Option<bool>,
+    has_uvs1: Option<bool>,
+    has_tangents: Option<bool>,
+    /// Material of the first primitive
+    material: Option<gltf::Material<'a>>,
+}
+
+impl<'a> GltfMeshBuilder<'a> {
+    fn add_node(&mut self, node: &gltf::Node<'a>, parent: Mat4, buffers: &[gltf::buffer::Data]) {
+        let transform = parent * Mat4::from_cols_array_2d(&node.transform().matrix());
+        if let Some(mesh) = node.mesh() {
+            self.add_mesh(&mesh, transform, buffers);
+        }
+        for child in node.children() {
+            self.add_node(&child, transform, buffers);
+        }
+    }
+
+    fn add_mesh(&mut self, mesh: &gltf::Mesh<'a>, transform: Mat4, buffers: &[gltf::buffer::Data]) {
+        let normal_matrix = Mat3::from_mat4(transform).inverse().transpose();
+        let tangent_matrix = Mat3::from_mat4(transform);
+        // Mirroring transforms flip the triangle winding
+        let flip_winding = transform.determinant() < 0.0;
+
+        for primitive in mesh.primitives() {
+            if primitive.mode() != gltf::mesh::Mode::Triangles {
+                continue;
+            }
+            let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+            let Some(positions) = reader.read_positions() else {
+                continue;
+            };
+            let base = self.attributes.positions.len() as u32;
+            self.attributes.positions.extend(positions.map(|p| transform.transform_point3(Vec3::from(p)).to_array()));
+            let vertex_count = self.attributes.positions.len() - base as usize;
+
+            let normals = reader.read_normals().map(|normals| {
+                normals
+                    .map(|n| (normal_matrix * Vec3::from(n)).normalize_or_zero().to_array())
+                    .collect::<Vec<_>>()
+            });
+            merge(&mut self.normals, &mut self.has_normals, normals, vertex_count);
+            let uvs = reader.read_tex_coords(0).map(|uvs| uvs.into_f32().collect::<Vec<_>>());
+            merge(&mut self.uvs, &mut self.has_uvs, uvs, vertex_count);
+            let uvs1 = reader.read_tex_coords(1).map(|uvs| uvs.into_f32().collect::<Vec<_>>());
+            merge(&mut self.uvs1, &mut self.has_uvs1, uvs1, vertex_count);
+            let tangents = reader.read_tangents().map(|tangents| {
+                tangents
+                    .map(|t| {
+                        let xyz = (tangent_matrix * Vec3::new(t[0], t[1], t[2])).normalize_or_zero();
+                        xyz.extend(t[3]).to_array()
+                    })
+                    .collect::<Vec<_>>()
+            });
+            merge(&mut self.tangents, &mut self.has_tangents, tangents, vertex_count);
+
+            // Indices may be u8/u16/u32; non-indexed primitives are plain triangle lists
+            let indices: Vec<u32> = match reader.read_indices() {
+                Some(indices) => indices.into_u32().collect(),
+                None => (0..vertex_count as u32).collect(),
+            };
+            for triangle in indices.chunks_exact(3) {
+                let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|i| base + i);
+                if flip_winding {
+                    self.attributes.indices.extend([a, c, b]);
+                } else {
+                    self.attributes.indices.extend([a, b, c]);
+                }
+            }
+
+            if self.material.is_none() {
+                self.material = Some(primitive.material());
+            }
+        }
+    }
+
+    fn finish(self) -> Option<MeshData> {
+        if self.attributes.indices.is_empty() {
+            return None;
+        }
+        let keep = |has: Option<bool>| has.unwrap_or(false);
+        let mut attributes = self.attributes;
+        attributes.normals = keep(self.has_normals).then_some(self.normals);
+        attributes.uvs = keep(self.has_uvs).then_some(self.uvs);
+        attributes.uvs1 = keep(self.has_uvs1).then_some(self.uvs1);
+        // Provided tangents are only meaningful together with the normals and UVs they belong to
+        let tangents_usable = keep(self.has_tangents) && attributes.normals.is_some() && attributes.uvs.is_some();
+        attributes.tangents = tangents_usable.then_some(self.tangents);
+        Some(attributes.into_mesh())
+    }
+}
+
+/// Append one primitive's attribute, tracking whether every primitive had it
+fn merge<T>(merged: &mut Vec<T>, has_all: &mut Option<bool>, values: Option<Vec<T>>, vertex_count: usize) {
+    match values {
+        Some(values) if values.len() == vertex_count && has_all.unwrap_or(true) => {
+            merged.extend(values);
+            *has_all = Some(true);
+        }
+        _ => *has_all = Some(false),
+    }
+}
+
+/// Decode the base color, normal, metallic-roughness and emissive textures of a material
+fn gltf_material_textures(material: &gltf::Material, images: &[gltf::image::Data]) -> ModelTextures {
+    let decode = |texture: gltf::Texture| images.get(texture.source().index()).and_then(gltf_image_to_dynamic);
+    let pbr = material.pbr_metallic_roughness();
+
+    let metallic_roughness = pbr.metallic_roughness_texture().and_then(|info| {
+        let source = info.texture().source().index();
+        let mut rgba = decode(info.texture())?.to_rgba8();
+        // The ORM slot reads occlusion from red; glTF only guarantees it there
+        // when the occlusion texture is the same image, so fill it white otherwise
+        let packed_occlusion = material.occlusion_texture()
+            .is_some_and(|occlusion| occlusion.texture().source().index() == source);
+        if !packed_occlusion {
+            for pixel in rgba.pixels_mut() {
+                pixel[0] = 255;
+            }
+        }
+        Some(DynamicImage::ImageRgba8(rgba))
+    });
+
+    // glTF multiplies the emissive texture by the factor; without a texture the factor is the color
+    let emissive_factor = material.emissive_factor();
+    let emissive = if emissive_factor == [0.0; 3] {
+        None
+    } else {
+        match material.emissive_texture() {
+            Some(info) => decode(info.texture()).map(|image| scale_srgb_image(&image, emissive_factor)),
+            None => {
+                let color = emissive_factor.map(linear_to_srgb);
+                Some(DynamicImage::ImageRgb8(image::RgbImage::from_pixel(1, 1, image::Rgb(color))))
+            }
+        }
+    };
+
+    const TEXTURE_TRANSFORM: &str = "KHR_texture_transform";
+    let mappings: Vec<(&str, TextureMapping)> = [
+        ("base color", pbr.base_color_texture().map(|info| TextureMapping::from_info(&info))),
+        ("metallic-roughness", pbr.metallic_roughness_texture().map(|info| TextureMapping::from_info(&info))),
+        ("emissive", material.emissive_texture().map(|info| TextureMapping::from_info(&info))),
+        ("normal", material.normal_texture().map(|normal| {
+            TextureMapping::from_json(normal.tex_coord(), normal.extension_value(TEXTURE_TRANSFORM))
+        })),
+        ("occlusion", material.occlusion_texture().map(|occlusion| {
+            TextureMapping::from_json(occlusion.tex_coord(), occlusion.extension_value(TEXTURE_TRANSFORM))
+        })),
+    ]
+    .into_iter()
+    .filter_map(|(name, mapping)| mapping.map(|mapping| (name, mapping)))
+    .collect();
+
+    // Only occlusion can read UV1, which is never transformed; every other map is
+    // sampled from UV0 through one shared transform, taken from the first such map
+    let on_uv1 = |name: &str, mapping: &TextureMapping| name == "occlusion" && mapping.tex_coord == 1;
+    let uv_transform = mappings.iter()
+        .find(|(name, mapping)| !on_uv1(name, mapping))
+        .map(|(_, mapping)| mapping.transform);
+    let mut warnings = Vec::new();
+    let mut misplaced = Vec::new();
+    for (name, mapping) in &mappings {
+        let applied = if on_uv1(name, mapping) { UvTransform::IDENTITY } else { uv_transform.unwrap_or(UvTransform::IDENTITY) };
+        if mapping.transform != applied {
+            misplaced.push(*name);
+        }
+        if mapping.tex_coord != 0 && !on_uv1(name, mapping) {
+            warnings.push(format!("The {} texture uses TEXCOORD_{}; it is sampled from TEXCOORD_0 instead", name, mapping.tex_coord));
+        }
+    }
+    if !misplaced.is_empty() {
+        warnings.push(format!(
+            "The material's maps have different texture transforms; only one is applied, so the {} map(s) are misaligned",
+            misplaced.join(", "),
+        ));
+    }
+    let occlusion_tex_coord = mappings.iter()
+        .find(|(name, _)| *name == "occlusion")
+        .map(|(_, mapping)| mapping.tex_coord);
+
+    ModelTextures {
+        base_color: pbr.base_color_texture().and_then(|info| decode(info.texture())),
+        normal: material.normal_texture().and_then(|info| decode(info.texture())),
+        metallic_roughness,
+        occlusion_tex_coord,
+        emissive_strength: emissive.is_some().then(|| material.emissive_strength().unwrap_or(1.0)),
+        emissive,
+        uv_transform,
+        warnings,
+    }
+}
+
+/// Multiply an sRGB image by a linear color, as glTF does with its color factors
+fn scale_srgb_image(image: &DynamicImage, factor: [f32; 3]) -> DynamicImage {
+    let lookup: [[u8; 256]; 3] = factor.map(|f| {
+        std::array::from_fn(|value| linear_to_srgb(srgb_to_linear(value as u8) * f))
+    });
+    let mut rgb = image.to_rgb8();
+    for pixel in rgb.pixels_mut() {
+        for (channel, table) in pixel.0.iter_mut().zip(&lookup) {
+            *channel = table[*channel as usize];
+        }
+    }
+    DynamicImage::ImageRgb8(rgb)
+}
+
+fn srgb_to_linear(value: u8) -> f32 {
+    let c = value as f32 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f32) -> u8 {
+    let c = value.clamp(0.0, 1.0);
+    let encoded = if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+    (encoded * 255.0).round() as u8
+}
+
+/// Convert decoded glTF image data; only 8-bit formats are supported
+fn gltf_image_to_dynamic(data: &gltf::image::Data) -> Option<DynamicImage> {
+    use gltf::image::Format;
+    let (width, height, pixels) = (data.width, data.height, data.pixels.clone());
+    match data.format {
+        Format::R8G8B8A8 => image::RgbaImage::from_raw(width, height, pixels).map(DynamicImage::ImageRgba8),
+        Format::R8G8B8 => image::RgbImage::from_raw(width, height, pixels).map(DynamicImage::ImageRgb8),
+        Format::R8 => image::GrayImage::from_raw(width, height, pixels).map(DynamicImage::ImageLuma8),
+        Format::R8G8 => image::GrayAlphaImage::from_raw(width, height, pixels).map(DynamicImage::ImageLumaA8),
+        format => {
+            log::warn!("Skipping embedded texture with unsupported format {:?}", format);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `material_uv` from pbr.wgsl
+    fn viewer_uv(transform: &UvTransform, uv: Vec2) -> Vec2 {
+        let pivot = Vec2::splat(0.5);
+        let tiled = (uv - pivot) / Vec2::from(transform.tile_size);
+        let (sin, cos) = transform.rotation_degrees.to_radians().sin_cos();
+        let rotated = Vec2::new(cos * tiled.x - sin * tiled.y, sin * tiled.x + cos * tiled.y);
+        rotated + pivot + Vec2::from(transform.offset)
+    }
+
+    /// The transform from the KHR_texture_transform specification
+    fn gltf_uv(offset: [f32; 2], rotation: f32, scale: [f32; 2], uv: Vec2) -> Vec2 {
+        let (sin, cos) = rotation.sin_cos();
+        let scaled = uv * Vec2::from(scale);
+        Vec2::new(cos * scaled.x + sin * scaled.y, -sin * scaled.x + cos * scaled.y) + Vec2::from(offset)
+    }
+
+    #[test]
+    fn texture_transform_matches_gltf() {
+        let (offset, rotation, scale) = ([0.25, -0.5], 0.6, [2.0, 0.5]);
+        let transform = UvTransform::from_gltf(offset, rotation, scale);
+        for uv in [Vec2::ZERO, Vec2::ONE, Vec2::new(0.3, 0.8), Vec2::new(-1.5, 2.0)] {
+            let expected = gltf_uv(offset, rotation, scale, uv);
+            assert!(viewer_uv(&transform, uv).abs_diff_eq(expected, 1e-5), "uv {uv}");
+        }
+    }
+
+    #[test]
+    fn json_texture_transform_overrides_tex_coord() {
+        let extension = serde_json::json!({ "offset": [0.5, 0.0], "scale": [2.0, 2.0], "texCoord": 1 });
+        let mapping = TextureMapping::from_json(0, Some(&extension));
+        assert_eq!(mapping.tex_coord, 1);
+        assert_eq!(mapping.transform, UvTransform::from_gltf([0.5, 0.0], 0.0, [2.0, 2.0]));
+
+        let plain = TextureMapping::from_json(1, None);
+        assert_eq!(plain, TextureMapping { tex_coord: 1, transform: UvTransform::IDENTITY });
+    }
+
+    #[test]
+    fn identity_texture_transform_is_default_uv_state() {
+        let transform = UvTransform::from_gltf([0.0, 0.0], 0.0, [1.0, 1.0]);
+        assert_eq!(transform.tile_size, [1.0, 1.0]);
+        assert_eq!(transform.offset, [0.0, 0.0]);
+        assert_eq!(transform.rotation_degrees, 0.0);
+    }
+}
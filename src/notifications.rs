@@ -0,0 +1,122 @@
+//! Toast notifications shown over the 3D viewport
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+use egui::*;
+
+/// Maximum number of toasts shown at once (oldest are dropped first)
+const MAX_NOTIFICATIONS: usize = 5;
+/// Time over which a toast fades out at the end of its lifetime
+const FADE_DURATION: Duration = Duration::from_millis(600);
+
+/// Severity of a notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationLevel {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl NotificationLevel {
+    fn color(&self) -> Color32 {
+        match self {
+            NotificationLevel::Info => Color32::from_rgb(100, 200, 255),
+            NotificationLevel::Success => Color32::from_rgb(120, 220, 120),
+            NotificationLevel::Warning => Color32::from_rgb(255, 200, 80),
+            NotificationLevel::Error => Color32::from_rgb(255, 100, 100),
+        }
+    }
+
+    fn icon(&self) -> &'static str {
+        match self {
+            NotificationLevel::Info => "ℹ",
+            NotificationLevel::Success => "✔",
+            NotificationLevel::Warning => "⚠",
+            NotificationLevel::Error => "✖",
+        }
+    }
+
+    /// Errors stay up longer so they can be read
+    fn lifetime(&self) -> Duration {
+        match self {
+            NotificationLevel::Error => Duration::from_secs(8),
+            NotificationLevel::Warning => Duration::from_secs(6),
+            _ => Duration::from_secs(4),
+        }
+    }
+}
+
+/// A single toast message
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub level: NotificationLevel,
+    pub message: String,
+    created: Instant,
+}
+
+/// Queue of toasts, rendered stacked in the top-right corner
+#[derive(Debug, Default)]
+pub struct Notifications {
+    items: VecDeque<Notification>,
+}
+
+impl Notifications {
+    /// Queue a toast. The message is also written to the log.
+    pub fn push(&mut self, level: NotificationLevel, message: impl Into<String>) {
+        let message = message.into();
+        match level {
+            NotificationLevel::Error => log::error!("{}", message),
+            NotificationLevel::Warning => log::warn!("{}", message),
+            _ => log::info!("{}", message),
+        }
+
+        if self.items.len() >= MAX_NOTIFICATIONS {
+            self.items.pop_front();
+        }
+        self.items.push_back(Notification {
+            level,
+            message,
+            created: Instant::now(),
+        });
+    }
+
+    /// Draw the active toasts starting at `top` and drop expired ones
+    pub fn show(&mut self, ctx: &Context, top: f32) {
+        let now = Instant::now();
+        self.items.retain(|n| now.duration_since(n.created) < n.level.lifetime());
+        if self.items.is_empty() {
+            return;
+        }
+
+        Area::new(Id::new("notifications"))
+            .anchor(Align2::RIGHT_TOP, [-12.0, top])
+            .order(Order::Foreground)
+            .interactable(false)
+            .show(ctx, |ui| {
+                ui.set_max_width(360.0);
+                for notification in self.items.iter().rev() {
+                    let remaining = notification.level.lifetime()
+                        .saturating_sub(now.duration_since(notification.created));
+                    let alpha = (remaining.as_secs_f32() / FADE_DURATION.as_secs_f32()).min(1.0);
+                    let color = notification.level.color();
+
+                    Frame::none()
+                        .fill(Color32::from_rgba_unmultiplied(25, 25, 32, 235).gamma_multiply(alpha))
+                        .stroke(Stroke::new(1.0, color.gamma_multiply(alpha)))
+                        .rounding(6.0)
+                        .inner_margin(Margin::symmetric(10.0, 6.0))
+                        .show(ui, |ui| {
+                            ui.horizontal_wrapped(|ui| {
+                                ui.label(RichText::new(notification.level.icon()).color(color.gamma_multiply(alpha)));
+                                ui.label(
+                                    RichText::new(&notification.message)
+                                        .color(Color32::from_rgb(220, 220, 220).gamma_multiply(alpha)),
+                                );
+                            });
+                        });
+                    ui.add_space(6.0);
+                }
+            });
+    }
+}
@@ -4,6 +4,9 @@ use wgpu::*;
 use wgpu::util::DeviceExt;
 use bytemuck::{Pod, Zeroable};
 use glam::Mat4;
+use crate::render_target::SCENE_DEPTH_FORMAT;
+use crate::shadow::{ShadowMap, SHADOW_MAP_FORMAT};
+use crate::state_wgpu::MAX_SECONDARY_LIGHTS;
 
 /// Uniform buffer for camera/view matrices
 #[repr(C)]
@@ -12,6 +15,7 @@ pub struct CameraUniform {
     pub view_proj: [[f32; 4]; 4],
     pub view: [[f32; 4]; 4],
     pub proj: [[f32; 4]; 4],
+    pub position: [f32; 4],  // Camera world position (w unused)
 }
 
 impl CameraUniform {
@@ -20,6 +24,7 @@ impl CameraUniform {
             view_proj: Mat4::IDENTITY.to_cols_array_2d(),
             view: Mat4::IDENTITY.to_cols_array_2d(),
             proj: Mat4::IDENTITY.to_cols_array_2d(),
+            position: [0.0, 0.0, 0.0, 1.0],
         }
     }
 
@@ -27,6 +32,7 @@ impl CameraUniform {
         self.view_proj = camera.view_proj_matrix().to_cols_array_2d();
         self.view = camera.view_matrix().to_cols_array_2d();
         self.proj = camera.projection_matrix().to_cols_array_2d();
+        self.position = camera.position.extend(1.0).to_array();
     }
 }
 
@@ -50,15 +56,56 @@ impl ModelUniform {
 #[derive(Debug, Clone, Copy)]
 pub struct MaterialUniform {
     pub base_color_tint: [f32; 3],
-    pub _padding0: f32,
+    pub emissive_strength: f32,
     pub metallic: f32,
     pub roughness: f32,
     pub normal_strength: f32,
     pub uv_scale: f32,
     pub view_mode: u32,  // ViewMode as u32
-    pub texture_flags: u32,  // Bit flags: bit 0=base_color, bit 1=normal, bit 2=metallic_roughness, bit 3=ao, bit 4=emissive, bit 5=height
+    pub texture_flags: u32,  // Bit flags: bit 0=base_color, bit 1=normal, bit 2=metallic_roughness, bit 3=ao, bit 4=emissive, bit 5=height, bit 6=ORM occlusion
+    pub material_flags: u32,  // Bit flags: bit 0=occlusion samples UV1, bit 1=flip normal map green (DirectX)
+    pub parallax_steps: u32,  // Ray-march steps for parallax occlusion mapping
     pub light_direction: [f32; 3],  // Light direction (normalized)
-    pub _padding1: f32,  // Padding to maintain 16-byte alignment
+    pub parallax_depth: f32,  // Parallax depth in UV units (0 disables parallax)
+    pub env_rotation: [f32; 2],  // sin/cos of the environment yaw
+    pub normal_debug_space: u32,  // NormalDebugSpace as u32 (0=tangent, 1=world)
+    pub specular_aa_strength: f32,  // 0 disables geometric specular AA
+    pub light_color: [f32; 3],  // Linear RGB color of the directional light
+    pub exposure: f32,  // Linear exposure multiplier applied before tone mapping
+    pub ao_strength: f32,
+    pub shading_flags: u32,  // Bit flags: bit 0=AO also darkens direct light (debug), bit 1=multi-scatter specular
+    pub overlay_flags: u32,  // Bit flags: bit 0=UV tile boundary lines
+    pub environment_flags: u32,  // Bit flags: bit 0=environment map loaded (IBL replaces the fixed ambient)
+    pub uv_tile_line_color: [f32; 3],
+    pub tone_mapping: u32,  // ToneMapping as u32 (0=none, 1=Reinhard, 2=ACES)
+    pub wireframe_color: [f32; 3],
+    pub uv_scale_v: f32,  // uv_scale is the U tile size
+    pub uv_offset: [f32; 2],
+    pub uv_rotation: [f32; 2],  // sin/cos of the UV rotation
+    pub displacement_scale: f32,
+    pub displacement_midpoint: f32,  // Height value that leaves the surface in place
+    pub displacement_bias: f32,
+    pub displacement_enabled: u32,  // 1 = vertices move along the normal by the height map
+    pub displacement_clamp: [f32; 2],  // Min/max displacement
+    pub light_intensity: f32,  // Radiance of the directional light (scales light_color)
+    pub secondary_light_count: u32,  // Used entries of secondary_lights
+    pub ambient_color: [f32; 3],  // Linear RGB of the constant ambient light (without an environment map)
+    pub ambient_intensity: f32,
+    pub secondary_lights: [SecondaryLightUniform; MAX_SECONDARY_LIGHTS],  // Unshadowed fill and rim lights
+    pub tessellation_debug: u32,  // 0=off, 1=triangle density heatmap
+    pub tangent_frame_axis: u32,  // TangentFrameAxis as u32 (0=tangent, 1=bitangent, 2=normal)
+    pub displacement_distance_scale: f32,  // Camera distance multiplier before the falloff curve
+    pub displacement_falloff: u32,  // TessellationFalloff as u32 (0=linear, 1=quadratic, 2=exponential)
+}
+
+/// One additional directional light in the material uniform
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, Pod, Zeroable)]
+pub struct SecondaryLightUniform {
+    pub direction: [f32; 3],  // Towards the light (normalized)
+    pub intensity: f32,
+    pub color: [f32; 3],  // Linear RGB
+    pub _padding: f32,
 }
 
 unsafe impl bytemuck::Pod for MaterialUniform {}
@@ -68,15 +115,46 @@ impl MaterialUniform {
     pub fn new() -> Self {
         Self {
             base_color_tint: [0.8, 0.8, 0.8],
-            _padding0: 0.0,
+            emissive_strength: 1.0,
             metallic: 0.0,
             roughness: 0.5,
             normal_strength: 1.0,
             uv_scale: 1.0,
             view_mode: 0,  // Lit
             texture_flags: 0,
+            material_flags: 0,
+            parallax_steps: 16,
             light_direction: [-1.0, -1.0, -1.0],  // Default light direction
-            _padding1: 0.0,
+            parallax_depth: 0.0,
+            env_rotation: [0.0, 1.0],
+            normal_debug_space: 0,
+            specular_aa_strength: 0.0,
+            light_color: [1.0, 1.0, 1.0],
+            exposure: 1.0,
+            ao_strength: 1.0,
+            shading_flags: 0,
+            overlay_flags: 0,
+            environment_flags: 0,
+            uv_tile_line_color: [1.0, 1.0, 0.0],
+            tone_mapping: 0,
+            wireframe_color: [0.1, 0.1, 0.1],
+            uv_scale_v: 1.0,
+            uv_offset: [0.0, 0.0],
+            uv_rotation: [0.0, 1.0],
+            displacement_scale: 0.0,
+            displacement_midpoint: 0.5,
+            displacement_bias: 0.0,
+            displacement_enabled: 0,
+            displacement_clamp: [-1.0, 1.0],
+            light_intensity: 3.0,
+            secondary_light_count: 0,
+            ambient_color: [1.0, 1.0, 1.0],
+            ambient_intensity: 0.3,
+            secondary_lights: [SecondaryLightUniform::default(); MAX_SECONDARY_LIGHTS],
+            tessellation_debug: 0,
+            tangent_frame_axis: 0,
+            displacement_distance_scale: 0.0,
+            displacement_falloff: 0,
         }
     }
 }
@@ -84,6 +162,21 @@ impl MaterialUniform {
 /// Render pipeline and resources
 pub struct RenderPipeline {
     pub pipeline: wgpu::RenderPipeline,
+    /// Same pipeline rasterizing triangle edges; `None` without `POLYGON_MODE_LINE`
+    pub wireframe_pipeline: Option<wgpu::RenderPipeline>,
+    /// Depth-only pass run before the color pass so each pixel is shaded once
+    pub depth_prepass_pipeline: wgpu::RenderPipeline,
+    /// Color pass variant testing `Equal` against the pre-pass depth
+    pub depth_equal_pipeline: wgpu::RenderPipeline,
+    /// Renders the mesh depth from the light into the shadow map
+    pub shadow_pipeline: wgpu::RenderPipeline,
+    pub shadow_map: ShadowMap,
+    shader: ShaderModule,
+    pipeline_layout: PipelineLayout,
+    shadow_pipeline_layout: PipelineLayout,
+    sample_count: u32,
+    /// Camera + model layout, shared with overlay pipelines drawn in the same pass
+    pub camera_bind_group_layout: BindGroupLayout,
     pub camera_uniform: CameraUniform,
     pub camera_buffer: Buffer,
     pub camera_bind_group: BindGroup,
@@ -97,8 +190,9 @@ pub struct RenderPipeline {
 impl RenderPipeline {
     pub fn new(
         device: &Device,
-        shader: &ShaderModule,
+        shader: ShaderModule,
         surface_format: TextureFormat,
+        sample_count: u32,
     ) -> Result<Self, anyhow::Error> {
         // Create camera uniform buffer
         let camera_uniform = CameraUniform::new();
@@ -170,61 +264,8 @@ impl RenderPipeline {
             usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
         });
 
-        // Create texture bind group layout (will be provided by TextureSet)
-        // Note: This layout should match TextureSet::bind_group_layout
-        let texture_bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
-            entries: &[
-                BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: ShaderStages::FRAGMENT,
-                    ty: BindingType::Texture {
-                        multisampled: false,
-                        view_dimension: TextureViewDimension::D2,
-                        sample_type: TextureSampleType::Float { filterable: true },
-                    },
-                    count: None,
-                },
-                BindGroupLayoutEntry {
-                    binding: 1,
-                    visibility: ShaderStages::FRAGMENT,
-                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
-                    count: None,
-                },
-                BindGroupLayoutEntry {
-                    binding: 2,
-                    visibility: ShaderStages::FRAGMENT,
-                    ty: BindingType::Texture {
-                        multisampled: false,
-                        view_dimension: TextureViewDimension::D2,
-                        sample_type: TextureSampleType::Float { filterable: true },
-                    },
-                    count: None,
-                },
-                BindGroupLayoutEntry {
-                    binding: 3,
-                    visibility: ShaderStages::FRAGMENT,
-                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
-                    count: None,
-                },
-                BindGroupLayoutEntry {
-                    binding: 4,
-                    visibility: ShaderStages::FRAGMENT,
-                    ty: BindingType::Texture {
-                        multisampled: false,
-                        view_dimension: TextureViewDimension::D2,
-                        sample_type: TextureSampleType::Float { filterable: true },
-                    },
-                    count: None,
-                },
-                BindGroupLayoutEntry {
-                    binding: 5,
-                    visibility: ShaderStages::FRAGMENT,
-                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
-                    count: None,
-                },
-            ],
-            label: Some("texture_bind_group_layout"),
-        });
+        // Texture bind group layout is owned by TextureSet (material textures + environment)
+        let texture_bind_group_layout = crate::texture_manager::TextureSet::bind_group_layout(device);
 
         // Create material bind group layout
         // Make it accessible in both vertex and fragment stages for UV scale
@@ -252,59 +293,39 @@ impl RenderPipeline {
             label: Some("material_bind_group"),
         });
 
+        let shadow_map = ShadowMap::new(device);
+
         // Create render pipeline layout
         let render_pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
             label: Some("Render Pipeline Layout"),
-            bind_group_layouts: &[&camera_bind_group_layout, &texture_bind_group_layout, &material_bind_group_layout],
+            bind_group_layouts: &[&camera_bind_group_layout, &texture_bind_group_layout, &material_bind_group_layout, &shadow_map.bind_group_layout],
             push_constant_ranges: &[],
         });
-
-        // Create render pipeline
-        let pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
-            label: Some("Render Pipeline"),
-            layout: Some(&render_pipeline_layout),
-            vertex: VertexState {
-                module: shader,
-                entry_point: "vs_main",
-                buffers: &[crate::mesh_wgpu::Vertex::desc()],
-                compilation_options: Default::default(),
-            },
-            fragment: Some(FragmentState {
-                module: shader,
-                entry_point: "fs_main",
-                targets: &[Some(ColorTargetState {
-                    format: surface_format,
-                    blend: Some(BlendState::REPLACE),
-                    write_mask: ColorWrites::ALL,
-                })],
-                compilation_options: Default::default(),
-            }),
-            primitive: PrimitiveState {
-                topology: PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: FrontFace::Ccw,
-                cull_mode: Some(Face::Back),
-                polygon_mode: PolygonMode::Fill,
-                unclipped_depth: false,
-                conservative: false,
-            },
-            depth_stencil: Some(DepthStencilState {
-                format: TextureFormat::Depth32Float,
-                depth_write_enabled: true,
-                depth_compare: CompareFunction::Less,
-                stencil: StencilState::default(),
-                bias: DepthBiasState::default(),
-            }),
-            multisample: MultisampleState {
-                count: 1,
-                mask: !0,
-                alpha_to_coverage_enabled: false,
-            },
-            multiview: None,
+        // The shadow pass writes the shadow map, so its group 3 holds only the uniform
+        let shadow_pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("Shadow Pipeline Layout"),
+            bind_group_layouts: &[&camera_bind_group_layout, &texture_bind_group_layout, &material_bind_group_layout, &shadow_map.pass_bind_group_layout],
+            push_constant_ranges: &[],
         });
 
+        let pipeline = create_pbr_pipeline(device, &render_pipeline_layout, &shader, surface_format, sample_count, PolygonMode::Fill, false);
+        let wireframe_pipeline = create_wireframe_pipeline(device, &render_pipeline_layout, &shader, surface_format, sample_count);
+        let depth_prepass_pipeline = create_depth_prepass_pipeline(device, &render_pipeline_layout, &shader, sample_count);
+        let depth_equal_pipeline = create_pbr_pipeline(device, &render_pipeline_layout, &shader, surface_format, sample_count, PolygonMode::Fill, true);
+        let shadow_pipeline = create_shadow_pipeline(device, &shadow_pipeline_layout, &shader);
+
         Ok(Self {
             pipeline,
+            wireframe_pipeline,
+            depth_prepass_pipeline,
+            depth_equal_pipeline,
+            shadow_pipeline,
+            shadow_map,
+            shader,
+            pipeline_layout: render_pipeline_layout,
+            shadow_pipeline_layout,
+            sample_count,
+            camera_bind_group_layout,
             camera_uniform,
             camera_buffer,
             camera_bind_group,
@@ -316,6 +337,36 @@ impl RenderPipeline {
         })
     }
 
+    /// Recreate the pipeline for a new color format or MSAA sample count
+    pub fn rebuild(&mut self, device: &Device, format: TextureFormat, sample_count: u32) {
+        self.pipeline = create_pbr_pipeline(device, &self.pipeline_layout, &self.shader, format, sample_count, PolygonMode::Fill, false);
+        self.wireframe_pipeline = create_wireframe_pipeline(device, &self.pipeline_layout, &self.shader, format, sample_count);
+        self.depth_prepass_pipeline = create_depth_prepass_pipeline(device, &self.pipeline_layout, &self.shader, sample_count);
+        self.depth_equal_pipeline = create_pbr_pipeline(device, &self.pipeline_layout, &self.shader, format, sample_count, PolygonMode::Fill, true);
+        self.sample_count = sample_count;
+    }
+
+    /// Swap in a new shader module (shader hot-reload). On a validation error
+    /// the previous pipeline stays in use.
+    pub fn recreate(&mut self, device: &Device, shader: ShaderModule, format: TextureFormat) -> Result<(), anyhow::Error> {
+        device.push_error_scope(ErrorFilter::Validation);
+        let pipeline = create_pbr_pipeline(device, &self.pipeline_layout, &shader, format, self.sample_count, PolygonMode::Fill, false);
+        let wireframe_pipeline = create_wireframe_pipeline(device, &self.pipeline_layout, &shader, format, self.sample_count);
+        let depth_prepass_pipeline = create_depth_prepass_pipeline(device, &self.pipeline_layout, &shader, self.sample_count);
+        let depth_equal_pipeline = create_pbr_pipeline(device, &self.pipeline_layout, &shader, format, self.sample_count, PolygonMode::Fill, true);
+        let shadow_pipeline = create_shadow_pipeline(device, &self.shadow_pipeline_layout, &shader);
+        if let Some(error) = pollster::block_on(device.pop_error_scope()) {
+            anyhow::bail!("{}", error);
+        }
+        self.pipeline = pipeline;
+        self.wireframe_pipeline = wireframe_pipeline;
+        self.depth_prepass_pipeline = depth_prepass_pipeline;
+        self.depth_equal_pipeline = depth_equal_pipeline;
+        self.shadow_pipeline = shadow_pipeline;
+        self.shader = shader;
+        Ok(())
+    }
+
     pub fn update_camera(&mut self, queue: &Queue, camera: &crate::camera_wgpu::Camera) {
         self.camera_uniform.update_view_proj(camera);
         queue.write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&[self.camera_uniform]));
@@ -331,27 +382,56 @@ impl RenderPipeline {
         queue: &Queue,
         material: &crate::state_wgpu::MaterialParams,
         view_mode: crate::state_wgpu::ViewMode,
+        normal_debug_space: crate::state_wgpu::NormalDebugSpace,
         loaded_textures: &crate::state_wgpu::LoadedTextures,
+        enabled_maps: &crate::state_wgpu::EnabledMaps,
     ) {
         self.material_uniform.base_color_tint = material.base_color_tint;
         self.material_uniform.metallic = material.metallic_multiplier;
         self.material_uniform.roughness = material.roughness_multiplier;
         self.material_uniform.normal_strength = material.normal_strength;
-        self.material_uniform.uv_scale = material.uv_scale;
+        [self.material_uniform.uv_scale, self.material_uniform.uv_scale_v] = material.uv_tile_size();
+        self.material_uniform.uv_offset = material.uv_offset;
+        let (uv_sin, uv_cos) = material.uv_rotation_degrees.to_radians().sin_cos();
+        self.material_uniform.uv_rotation = [uv_sin, uv_cos];
+        self.material_uniform.ao_strength = material.ao_strength;
+        self.material_uniform.emissive_strength = material.emissive_strength;
+        self.material_uniform.parallax_depth = material.displacement_strength;
+        self.material_uniform.parallax_steps = material.parallax_steps;
         
         // Set view mode as u32
         self.material_uniform.view_mode = view_mode as u32;
+        self.material_uniform.normal_debug_space = normal_debug_space as u32;
         
         // Pack texture availability flags into a u32
         let mut flags = 0u32;
         if loaded_textures.base_color { flags |= 1 << 0; }
         if loaded_textures.normal { flags |= 1 << 1; }
         if loaded_textures.metallic || loaded_textures.orm { flags |= 1 << 2; }
-        if loaded_textures.ao || loaded_textures.orm { flags |= 1 << 3; }
+        if loaded_textures.ao { flags |= 1 << 3; }
         if loaded_textures.emissive { flags |= 1 << 4; }
         if loaded_textures.height { flags |= 1 << 5; }
+        // Without a separate AO map, occlusion comes from the red channel of a packed ORM map
+        if loaded_textures.orm { flags |= 1 << 6; }
+        // Bits 8+ mirror the ones above for maps ignored by Lit shading
+        let mut ignored = 0u32;
+        if !enabled_maps.base_color { ignored |= 1 << 0; }
+        if !enabled_maps.normal { ignored |= 1 << 1; }
+        if !enabled_maps.metallic_roughness { ignored |= 1 << 2; }
+        if !enabled_maps.ao { ignored |= (1 << 3) | (1 << 6); }
+        if !enabled_maps.emissive { ignored |= 1 << 4; }
+        if !enabled_maps.height { ignored |= 1 << 5; }
+        flags |= ignored << 8;
         self.material_uniform.texture_flags = flags;
         
+        // Pack per-material options
+        let mut material_flags = 0u32;
+        if material.occlusion_uses_uv1 { material_flags |= 1 << 0; }
+        if material.flip_normal_y { material_flags |= 1 << 1; }
+        if material.invert_roughness { material_flags |= 1 << 2; }
+        if material.invert_ao { material_flags |= 1 << 3; }
+        self.material_uniform.material_flags = material_flags;
+        
         queue.write_buffer(&self.material_buffer, 0, bytemuck::cast_slice(&[self.material_uniform]));
     }
     
@@ -364,6 +444,279 @@ impl RenderPipeline {
         self.material_uniform.light_direction = [normalized.x, normalized.y, normalized.z];
         queue.write_buffer(&self.material_buffer, 0, bytemuck::cast_slice(&[self.material_uniform]));
     }
+    
+    /// Upload the key light (direction, color, intensity), the ambient light and the
+    /// secondary lights; only the first `MAX_SECONDARY_LIGHTS` of those are used
+    pub fn update_light(
+        &mut self,
+        queue: &Queue,
+        light: &crate::state_wgpu::LightParams,
+        secondary_lights: &[crate::state_wgpu::LightParams],
+    ) {
+        let direction = light.direction.normalize();
+        self.material_uniform.light_direction = [direction.x, direction.y, direction.z];
+        self.material_uniform.light_color = light.color;
+        self.material_uniform.light_intensity = light.intensity;
+        self.material_uniform.ambient_color = light.ambient_color;
+        self.material_uniform.ambient_intensity = light.ambient_intensity;
+        let secondary_lights = &secondary_lights[..secondary_lights.len().min(MAX_SECONDARY_LIGHTS)];
+        self.material_uniform.secondary_light_count = secondary_lights.len() as u32;
+        self.material_uniform.secondary_lights = [SecondaryLightUniform::default(); MAX_SECONDARY_LIGHTS];
+        for (uniform, light) in self.material_uniform.secondary_lights.iter_mut().zip(secondary_lights) {
+            *uniform = SecondaryLightUniform {
+                direction: light.direction.normalize_or_zero().to_array(),
+                intensity: light.intensity,
+                color: light.color,
+                _padding: 0.0,
+            };
+        }
+        queue.write_buffer(&self.material_buffer, 0, bytemuck::cast_slice(&[self.material_uniform]));
+    }
+    
+    pub fn update_overlay(
+        &mut self,
+        queue: &Queue,
+        overlay: &crate::state_wgpu::OverlayParams,
+    ) {
+        let mut overlay_flags = 0u32;
+        if overlay.uv_tile_lines { overlay_flags |= 1 << 0; }
+        self.material_uniform.overlay_flags = overlay_flags;
+        self.material_uniform.uv_tile_line_color = overlay.uv_tile_line_color;
+        self.material_uniform.wireframe_color = overlay.wireframe_color;
+        queue.write_buffer(&self.material_buffer, 0, bytemuck::cast_slice(&[self.material_uniform]));
+    }
+    
+    pub fn update_displacement(
+        &mut self,
+        queue: &Queue,
+        displacement: &crate::state_wgpu::GpuTessellationParams,
+    ) {
+        self.material_uniform.displacement_enabled = displacement.enabled as u32;
+        self.material_uniform.displacement_scale = displacement.displacement_scale;
+        self.material_uniform.displacement_midpoint = displacement.displacement_midpoint;
+        self.material_uniform.displacement_bias = displacement.displacement_bias;
+        self.material_uniform.displacement_clamp = [displacement.displacement_clamp_min, displacement.displacement_clamp_max];
+        self.material_uniform.displacement_distance_scale = displacement.distance_scale;
+        self.material_uniform.displacement_falloff = displacement.distance_falloff as u32;
+        self.material_uniform.tessellation_debug =
+            (displacement.debug_visualization == crate::state_wgpu::TessellationDebugMode::TessellationDensity) as u32;
+        queue.write_buffer(&self.material_buffer, 0, bytemuck::cast_slice(&[self.material_uniform]));
+    }
+    
+    /// Vector of the tangent frame shown by the Tangents view mode
+    pub fn update_tangent_frame_axis(&mut self, queue: &Queue, axis: crate::state_wgpu::TangentFrameAxis) {
+        self.material_uniform.tangent_frame_axis = axis as u32;
+        queue.write_buffer(&self.material_buffer, 0, bytemuck::cast_slice(&[self.material_uniform]));
+    }
+    
+    pub fn update_environment(
+        &mut self,
+        queue: &Queue,
+        environment: &crate::state_wgpu::EnvironmentParams,
+    ) {
+        let (sin, cos) = environment.rotation_degrees.to_radians().sin_cos();
+        self.material_uniform.env_rotation = [sin, cos];
+        self.material_uniform.environment_flags = if environment.path.is_some() { 1 << 0 } else { 0 };
+        queue.write_buffer(&self.material_buffer, 0, bytemuck::cast_slice(&[self.material_uniform]));
+    }
+    
+    pub fn update_shading(
+        &mut self,
+        queue: &Queue,
+        shading: &crate::state_wgpu::ShadingParams,
+    ) {
+        self.material_uniform.specular_aa_strength = if shading.specular_aa {
+            shading.specular_aa_strength
+        } else {
+            0.0
+        };
+        
+        let mut shading_flags = 0u32;
+        if shading.ao_affects_direct { shading_flags |= 1 << 0; }
+        if shading.multi_scatter { shading_flags |= 1 << 1; }
+        self.material_uniform.shading_flags = shading_flags;
+        self.material_uniform.exposure = shading.exposure();
+        self.material_uniform.tone_mapping = shading.tone_mapping as u32;
+        queue.write_buffer(&self.material_buffer, 0, bytemuck::cast_slice(&[self.material_uniform]));
+    }
+}
+
+/// Line-mode variant of the PBR pipeline, if the device can rasterize polygons as lines
+fn create_wireframe_pipeline(
+    device: &Device,
+    layout: &PipelineLayout,
+    shader: &ShaderModule,
+    format: TextureFormat,
+    sample_count: u32,
+) -> Option<wgpu::RenderPipeline> {
+    device
+        .features()
+        .contains(Features::POLYGON_MODE_LINE)
+        .then(|| create_pbr_pipeline(device, layout, shader, format, sample_count, PolygonMode::Line, false))
+}
+
+/// Depth-only variant of the PBR pipeline for the pre-pass. It shares `vs_main`
+/// (including height displacement) so the depth matches the color pass exactly.
+fn create_depth_prepass_pipeline(
+    device: &Device,
+    layout: &PipelineLayout,
+    shader: &ShaderModule,
+    sample_count: u32,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&RenderPipelineDescriptor {
+        label: Some("Depth Pre-pass Pipeline"),
+        layout: Some(layout),
+        vertex: VertexState {
+            module: shader,
+            entry_point: "vs_main",
+            buffers: &[
+                crate::mesh_wgpu::Vertex::desc(),
+                crate::instance::InstanceRaw::desc(),
+                crate::mesh_buffer::MeshBuffer::edge_length_desc(),
+            ],
+            compilation_options: Default::default(),
+        },
+        fragment: None,
+        primitive: PrimitiveState {
+            topology: PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: FrontFace::Ccw,
+            cull_mode: Some(Face::Back),
+            polygon_mode: PolygonMode::Fill,
+            unclipped_depth: false,
+            conservative: false,
+        },
+        depth_stencil: Some(DepthStencilState {
+            format: SCENE_DEPTH_FORMAT,
+            depth_write_enabled: true,
+            depth_compare: CompareFunction::Less,
+            stencil: StencilState::default(),
+            bias: DepthBiasState::default(),
+        }),
+        multisample: MultisampleState {
+            count: sample_count,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview: None,
+    })
 }
 
+/// Depth-only pipeline rendering the mesh from the light (`vs_shadow`). Faces are
+/// not culled so open meshes such as the plane still cast shadows, and a slope-scaled
+/// depth bias keeps surfaces from shadowing themselves.
+fn create_shadow_pipeline(
+    device: &Device,
+    layout: &PipelineLayout,
+    shader: &ShaderModule,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&RenderPipelineDescriptor {
+        label: Some("Shadow Pipeline"),
+        layout: Some(layout),
+        vertex: VertexState {
+            module: shader,
+            entry_point: "vs_shadow",
+            buffers: &[crate::mesh_wgpu::Vertex::desc(), crate::instance::InstanceRaw::desc()],
+            compilation_options: Default::default(),
+        },
+        fragment: None,
+        primitive: PrimitiveState {
+            topology: PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: FrontFace::Ccw,
+            cull_mode: None,
+            polygon_mode: PolygonMode::Fill,
+            unclipped_depth: false,
+            conservative: false,
+        },
+        depth_stencil: Some(DepthStencilState {
+            format: SHADOW_MAP_FORMAT,
+            depth_write_enabled: true,
+            depth_compare: CompareFunction::Less,
+            stencil: StencilState::default(),
+            bias: DepthBiasState { constant: 2, slope_scale: 2.0, clamp: 0.0 },
+        }),
+        multisample: MultisampleState::default(),
+        multiview: None,
+    })
+}
 
+/// Create the PBR render pipeline for a color format and MSAA sample count.
+/// `PolygonMode::Line` draws the triangle edges in the flat wireframe color,
+/// biased towards the camera so they win the depth test against the filled surface.
+/// With `after_prepass` only fragments matching the pre-pass depth are shaded.
+fn create_pbr_pipeline(
+    device: &Device,
+    layout: &PipelineLayout,
+    shader: &ShaderModule,
+    format: TextureFormat,
+    sample_count: u32,
+    polygon_mode: PolygonMode,
+    after_prepass: bool,
+) -> wgpu::RenderPipeline {
+    let wireframe = polygon_mode == PolygonMode::Line;
+    let label = if wireframe {
+        "Wireframe Pipeline"
+    } else if after_prepass {
+        "Render Pipeline (Depth Equal)"
+    } else {
+        "Render Pipeline"
+    };
+    let depth_compare = if wireframe {
+        CompareFunction::LessEqual
+    } else if after_prepass {
+        CompareFunction::Equal
+    } else {
+        CompareFunction::Less
+    };
+    device.create_render_pipeline(&RenderPipelineDescriptor {
+        label: Some(label),
+        layout: Some(layout),
+        vertex: VertexState {
+            module: shader,
+            entry_point: "vs_main",
+            buffers: &[
+                crate::mesh_wgpu::Vertex::desc(),
+                crate::instance::InstanceRaw::desc(),
+                crate::mesh_buffer::MeshBuffer::edge_length_desc(),
+            ],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(FragmentState {
+            module: shader,
+            entry_point: if wireframe { "fs_wireframe" } else { "fs_main" },
+            targets: &[Some(ColorTargetState {
+                format,
+                blend: Some(BlendState::REPLACE),
+                write_mask: ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: PrimitiveState {
+            topology: PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: FrontFace::Ccw,
+            cull_mode: Some(Face::Back),
+            polygon_mode,
+            unclipped_depth: false,
+            conservative: false,
+        },
+        depth_stencil: Some(DepthStencilState {
+            format: SCENE_DEPTH_FORMAT,
+            depth_write_enabled: !wireframe && !after_prepass,
+            depth_compare,
+            stencil: StencilState::default(),
+            bias: if wireframe {
+                DepthBiasState { constant: -2, slope_scale: -1.0, clamp: 0.0 }
+            } else {
+                DepthBiasState::default()
+            },
+        }),
+        multisample: MultisampleState {
+            count: sample_count,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview: None,
+    })
+}
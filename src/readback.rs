@@ -0,0 +1,183 @@
+//! Asynchronous GPU → CPU readback for screenshots
+//!
+//! A capture records a texture-to-buffer copy into the frame's encoder. Once the
+//! frame is submitted the buffer is mapped asynchronously, and `poll` checks for
+//! completion on later frames with `Maintain::Poll` so the render thread never
+//! waits on the GPU. Encoding and writing the image happens on a worker thread.
+
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, OnceLock};
+use wgpu::*;
+use crate::notifications::{NotificationLevel, Notifications};
+
+/// A copy in flight, waiting for its buffer to be mapped
+struct PendingReadback {
+    buffer: Buffer,
+    width: u32,
+    height: u32,
+    padded_bytes_per_row: u32,
+    /// Source texture stores BGRA and needs swizzling to RGBA
+    bgra: bool,
+    path: PathBuf,
+    /// Filled in by the `map_async` callback
+    map_result: Arc<OnceLock<Result<(), BufferAsyncError>>>,
+    map_requested: bool,
+}
+
+/// Outcome of a worker thread writing an image
+type SaveResult = Result<PathBuf, String>;
+
+/// Queue of in-flight captures and the files being written
+pub struct ReadbackManager {
+    pending: Vec<PendingReadback>,
+    results_tx: Sender<SaveResult>,
+    results_rx: Receiver<SaveResult>,
+}
+
+impl Default for ReadbackManager {
+    fn default() -> Self {
+        let (results_tx, results_rx) = mpsc::channel();
+        Self {
+            pending: Vec::new(),
+            results_tx,
+            results_rx,
+        }
+    }
+}
+
+impl ReadbackManager {
+    /// Record a copy of `texture` into `encoder`; the image is written to `path`
+    /// a few frames after the encoder is submitted.
+    pub fn capture(
+        &mut self,
+        device: &Device,
+        encoder: &mut CommandEncoder,
+        texture: &Texture,
+        path: PathBuf,
+    ) -> Result<(), anyhow::Error> {
+        let bgra = match texture.format() {
+            TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => false,
+            TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => true,
+            format => anyhow::bail!("screenshots of {:?} textures are not supported", format),
+        };
+
+        let width = texture.width();
+        let height = texture.height();
+        // Buffer rows must be aligned to 256 bytes for texture copies
+        let padded_bytes_per_row = (4 * width).next_multiple_of(COPY_BYTES_PER_ROW_ALIGNMENT);
+
+        let buffer = device.create_buffer(&BufferDescriptor {
+            label: Some("screenshot_readback_buffer"),
+            size: padded_bytes_per_row as BufferAddress * height as BufferAddress,
+            usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            ImageCopyBuffer {
+                buffer: &buffer,
+                layout: ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            texture.size(),
+        );
+
+        self.pending.push(PendingReadback {
+            buffer,
+            width,
+            height,
+            padded_bytes_per_row,
+            bgra,
+            path,
+            map_result: Arc::new(OnceLock::new()),
+            map_requested: false,
+        });
+        Ok(())
+    }
+
+    /// Start mapping the buffers of captures recorded this frame.
+    /// Must be called after the encoder holding the copies has been submitted.
+    pub fn after_submit(&mut self) {
+        for readback in self.pending.iter_mut().filter(|r| !r.map_requested) {
+            let map_result = Arc::clone(&readback.map_result);
+            readback.buffer.slice(..).map_async(MapMode::Read, move |result| {
+                let _ = map_result.set(result);
+            });
+            readback.map_requested = true;
+        }
+    }
+
+    /// Hand finished copies to worker threads and report written files.
+    /// Never blocks: mapping progress is only polled.
+    pub fn poll(&mut self, device: &Device, notifications: &mut Notifications) {
+        if self.pending.iter().any(|r| r.map_requested) {
+            device.poll(Maintain::Poll);
+        }
+
+        let (finished, still_pending) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition(|r| r.map_result.get().is_some());
+        self.pending = still_pending;
+
+        for readback in finished {
+            if matches!(readback.map_result.get(), Some(Ok(()))) {
+                self.start_save(readback);
+            } else {
+                notifications.push(
+                    NotificationLevel::Error,
+                    format!("Screenshot failed: could not read back {}", readback.path.display()),
+                );
+            }
+        }
+
+        while let Ok(result) = self.results_rx.try_recv() {
+            match result {
+                Ok(path) => notifications.push(
+                    NotificationLevel::Success,
+                    format!("Saved screenshot to {}", path.display()),
+                ),
+                Err(e) => notifications.push(NotificationLevel::Error, format!("Screenshot failed: {}", e)),
+            }
+        }
+    }
+
+    /// Copy the mapped rows out (dropping row padding) and write the file on a worker thread
+    fn start_save(&mut self, readback: PendingReadback) {
+        let row_bytes = (4 * readback.width) as usize;
+        let mut pixels = Vec::with_capacity(row_bytes * readback.height as usize);
+        {
+            let mapped = readback.buffer.slice(..).get_mapped_range();
+            for row in mapped.chunks_exact(readback.padded_bytes_per_row as usize) {
+                pixels.extend_from_slice(&row[..row_bytes]);
+            }
+        }
+        readback.buffer.unmap();
+
+        let PendingReadback { width, height, bgra, path, .. } = readback;
+        let results_tx = self.results_tx.clone();
+        std::thread::spawn(move || {
+            let result = save_image(pixels, width, height, bgra, &path)
+                .map(|()| path)
+                .map_err(|e| e.to_string());
+            let _ = results_tx.send(result);
+        });
+    }
+}
+
+fn save_image(mut pixels: Vec<u8>, width: u32, height: u32, bgra: bool, path: &std::path::Path) -> Result<(), anyhow::Error> {
+    if bgra {
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+    }
+    let image = image::RgbaImage::from_raw(width, height, pixels)
+        .ok_or_else(|| anyhow::anyhow!("readback size does not match {}x{}", width, height))?;
+    // The viewport is opaque; dropping alpha also keeps JPEG output working
+    image::DynamicImage::ImageRgba8(image).to_rgb8().save(path)?;
+    Ok(())
+}
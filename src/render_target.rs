@@ -0,0 +1,207 @@
+//! Offscreen scene render target and compositing onto the swapchain
+
+use wgpu::*;
+use crate::shader::load_shader_from_str;
+
+/// Depth format used by the scene passes
+pub const SCENE_DEPTH_FORMAT: TextureFormat = TextureFormat::Depth32Float;
+
+const COMPOSITE_SHADER: &str = include_str!("../assets/shaders/composite.wgsl");
+
+/// Pipeline that draws the scene target into the 3D viewport of the swapchain
+pub struct CompositePipeline {
+    pub pipeline: wgpu::RenderPipeline,
+    pub bind_group_layout: BindGroupLayout,
+    pub sampler: Sampler,
+}
+
+impl CompositePipeline {
+    pub fn new(device: &Device, surface_format: TextureFormat) -> Self {
+        let shader = load_shader_from_str(device, COMPOSITE_SHADER, Some("composite_shader"));
+
+        let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: TextureViewDimension::D2,
+                        sample_type: TextureSampleType::Float { filterable: true },
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+            label: Some("composite_bind_group_layout"),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("Composite Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("Composite Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(ColorTargetState {
+                    format: surface_format,
+                    blend: Some(BlendState::REPLACE),
+                    write_mask: ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+            multiview: None,
+        });
+
+        // Linear filtering smooths both upscaling and supersampled downscaling
+        let sampler = device.create_sampler(&SamplerDescriptor {
+            label: Some("composite_sampler"),
+            address_mode_u: AddressMode::ClampToEdge,
+            address_mode_v: AddressMode::ClampToEdge,
+            address_mode_w: AddressMode::ClampToEdge,
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            sampler,
+        }
+    }
+}
+
+/// Offscreen color and depth targets the 3D scene is rendered into.
+/// With MSAA the scene is drawn into a multisampled color texture that is
+/// resolved into `color_texture` at the end of the pass.
+pub struct SceneTarget {
+    pub color_texture: Texture,
+    pub color_view: TextureView,
+    /// Multisampled color target (`None` when `sample_count` is 1)
+    pub msaa_color_view: Option<TextureView>,
+    /// Only held to keep the texture behind `depth_view` alive
+    _depth_texture: Texture,
+    pub depth_view: TextureView,
+    pub composite_bind_group: BindGroup,
+    pub width: u32,
+    pub height: u32,
+    pub sample_count: u32,
+}
+
+impl SceneTarget {
+    pub fn new(
+        device: &Device,
+        composite: &CompositePipeline,
+        format: TextureFormat,
+        width: u32,
+        height: u32,
+        sample_count: u32,
+    ) -> Self {
+        let size = Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+
+        let color_texture = device.create_texture(&TextureDescriptor {
+            label: Some("scene_color_texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format,
+            // COPY_SRC for screenshot readback
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let color_view = color_texture.create_view(&TextureViewDescriptor::default());
+
+        let msaa_color_view = (sample_count > 1).then(|| {
+            device
+                .create_texture(&TextureDescriptor {
+                    label: Some("scene_msaa_color_texture"),
+                    size,
+                    mip_level_count: 1,
+                    sample_count,
+                    dimension: TextureDimension::D2,
+                    format,
+                    usage: TextureUsages::RENDER_ATTACHMENT,
+                    view_formats: &[],
+                })
+                .create_view(&TextureViewDescriptor::default())
+        });
+
+        let depth_texture = device.create_texture(&TextureDescriptor {
+            label: Some("scene_depth_texture"),
+            size,
+            mip_level_count: 1,
+            sample_count,
+            dimension: TextureDimension::D2,
+            format: SCENE_DEPTH_FORMAT,
+            usage: TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let depth_view = depth_texture.create_view(&TextureViewDescriptor::default());
+
+        let composite_bind_group = device.create_bind_group(&BindGroupDescriptor {
+            layout: &composite.bind_group_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(&color_view),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::Sampler(&composite.sampler),
+                },
+            ],
+            label: Some("composite_bind_group"),
+        });
+
+        Self {
+            color_texture,
+            color_view,
+            msaa_color_view,
+            _depth_texture: depth_texture,
+            depth_view,
+            composite_bind_group,
+            width,
+            height,
+            sample_count,
+        }
+    }
+
+    /// Attachment view and resolve target for the scene color attachment
+    pub fn color_attachment(&self) -> (&TextureView, Option<&TextureView>) {
+        match &self.msaa_color_view {
+            Some(msaa_view) => (msaa_view, Some(&self.color_view)),
+            None => (&self.color_view, None),
+        }
+    }
+
+    /// Target size for a viewport rendered at `render_scale`, clamped to `max_dimension`
+    pub fn scaled_size(viewport_width: f32, viewport_height: f32, render_scale: f32, max_dimension: u32) -> (u32, u32) {
+        let scale = |value: f32| ((value * render_scale).round() as u32).clamp(1, max_dimension);
+        (scale(viewport_width), scale(viewport_height))
+    }
+}
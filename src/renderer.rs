@@ -2,6 +2,7 @@
 
 use wgpu::*;
 use winit::window::Window;
+use crate::render_target::SCENE_DEPTH_FORMAT;
 
 #[allow(deprecated)]
 use raw_window_handle::{HasRawWindowHandle, HasRawDisplayHandle};
@@ -13,17 +14,33 @@ pub struct Renderer {
     pub queue: Queue,
     pub config: SurfaceConfiguration,
     pub size: winit::dpi::PhysicalSize<u32>,
-    pub depth_texture: Texture,
-    pub depth_texture_view: TextureView,
+    /// MSAA sample counts usable for the scene color and depth targets (always contains 1)
+    pub supported_sample_counts: Vec<u32>,
+    /// Samplers honor `anisotropy_clamp` (missing on some downlevel adapters)
+    pub anisotropic_filtering: bool,
+    /// Present modes the surface accepts
+    pub supported_present_modes: Vec<PresentMode>,
 }
 
 impl Renderer {
-    pub async fn new(window: &Window) -> Result<Self, anyhow::Error> {
+    /// Create the renderer. `debug` enables wgpu validation layers and logs
+    /// uncaptured errors instead of panicking. `backends` restricts the graphics
+    /// APIs tried; otherwise `WGPU_BACKEND` or every available backend is used.
+    pub async fn new(window: &Window, debug: bool, backends: Option<Backends>) -> Result<Self, anyhow::Error> {
         let size = window.inner_size();
         
-        // Create instance
+        // Create instance (validation is expensive, so only enable it on request)
+        let flags = if debug {
+            InstanceFlags::VALIDATION | InstanceFlags::DEBUG
+        } else {
+            InstanceFlags::empty()
+        };
+        let backends = backends
+            .or_else(util::backend_bits_from_env)
+            .unwrap_or(Backends::all());
         let instance = Instance::new(InstanceDescriptor {
-            backends: Backends::VULKAN,
+            backends,
+            flags,
             ..Default::default()
         });
         
@@ -45,7 +62,21 @@ impl Renderer {
                 force_fallback_adapter: false,
             })
             .await
-            .ok_or_else(|| anyhow::anyhow!("Failed to find an appropriate adapter"))?;
+            .ok_or_else(|| anyhow::anyhow!("Failed to find an appropriate adapter (backends: {:?})", backends))?;
+        let adapter_info = adapter.get_info();
+        log::info!(
+            "Using {} ({:?}, {:?} backend, driver {} {})",
+            adapter_info.name,
+            adapter_info.device_type,
+            adapter_info.backend,
+            adapter_info.driver,
+            adapter_info.driver_info,
+        );
+        
+        // Sample counts other than 1 and 4 need adapter-specific format features;
+        // line polygon mode enables the wireframe view
+        let required_features = adapter.features()
+            & (Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES | Features::POLYGON_MODE_LINE);
         
         // Request device
         // Note: TESSELATION_SHADER feature may not be available on all hardware
@@ -53,7 +84,7 @@ impl Renderer {
         let (device, queue) = adapter
             .request_device(
                 &DeviceDescriptor {
-                    required_features: Features::empty(), // Start without tessellation for compatibility
+                    required_features, // No tessellation, for compatibility
                     required_limits: Limits::default(),
                     label: None,
                 },
@@ -61,6 +92,12 @@ impl Renderer {
             )
             .await?;
         
+        if debug {
+            device.on_uncaptured_error(Box::new(|error| {
+                log::error!(target: "wgpu", "{}", error);
+            }));
+        }
+        
         // Get surface capabilities
         let surface_caps = surface.get_capabilities(&adapter);
         let surface_format = surface_caps
@@ -70,36 +107,38 @@ impl Renderer {
             .find(|f| f.is_srgb())
             .unwrap_or(surface_caps.formats[0]);
         
-        // Configure surface
+        // Configure surface (Fifo is the one present mode every surface supports)
         let config = SurfaceConfiguration {
             usage: TextureUsages::RENDER_ATTACHMENT,
             format: surface_format,
             width: size.width,
             height: size.height,
-            present_mode: surface_caps.present_modes[0],
+            present_mode: PresentMode::Fifo,
             alpha_mode: surface_caps.alpha_modes[0],
             view_formats: vec![],
             desired_maximum_frame_latency: 2,
         };
         surface.configure(&device, &config);
         
-        // Create depth texture
-        let depth_texture = device.create_texture(&TextureDescriptor {
-            size: Extent3d {
-                width: config.width,
-                height: config.height,
-                depth_or_array_layers: 1,
-            },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: TextureDimension::D2,
-            format: TextureFormat::Depth32Float,
-            usage: TextureUsages::RENDER_ATTACHMENT,
-            label: Some("depth_texture"),
-            view_formats: &[],
-        });
+        // 4x is guaranteed for render attachments; 2x and 8x depend on the adapter
+        let sample_count_supported = |count: u32| {
+            [surface_format, SCENE_DEPTH_FORMAT]
+                .iter()
+                .all(|format| adapter.get_texture_format_features(*format).flags.sample_count_supported(count))
+        };
+        let supported_sample_counts = [1, 2, 4, 8]
+            .into_iter()
+            .filter(|&count| match count {
+                1 | 4 => true,
+                _ => required_features.contains(Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES)
+                    && sample_count_supported(count),
+            })
+            .collect();
         
-        let depth_texture_view = depth_texture.create_view(&TextureViewDescriptor::default());
+        let anisotropic_filtering = adapter
+            .get_downlevel_capabilities()
+            .flags
+            .contains(DownlevelFlags::ANISOTROPIC_FILTERING);
         
         Ok(Self {
             surface,
@@ -107,35 +146,32 @@ impl Renderer {
             queue,
             config,
             size,
-            depth_texture,
-            depth_texture_view,
+            supported_sample_counts,
+            anisotropic_filtering,
+            supported_present_modes: surface_caps.present_modes,
         })
     }
     
+    /// Reconfigure the surface with another present mode, falling back to Fifo
+    /// if the surface doesn't support it. Returns the mode in use.
+    pub fn set_present_mode(&mut self, present_mode: PresentMode) -> PresentMode {
+        let present_mode = if self.supported_present_modes.contains(&present_mode) {
+            present_mode
+        } else {
+            log::warn!("Present mode {:?} is not supported, using Fifo", present_mode);
+            PresentMode::Fifo
+        };
+        self.config.present_mode = present_mode;
+        self.surface.configure(&self.device, &self.config);
+        present_mode
+    }
+    
     pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
         if new_size.width > 0 && new_size.height > 0 {
             self.size = new_size;
             self.config.width = new_size.width;
             self.config.height = new_size.height;
             self.surface.configure(&self.device, &self.config);
-            
-            // Recreate depth texture
-            self.depth_texture = self.device.create_texture(&TextureDescriptor {
-                size: Extent3d {
-                    width: self.config.width,
-                    height: self.config.height,
-                    depth_or_array_layers: 1,
-                },
-                mip_level_count: 1,
-                sample_count: 1,
-                dimension: TextureDimension::D2,
-                format: TextureFormat::Depth32Float,
-                usage: TextureUsages::RENDER_ATTACHMENT,
-                label: Some("depth_texture"),
-                view_formats: &[],
-            });
-            
-            self.depth_texture_view = self.depth_texture.create_view(&TextureViewDescriptor::default());
         }
     }
     
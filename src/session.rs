@@ -0,0 +1,174 @@
+//! Settings carried over from the previous session (saved on exit, restored on launch)
+
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use crate::camera_wgpu::OrbitCamera;
+use crate::config::config_dir;
+use crate::state_wgpu::{prune_recent, AppState, ClipPlanes, LightParams, MaterialParams, ViewMode, DEFAULT_BACKGROUND_COLOR, DEFAULT_FOV_DEGREES, DEFAULT_MSAA_SAMPLES, DEFAULT_UI_PANEL_WIDTH};
+
+/// File name of the session state inside the config directory
+const SESSION_FILE_NAME: &str = "session.json";
+
+/// Window size used when no session was saved (logical pixels)
+pub const DEFAULT_WINDOW_SIZE: [f64; 2] = [1600.0, 900.0];
+
+/// Orbit camera placement
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CameraSession {
+    pub target: [f32; 3],
+    pub distance: f32,
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+/// Window, camera and viewer settings of the last session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    /// Inner window size in logical pixels
+    pub window_size: [f64; 2],
+    pub camera: Option<CameraSession>,
+    pub material_params: MaterialParams,
+    pub light_params: LightParams,
+    pub secondary_lights: Vec<LightParams>,
+    pub background_color: [f32; 3],
+    pub view_mode: ViewMode,
+    pub texture_folder: Option<String>,
+    pub recent_folders: Vec<String>,
+    pub recent_models: Vec<String>,
+    pub ui_panel_width: f32,
+    pub fov_degrees: f32,
+    pub clip_planes: ClipPlanes,
+    pub camera_smoothing: f32,
+    pub render_scale: f32,
+    pub msaa_samples: u32,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self {
+            window_size: DEFAULT_WINDOW_SIZE,
+            camera: None,
+            material_params: MaterialParams::default(),
+            light_params: LightParams::default(),
+            secondary_lights: Vec::new(),
+            background_color: DEFAULT_BACKGROUND_COLOR,
+            view_mode: ViewMode::default(),
+            texture_folder: None,
+            recent_folders: Vec::new(),
+            recent_models: Vec::new(),
+            ui_panel_width: DEFAULT_UI_PANEL_WIDTH,
+            fov_degrees: DEFAULT_FOV_DEGREES,
+            clip_planes: ClipPlanes::default(),
+            camera_smoothing: 0.0,
+            render_scale: 1.0,
+            msaa_samples: DEFAULT_MSAA_SAMPLES,
+        }
+    }
+}
+
+impl Session {
+    /// Path of the session file
+    pub fn path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join(SESSION_FILE_NAME))
+    }
+
+    /// Load the last session, returning `None` if no file exists or it can't be parsed
+    pub fn load() -> Option<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return None;
+        }
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                log::warn!("Failed to read session {}: {}", path.display(), e);
+                return None;
+            }
+        };
+        match serde_json::from_str(&contents) {
+            Ok(session) => {
+                log::info!("Restored session from {}", path.display());
+                Some(session)
+            }
+            Err(e) => {
+                log::warn!("Ignoring invalid session {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    /// Write the session file
+    pub fn save(&self) -> Result<PathBuf, anyhow::Error> {
+        let path = Self::path()
+            .ok_or_else(|| anyhow::anyhow!("Could not determine config directory"))?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+
+    /// Capture the current settings
+    pub fn from_state(state: &AppState, camera: &OrbitCamera, window_size: [f64; 2]) -> Self {
+        Self {
+            window_size,
+            camera: Some(CameraSession {
+                target: camera.target.to_array(),
+                distance: camera.distance,
+                yaw: camera.yaw,
+                pitch: camera.pitch,
+            }),
+            material_params: state.material_params.clone(),
+            light_params: state.light_params.clone(),
+            secondary_lights: state.secondary_lights.clone(),
+            background_color: state.background_color,
+            view_mode: state.view_mode,
+            texture_folder: state.texture_folder.clone(),
+            recent_folders: state.recent_folders.clone(),
+            recent_models: state.recent_models.clone(),
+            ui_panel_width: state.ui_panel_width,
+            fov_degrees: state.fov_degrees,
+            clip_planes: state.clip_planes.clone(),
+            camera_smoothing: state.camera_smoothing,
+            render_scale: state.render_scale,
+            msaa_samples: state.msaa_samples,
+        }
+    }
+
+    /// Apply these settings to the app state. A texture folder that no longer
+    /// exists is dropped; otherwise it is queued for loading. Recent entries
+    /// that no longer exist are pruned.
+    pub fn apply_to(&self, state: &mut AppState) {
+        state.material_params = self.material_params.clone();
+        state.light_params = self.light_params.clone();
+        state.secondary_lights = self.secondary_lights.clone();
+        state.background_color = self.background_color;
+        state.view_mode = self.view_mode;
+        state.ui_panel_width = self.ui_panel_width;
+        state.fov_degrees = self.fov_degrees;
+        state.clip_planes = self.clip_planes.clone();
+        state.camera_smoothing = self.camera_smoothing;
+        state.render_scale = self.render_scale;
+        state.msaa_samples = self.msaa_samples;
+        if let Some(folder) = self.texture_folder.as_ref().filter(|folder| std::path::Path::new(folder).exists()) {
+            state.texture_folder = Some(folder.clone());
+            state.textures_need_reload = true;
+        }
+        state.recent_folders = self.recent_folders.clone();
+        state.recent_models = self.recent_models.clone();
+        prune_recent(&mut state.recent_folders);
+        prune_recent(&mut state.recent_models);
+        state.material_changed = true;
+    }
+
+    /// Apply the saved camera placement
+    pub fn apply_camera(&self, camera: &mut OrbitCamera) {
+        if let Some(saved) = self.camera {
+            camera.target = saved.target.into();
+            camera.distance = saved.distance.clamp(camera.min_distance, camera.max_distance);
+            camera.yaw = saved.yaw;
+            camera.pitch = saved.pitch.clamp(camera.min_pitch, camera.max_pitch);
+        }
+    }
+}
@@ -4,7 +4,6 @@ use wgpu::*;
 use std::borrow::Cow;
 
 /// Load a shader module from WGSL source
-#[allow(dead_code)]
 pub fn load_shader(device: &Device, source: &str, label: Option<&str>) -> ShaderModule {
     device.create_shader_module(ShaderModuleDescriptor {
         label,
@@ -20,4 +19,13 @@ pub fn load_shader_from_str(device: &Device, source: &'static str, label: Option
     })
 }
 
-
+/// Load a shader module from runtime WGSL source, returning compile errors
+/// instead of handing them to the device's uncaptured error handler
+pub fn load_shader_checked(device: &Device, source: &str, label: Option<&str>) -> Result<ShaderModule, anyhow::Error> {
+    device.push_error_scope(ErrorFilter::Validation);
+    let shader = load_shader(device, source, label);
+    match pollster::block_on(device.pop_error_scope()) {
+        Some(error) => Err(anyhow::anyhow!("{}", error)),
+        None => Ok(shader),
+    }
+}
@@ -0,0 +1,59 @@
+//! Reload the PBR shader from disk while developing (`--watch-shader`)
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Source location of the embedded PBR shader in the repository checkout
+pub const PBR_SHADER_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/shaders/pbr.wgsl");
+
+/// How often the file's modification time is checked
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Polls a shader file's modification time and hands out its source when it changes
+pub struct ShaderWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    last_poll: Instant,
+}
+
+impl ShaderWatcher {
+    /// The first poll always reads the file, so edits made since the build are picked up
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        log::info!("Watching {} for shader changes", path.display());
+        Self {
+            path,
+            modified: None,
+            last_poll: Instant::now() - POLL_INTERVAL,
+        }
+    }
+
+    pub fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    /// New shader source if the file changed since the last poll
+    pub fn poll(&mut self) -> Option<String> {
+        if self.last_poll.elapsed() < POLL_INTERVAL {
+            return None;
+        }
+        self.last_poll = Instant::now();
+
+        let modified = std::fs::metadata(&self.path).and_then(|meta| meta.modified()).ok()?;
+        if self.modified == Some(modified) {
+            return None;
+        }
+        self.modified = Some(modified);
+
+        match std::fs::read_to_string(&self.path) {
+            Ok(source) => Some(source),
+            Err(e) => {
+                log::warn!("Failed to read {}: {}", self.path.display(), e);
+                None
+            }
+        }
+    }
+}
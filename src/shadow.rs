@@ -0,0 +1,179 @@
+//! Shadow map for the directional light: the mesh depth rendered from the light,
+//! sampled with PCF in pbr.wgsl to attenuate direct lighting
+
+use wgpu::*;
+use wgpu::util::DeviceExt;
+use bytemuck::{Pod, Zeroable};
+use glam::{Mat4, Vec3};
+use crate::state_wgpu::ShadowParams;
+
+/// Resolution of the square shadow map
+pub const SHADOW_MAP_SIZE: u32 = 2048;
+pub const SHADOW_MAP_FORMAT: TextureFormat = TextureFormat::Depth32Float;
+
+/// Shadow uniform (matches `ShadowUniform` in pbr.wgsl)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct ShadowUniform {
+    pub light_view_proj: [[f32; 4]; 4],
+    /// Normal offset applied before the lookup (world units)
+    pub bias: f32,
+    pub enabled: u32,
+    pub texel_size: f32,
+    pub _padding: f32,
+}
+
+/// Shadow depth texture plus the bind groups that write and read it
+pub struct ShadowMap {
+    /// Group 3 of the PBR pipeline: uniform, shadow map and comparison sampler
+    pub bind_group_layout: BindGroupLayout,
+    pub bind_group: BindGroup,
+    /// Group 3 of the shadow pass: the uniform alone, since the map is the depth target there
+    pub pass_bind_group_layout: BindGroupLayout,
+    pub pass_bind_group: BindGroup,
+    pub view: TextureView,
+    uniform: ShadowUniform,
+    uniform_buffer: Buffer,
+}
+
+impl ShadowMap {
+    pub fn new(device: &Device) -> Self {
+        let texture = device.create_texture(&TextureDescriptor {
+            label: Some("Shadow Map"),
+            size: Extent3d {
+                width: SHADOW_MAP_SIZE,
+                height: SHADOW_MAP_SIZE,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: SHADOW_MAP_FORMAT,
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&TextureViewDescriptor::default());
+
+        // Linear comparison filtering gives each PCF tap a 2x2 bilinear blend
+        let sampler = device.create_sampler(&SamplerDescriptor {
+            label: Some("Shadow Sampler"),
+            address_mode_u: AddressMode::ClampToEdge,
+            address_mode_v: AddressMode::ClampToEdge,
+            address_mode_w: AddressMode::ClampToEdge,
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            mipmap_filter: FilterMode::Nearest,
+            compare: Some(CompareFunction::LessEqual),
+            ..Default::default()
+        });
+
+        let uniform = ShadowUniform {
+            light_view_proj: Mat4::IDENTITY.to_cols_array_2d(),
+            bias: 0.0,
+            enabled: 0,
+            texel_size: 1.0 / SHADOW_MAP_SIZE as f32,
+            _padding: 0.0,
+        };
+        let uniform_buffer = device.create_buffer_init(&util::BufferInitDescriptor {
+            label: Some("Shadow Buffer"),
+            contents: bytemuck::cast_slice(&[uniform]),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+
+        let uniform_entry = BindGroupLayoutEntry {
+            binding: 0,
+            visibility: ShaderStages::VERTEX | ShaderStages::FRAGMENT,
+            ty: BindingType::Buffer {
+                ty: BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("shadow_bind_group_layout"),
+            entries: &[
+                uniform_entry,
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Depth,
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Comparison),
+                    count: None,
+                },
+            ],
+        });
+        let bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("shadow_bind_group"),
+            layout: &bind_group_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::TextureView(&view),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindingResource::Sampler(&sampler),
+                },
+            ],
+        });
+
+        let pass_bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("shadow_pass_bind_group_layout"),
+            entries: &[uniform_entry],
+        });
+        let pass_bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("shadow_pass_bind_group"),
+            layout: &pass_bind_group_layout,
+            entries: &[BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        Self {
+            bind_group_layout,
+            bind_group,
+            pass_bind_group_layout,
+            pass_bind_group,
+            view,
+            uniform,
+            uniform_buffer,
+        }
+    }
+
+    /// Upload the light matrix and the shadow settings
+    pub fn update(&mut self, queue: &Queue, light_view_proj: Mat4, params: &ShadowParams) {
+        self.uniform.light_view_proj = light_view_proj.to_cols_array_2d();
+        self.uniform.bias = params.bias;
+        self.uniform.enabled = params.enabled as u32;
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[self.uniform]));
+    }
+}
+
+/// Orthographic view-projection looking along the light onto a bounding sphere.
+/// `light_direction` points towards the light, as in the material uniform.
+pub fn light_view_proj(light_direction: Vec3, center: Vec3, radius: f32) -> Mat4 {
+    let direction = light_direction.normalize_or_zero();
+    let direction = if direction == Vec3::ZERO { Vec3::Y } else { direction };
+    let radius = radius.max(0.01);
+    // Any up vector works for a directional light, as long as it isn't parallel
+    let up = if direction.y.abs() > 0.99 { Vec3::Z } else { Vec3::Y };
+    let eye = center + direction * radius * 2.0;
+    let view = Mat4::look_at_rh(eye, center, up);
+    let projection = Mat4::orthographic_rh(-radius, radius, -radius, radius, radius * 0.5, radius * 3.5);
+    projection * view
+}
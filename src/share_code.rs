@@ -0,0 +1,76 @@
+//! Compact share codes for the current material, light and view settings
+//!
+//! A share code is `pbr<version>:` followed by URL-safe base64 of a bincode payload.
+//! The version number in the prefix must be bumped whenever one of the shared
+//! structs changes layout, so codes from older builds are rejected cleanly
+//! instead of decoding into garbage.
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde::{Deserialize, Serialize};
+use crate::state_wgpu::{AppState, LightParams, MaterialParams, NormalDebugSpace, ViewMode};
+
+/// Prefix identifying the current share code format
+pub const SHARE_CODE_PREFIX: &str = "pbr9:";
+/// Longest accepted code, to reject pasted garbage before decoding
+const MAX_SHARE_CODE_LENGTH: usize = 1024;
+
+/// Settings captured in a share code
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareCode {
+    pub material_params: MaterialParams,
+    pub light_params: LightParams,
+    pub secondary_lights: Vec<LightParams>,
+    pub view_mode: ViewMode,
+    pub normal_debug_space: NormalDebugSpace,
+}
+
+impl ShareCode {
+    /// Capture the current settings from the app state
+    pub fn from_state(state: &AppState) -> Self {
+        Self {
+            material_params: state.material_params.clone(),
+            light_params: state.light_params.clone(),
+            secondary_lights: state.secondary_lights.clone(),
+            view_mode: state.view_mode,
+            normal_debug_space: state.normal_debug_space,
+        }
+    }
+
+    /// Apply these settings to the app state
+    pub fn apply_to(&self, state: &mut AppState) {
+        state.material_params = self.material_params.clone();
+        state.light_params = self.light_params.clone();
+        state.secondary_lights = self.secondary_lights.clone();
+        state.view_mode = self.view_mode;
+        state.normal_debug_space = self.normal_debug_space;
+        state.material_changed = true;
+    }
+
+    /// Encode as a share code string
+    pub fn encode(&self) -> Result<String, anyhow::Error> {
+        let payload = bincode::serialize(self)?;
+        Ok(format!("{}{}", SHARE_CODE_PREFIX, URL_SAFE_NO_PAD.encode(payload)))
+    }
+
+    /// Decode a share code, rejecting codes that are too long or from another format version
+    pub fn decode(code: &str) -> Result<Self, anyhow::Error> {
+        let code = code.trim();
+        if code.len() > MAX_SHARE_CODE_LENGTH {
+            anyhow::bail!("Share code is too long ({} characters)", code.len());
+        }
+        let Some(encoded) = code.strip_prefix(SHARE_CODE_PREFIX) else {
+            match code.split_once(':') {
+                Some((prefix, _)) if prefix.starts_with("pbr") => {
+                    anyhow::bail!("Share code version '{}' is not supported by this build", prefix)
+                }
+                _ => anyhow::bail!("Not a PBR viewer share code"),
+            }
+        };
+        let payload = URL_SAFE_NO_PAD.decode(encoded)
+            .map_err(|e| anyhow::anyhow!("Share code is corrupted: {}", e))?;
+        let share_code: Self = bincode::deserialize(&payload)
+            .map_err(|e| anyhow::anyhow!("Share code is corrupted: {}", e))?;
+        Ok(share_code)
+    }
+}
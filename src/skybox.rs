@@ -0,0 +1,191 @@
+//! Environment panorama drawn as the scene background
+
+use wgpu::*;
+use wgpu::util::DeviceExt;
+use bytemuck::{Pod, Zeroable};
+use glam::{Mat3, Mat4};
+use crate::camera_wgpu::Camera;
+use crate::environment::EnvironmentMap;
+use crate::render_target::SCENE_DEPTH_FORMAT;
+use crate::shader::load_shader_from_str;
+use crate::state_wgpu::{EnvironmentParams, ShadingParams};
+
+const SKYBOX_SHADER: &str = include_str!("../assets/shaders/skybox.wgsl");
+
+/// Skybox uniform (matches `SkyboxUniform` in skybox.wgsl)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct SkyboxUniform {
+    pub inv_view_proj: [[f32; 4]; 4],
+    pub env_rotation: [f32; 2],
+    pub exposure: f32,
+    pub tone_mapping: u32,
+}
+
+/// Pipeline drawing a fullscreen triangle that samples the environment panorama
+pub struct SkyboxPipeline {
+    pub pipeline: wgpu::RenderPipeline,
+    bind_group_layout: BindGroupLayout,
+    uniform_buffer: Buffer,
+    sampler: Sampler,
+    bind_group: BindGroup,
+}
+
+impl SkyboxPipeline {
+    pub fn new(device: &Device, environment: &EnvironmentMap, format: TextureFormat, sample_count: u32) -> Self {
+        let shader = load_shader_from_str(device, SKYBOX_SHADER, Some("skybox_shader"));
+
+        let uniform_buffer = device.create_buffer_init(&util::BufferInitDescriptor {
+            label: Some("Skybox Buffer"),
+            contents: bytemuck::cast_slice(&[SkyboxUniform::zeroed()]),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: TextureViewDimension::D2,
+                        sample_type: TextureSampleType::Float { filterable: true },
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+            label: Some("skybox_bind_group_layout"),
+        });
+
+        // Wraps around horizontally, clamps at the poles
+        let sampler = device.create_sampler(&SamplerDescriptor {
+            label: Some("skybox_sampler"),
+            address_mode_u: AddressMode::Repeat,
+            address_mode_v: AddressMode::ClampToEdge,
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let bind_group = Self::create_bind_group(device, &bind_group_layout, &uniform_buffer, &sampler, environment);
+
+        let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("Skybox Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("Skybox Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(ColorTargetState {
+                    format,
+                    blend: Some(BlendState::REPLACE),
+                    write_mask: ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: PrimitiveState::default(),
+            // Drawn first and never writes depth, so everything else covers it
+            depth_stencil: Some(DepthStencilState {
+                format: SCENE_DEPTH_FORMAT,
+                depth_write_enabled: false,
+                depth_compare: CompareFunction::Always,
+                stencil: StencilState::default(),
+                bias: DepthBiasState::default(),
+            }),
+            multisample: MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
+            multiview: None,
+        });
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            uniform_buffer,
+            sampler,
+            bind_group,
+        }
+    }
+
+    fn create_bind_group(
+        device: &Device,
+        layout: &BindGroupLayout,
+        uniform_buffer: &Buffer,
+        sampler: &Sampler,
+        environment: &EnvironmentMap,
+    ) -> BindGroup {
+        device.create_bind_group(&BindGroupDescriptor {
+            layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::TextureView(&environment.background.1),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindingResource::Sampler(sampler),
+                },
+            ],
+            label: Some("skybox_bind_group"),
+        })
+    }
+
+    /// Point the skybox at a newly loaded environment
+    pub fn set_environment(&mut self, device: &Device, environment: &EnvironmentMap) {
+        self.bind_group = Self::create_bind_group(device, &self.bind_group_layout, &self.uniform_buffer, &self.sampler, environment);
+    }
+
+    /// Upload the camera orientation, environment rotation and tone mapping
+    pub fn update(&self, queue: &Queue, camera: &Camera, environment: &EnvironmentParams, shading: &ShadingParams) {
+        // Only the camera's orientation matters for an infinitely distant background
+        let view_rotation = Mat4::from_mat3(Mat3::from_mat4(camera.view_matrix()));
+        let inv_view_proj = (camera.projection_matrix() * view_rotation).inverse();
+        let (sin, cos) = environment.rotation_degrees.to_radians().sin_cos();
+        let uniform = SkyboxUniform {
+            inv_view_proj: inv_view_proj.to_cols_array_2d(),
+            env_rotation: [sin, cos],
+            exposure: shading.exposure(),
+            tone_mapping: shading.tone_mapping as u32,
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniform]));
+    }
+
+    /// Draw the background; must come before the opaque scene in the pass
+    pub fn draw<'a>(&'a self, render_pass: &mut RenderPass<'a>) {
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
@@ -1,7 +1,18 @@
 //! Application state without Bevy dependencies
 
-use glam::Quat;
-use crate::mesh_wgpu::MeshType;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use glam::{Quat, Vec3};
+use serde::{Deserialize, Serialize};
+use crate::camera_wgpu::ViewPreset;
+use crate::mesh_wgpu::{Aabb, MeshType};
+use crate::debug_log::LogBuffer;
+use crate::texture_manager::TextureSetInfo;
+use crate::texture_loader::{DetectionConflict, TextureLoadFailure, TexturePaths};
+use crate::texture::{SlotSamplers, TextureFilterSettings};
+use crate::notifications::Notifications;
+use crate::frame_stats::FrameStats;
+use crate::console::Console;
 
 /// Default UI panel width
 pub const DEFAULT_UI_PANEL_WIDTH: f32 = 320.0;
@@ -9,6 +20,42 @@ pub const DEFAULT_UI_PANEL_WIDTH: f32 = 320.0;
 pub const MIN_UI_PANEL_WIDTH: f32 = 280.0;
 /// Maximum UI panel width
 pub const MAX_UI_PANEL_WIDTH: f32 = 600.0;
+/// Default maximum texture dimension (further clamped to the device limit)
+pub const DEFAULT_MAX_TEXTURE_SIZE: u32 = 8192;
+/// Default vertical gap between coplanar presentation layers
+pub const DEFAULT_LAYER_SEPARATION: f32 = 0.002;
+/// Default corner radius of the rounded rectangle mesh (fraction of its half-size)
+pub const DEFAULT_CORNER_RADIUS: f32 = 0.2;
+/// Default background clear color (dark gray)
+pub const DEFAULT_BACKGROUND_COLOR: [f32; 3] = [0.1, 0.1, 0.1];
+/// Background colors for judging a material against standard references (linear RGB)
+pub const BACKGROUND_PRESETS: &[(&str, [f32; 3])] = &[
+    ("White", [1.0, 1.0, 1.0]),
+    ("18% Gray", [0.18, 0.18, 0.18]),
+    ("Black", [0.0, 0.0, 0.0]),
+];
+/// Default MSAA sample count of the 3D view
+pub const DEFAULT_MSAA_SAMPLES: u32 = 4;
+/// Minimum 3D render resolution scale (undersampling)
+pub const MIN_RENDER_SCALE: f32 = 0.5;
+/// Maximum 3D render resolution scale (supersampling)
+pub const MAX_RENDER_SCALE: f32 = 2.0;
+/// Secondary lights the shader evaluates besides the key light
+pub const MAX_SECONDARY_LIGHTS: usize = 2;
+/// Default vertical field of view of the viewport camera
+pub const DEFAULT_FOV_DEGREES: f32 = 45.0;
+/// Field of view limits (beyond these the projection degenerates)
+pub const MIN_FOV_DEGREES: f32 = 10.0;
+pub const MAX_FOV_DEGREES: f32 = 120.0;
+/// Longest camera smoothing time constant (seconds)
+pub const MAX_CAMERA_SMOOTHING: f32 = 0.5;
+/// Default camera clip planes (manual mode)
+pub const DEFAULT_NEAR_PLANE: f32 = 0.1;
+pub const DEFAULT_FAR_PLANE: f32 = 100.0;
+/// Smallest near/far ratio auto mode allows, to keep depth precision
+const MIN_NEAR_FAR_RATIO: f32 = 1e-4;
+/// Number of entries kept in the recent folders and recent models lists
+pub const MAX_RECENT_ENTRIES: usize = 8;
 
 /// UI tab selection
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -18,10 +65,11 @@ pub enum UiTab {
     Material,
     Light,
     Textures,
+    Render,
 }
 
 /// View modes for visualizing different texture channels
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum ViewMode {
     #[default]
     Lit,
@@ -32,6 +80,12 @@ pub enum ViewMode {
     AO,
     Emissive,
     Height,
+    /// UV stretch heatmap: green = uniform texel density, red = stretched
+    UvStretch,
+    /// Procedural checkerboard with U/V direction ramps
+    UvChecker,
+    /// World-space tangent, bitangent or normal of the vertex tangent frame
+    TangentFrame,
 }
 
 impl ViewMode {
@@ -45,6 +99,9 @@ impl ViewMode {
             ViewMode::AO,
             ViewMode::Emissive,
             ViewMode::Height,
+            ViewMode::UvStretch,
+            ViewMode::UvChecker,
+            ViewMode::TangentFrame,
         ]
     }
 
@@ -58,21 +115,362 @@ impl ViewMode {
             ViewMode::AO => "AO",
             ViewMode::Emissive => "Emissive",
             ViewMode::Height => "Height",
+            ViewMode::UvStretch => "UV Stretch",
+            ViewMode::UvChecker => "UV Checker",
+            ViewMode::TangentFrame => "Tangents",
         }
     }
 }
 
-/// Material parameters controlled by sliders
+/// Space in which the Normals view mode displays normals
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum NormalDebugSpace {
+    /// Raw tangent-space normal as stored in the normal map
+    #[default]
+    Tangent,
+    /// Final world-space shading normal after the TBN transform
+    World,
+}
+
+impl NormalDebugSpace {
+    pub fn name(&self) -> &'static str {
+        match self {
+            NormalDebugSpace::Tangent => "Tangent",
+            NormalDebugSpace::World => "World",
+        }
+    }
+}
+
+/// Tangent frame vector shown by the Tangents view mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TangentFrameAxis {
+    #[default]
+    Tangent,
+    Bitangent,
+    Normal,
+}
+
+impl TangentFrameAxis {
+    pub fn all() -> &'static [TangentFrameAxis] {
+        &[TangentFrameAxis::Tangent, TangentFrameAxis::Bitangent, TangentFrameAxis::Normal]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TangentFrameAxis::Tangent => "Tangent",
+            TangentFrameAxis::Bitangent => "Bitangent",
+            TangentFrameAxis::Normal => "Normal",
+        }
+    }
+}
+
+/// Shading quality options that are independent of the material
+#[derive(Debug, Clone)]
+pub struct ShadingParams {
+    /// Widen specular highlights where the normal varies quickly to reduce shimmer
+    pub specular_aa: bool,
+    /// Scale of the screen-space normal variance fed into the roughness
+    pub specular_aa_strength: f32,
+    /// Debug: let ambient occlusion darken direct light too (physically wrong)
+    pub ao_affects_direct: bool,
+    /// Compensate the energy single-scatter GGX loses on rough surfaces
+    pub multi_scatter: bool,
+    /// Exposure adjustment in stops, applied before tone mapping
+    pub exposure_ev: f32,
+    pub tone_mapping: ToneMapping,
+}
+
+impl ShadingParams {
+    /// Linear multiplier for the exposure setting
+    pub fn exposure(&self) -> f32 {
+        self.exposure_ev.exp2()
+    }
+}
+
+impl Default for ShadingParams {
+    fn default() -> Self {
+        Self {
+            specular_aa: true,
+            specular_aa_strength: 1.0,
+            ao_affects_direct: false,
+            multi_scatter: true,
+            exposure_ev: 0.0,
+            tone_mapping: ToneMapping::default(),
+        }
+    }
+}
+
+/// Operator compressing linear HDR shading into the displayable range
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ToneMapping {
+    /// Clip at 1.0 (the raw shading output)
+    None,
+    Reinhard,
+    /// Narkowicz's fit of the ACES filmic curve
+    #[default]
+    Aces,
+}
+
+impl ToneMapping {
+    pub fn all() -> &'static [ToneMapping] {
+        &[ToneMapping::None, ToneMapping::Reinhard, ToneMapping::Aces]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ToneMapping::None => "None (clip)",
+            ToneMapping::Reinhard => "Reinhard",
+            ToneMapping::Aces => "ACES Filmic",
+        }
+    }
+}
+
+/// How finished frames are handed to the display
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PresentMode {
+    /// Wait for vertical blank (VSync); always supported
+    #[default]
+    Fifo,
+    /// Replace the queued frame with the newest one: low latency without tearing
+    Mailbox,
+    /// Present right away (uncapped, may tear)
+    Immediate,
+}
+
+impl PresentMode {
+    pub fn all() -> &'static [PresentMode] {
+        &[PresentMode::Fifo, PresentMode::Mailbox, PresentMode::Immediate]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PresentMode::Fifo => "VSync",
+            PresentMode::Mailbox => "Mailbox",
+            PresentMode::Immediate => "Off (uncapped)",
+        }
+    }
+
+    pub fn to_wgpu(self) -> wgpu::PresentMode {
+        match self {
+            PresentMode::Fifo => wgpu::PresentMode::Fifo,
+            PresentMode::Mailbox => wgpu::PresentMode::Mailbox,
+            PresentMode::Immediate => wgpu::PresentMode::Immediate,
+        }
+    }
+}
+
+/// Texture filtering presets trading sharpness for performance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextureQuality {
+    Performance,
+    #[default]
+    Balanced,
+    Quality,
+    /// Individual settings that don't match a preset
+    Custom,
+}
+
+impl TextureQuality {
+    pub fn presets() -> &'static [TextureQuality] {
+        &[
+            TextureQuality::Performance,
+            TextureQuality::Balanced,
+            TextureQuality::Quality,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TextureQuality::Performance => "Performance",
+            TextureQuality::Balanced => "Balanced",
+            TextureQuality::Quality => "Quality",
+            TextureQuality::Custom => "Custom",
+        }
+    }
+
+    /// Filtering settings of a preset (`None` for Custom)
+    pub fn settings(&self) -> Option<TextureFilterSettings> {
+        match self {
+            TextureQuality::Performance => Some(TextureFilterSettings {
+                linear_mipmaps: false,
+                anisotropy: 1,
+                generate_mipmaps: true,
+                ..Default::default()
+            }),
+            TextureQuality::Balanced => Some(TextureFilterSettings::default()),
+            TextureQuality::Quality => Some(TextureFilterSettings {
+                linear_mipmaps: true,
+                anisotropy: 16,
+                generate_mipmaps: true,
+                ..Default::default()
+            }),
+            TextureQuality::Custom => None,
+        }
+    }
+
+    /// Preset matching the given settings, or Custom. Wrap and filter mode are not part of a preset.
+    pub fn from_settings(filter: &TextureFilterSettings) -> Self {
+        Self::presets()
+            .iter()
+            .copied()
+            .find(|preset| preset.settings().map(|settings| filter.with_preset(settings)) == Some(*filter))
+            .unwrap_or(TextureQuality::Custom)
+    }
+}
+
+/// Debug overlays drawn on top of the shaded surface
+#[derive(Debug, Clone)]
+pub struct OverlayParams {
+    /// Draw lines where the texture repeats (integer UV boundaries)
+    pub uv_tile_lines: bool,
+    pub uv_tile_line_color: [f32; 3],
+    pub wireframe: WireframeMode,
+    pub wireframe_color: [f32; 3],
+}
+
+impl Default for OverlayParams {
+    fn default() -> Self {
+        Self {
+            uv_tile_lines: false,
+            uv_tile_line_color: [1.0, 1.0, 0.0],
+            wireframe: WireframeMode::Off,
+            wireframe_color: [0.1, 0.1, 0.1],
+        }
+    }
+}
+
+/// Size of the mesh currently on the GPU
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MeshStats {
+    pub vertices: u32,
+    pub triangles: u32,
+    /// Average triangle edge length in model units
+    pub mean_edge_length: f32,
+}
+
+/// How the mesh triangles are drawn (line drawing needs `POLYGON_MODE_LINE`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WireframeMode {
+    #[default]
+    Off,
+    /// Triangle edges drawn over the shaded surface
+    Overlay,
+    /// Triangle edges only, without the filled surface
+    Only,
+}
+
+impl WireframeMode {
+    pub fn all() -> &'static [WireframeMode] {
+        &[WireframeMode::Off, WireframeMode::Overlay, WireframeMode::Only]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WireframeMode::Off => "Off",
+            WireframeMode::Overlay => "Overlay",
+            WireframeMode::Only => "Wireframe Only",
+        }
+    }
+}
+
+/// Procedural reference grid on the ground plane
 #[derive(Debug, Clone)]
+pub struct GridParams {
+    pub enabled: bool,
+    /// Distance between minor lines (every 10th line is a major line)
+    pub spacing: f32,
+    pub color: [f32; 3],
+    /// Distance from the camera at which the grid has faded out
+    pub fade_distance: f32,
+    /// Highlight the X (red) and Z (blue) axes through the origin
+    pub show_axes: bool,
+}
+
+impl Default for GridParams {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            spacing: 0.25,
+            color: [0.6, 0.6, 0.6],
+            fade_distance: 20.0,
+            show_axes: true,
+        }
+    }
+}
+
+/// Shadow map from the directional light
+#[derive(Debug, Clone)]
+pub struct ShadowParams {
+    pub enabled: bool,
+    /// Lookup offset along the surface normal (world units); larger values remove
+    /// acne on the lit side but detach shadows from their casters
+    pub bias: f32,
+}
+
+impl Default for ShadowParams {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bias: 0.01,
+        }
+    }
+}
+
+/// Camera near/far clip planes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClipPlanes {
+    /// Derive the planes from the model bounds and the camera distance
+    pub auto: bool,
+    pub near: f32,
+    pub far: f32,
+}
+
+impl Default for ClipPlanes {
+    fn default() -> Self {
+        Self {
+            auto: true,
+            near: DEFAULT_NEAR_PLANE,
+            far: DEFAULT_FAR_PLANE,
+        }
+    }
+}
+
+/// Parallax occlusion mapping step count range and default
+pub const MIN_PARALLAX_STEPS: u32 = 4;
+pub const MAX_PARALLAX_STEPS: u32 = 64;
+pub const DEFAULT_PARALLAX_STEPS: u32 = 16;
+
+/// Material parameters controlled by sliders
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct MaterialParams {
     pub metallic_multiplier: f32,
     pub roughness_multiplier: f32,
     pub normal_strength: f32,
     pub ao_strength: f32,
     pub emissive_strength: f32,
+    /// Parallax occlusion depth in UV units (used when a height map is loaded)
     pub displacement_strength: f32,
+    /// Ray-march steps for parallax occlusion mapping
+    pub parallax_steps: u32,
     pub base_color_tint: [f32; 3],
+    /// Tile size along U (and V unless `uv_scale_v` is set)
     pub uv_scale: f32,
+    /// Separate tile size along V; `None` links it to `uv_scale`
+    pub uv_scale_v: Option<f32>,
+    /// Shift of the texture projection in UV units
+    pub uv_offset: [f32; 2],
+    /// Rotation of the texture projection around the UV center
+    pub uv_rotation_degrees: f32,
+    /// Sample the occlusion map with the second UV set (UV1) instead of UV0
+    pub occlusion_uses_uv1: bool,
+    /// Normal map uses the DirectX convention (green = down); flip it to OpenGL's
+    pub flip_normal_y: bool,
+    /// The roughness channel holds glossiness (1 - roughness)
+    pub invert_roughness: bool,
+    /// The occlusion map is inverted (white = occluded)
+    pub invert_ao: bool,
 }
 
 impl Default for MaterialParams {
@@ -82,14 +480,29 @@ impl Default for MaterialParams {
             roughness_multiplier: 0.5,
             normal_strength: 1.0,
             ao_strength: 1.0,
-            emissive_strength: 0.0,
+            emissive_strength: 1.0,
             displacement_strength: 0.1,
+            parallax_steps: DEFAULT_PARALLAX_STEPS,
             base_color_tint: [0.8, 0.8, 0.8],
             uv_scale: 1.0,
+            uv_scale_v: None,
+            uv_offset: [0.0, 0.0],
+            uv_rotation_degrees: 0.0,
+            occlusion_uses_uv1: false,
+            flip_normal_y: false,
+            invert_roughness: false,
+            invert_ao: false,
         }
     }
 }
 
+impl MaterialParams {
+    /// Tile size along U and V
+    pub fn uv_tile_size(&self) -> [f32; 2] {
+        [self.uv_scale, self.uv_scale_v.unwrap_or(self.uv_scale)]
+    }
+}
+
 /// GPU Tessellation parameters
 #[derive(Debug, Clone)]
 pub struct GpuTessellationParams {
@@ -103,6 +516,7 @@ pub struct GpuTessellationParams {
     pub displacement_clamp_max: f32,
     pub screen_space_scale: f32,
     pub distance_scale: f32,
+    pub distance_falloff: TessellationFalloff,
     pub quality_cap: f32,
     pub debug_visualization: TessellationDebugMode,
 }
@@ -120,12 +534,53 @@ impl Default for GpuTessellationParams {
             displacement_clamp_max: 1.0,
             screen_space_scale: 100.0,
             distance_scale: 0.1,
+            distance_falloff: TessellationFalloff::Linear,
             quality_cap: 64.0,
             debug_visualization: TessellationDebugMode::None,
         }
     }
 }
 
+impl GpuTessellationParams {
+    /// Largest distance a vertex can move along its normal (0 when disabled)
+    pub fn max_displacement(&self) -> f32 {
+        if !self.enabled {
+            return 0.0;
+        }
+        let midpoint_reach = self.displacement_midpoint.max(1.0 - self.displacement_midpoint);
+        let unclamped = self.displacement_scale.abs() * midpoint_reach + self.displacement_bias.abs();
+        unclamped.min(self.displacement_clamp_min.abs().max(self.displacement_clamp_max.abs()))
+    }
+}
+
+/// Curve describing how displacement detail drops with camera distance
+/// (evaluated by `displacement_lod` in pbr.wgsl)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TessellationFalloff {
+    #[default]
+    Linear,
+    Quadratic,
+    Exponential,
+}
+
+impl TessellationFalloff {
+    pub fn all() -> &'static [TessellationFalloff] {
+        &[
+            TessellationFalloff::Linear,
+            TessellationFalloff::Quadratic,
+            TessellationFalloff::Exponential,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TessellationFalloff::Linear => "Linear",
+            TessellationFalloff::Quadratic => "Quadratic",
+            TessellationFalloff::Exponential => "Exponential",
+        }
+    }
+}
+
 /// Debug visualization modes for tessellation
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TessellationDebugMode {
@@ -135,12 +590,106 @@ pub enum TessellationDebugMode {
     DisplacementOnly,
 }
 
-/// Light parameters
+/// Material parameter that can vary along an axis of the material matrix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixAxisVariable {
+    Metallic,
+    Roughness,
+    TintBrightness,
+}
+
+impl MatrixAxisVariable {
+    pub fn all() -> &'static [MatrixAxisVariable] {
+        &[
+            MatrixAxisVariable::Metallic,
+            MatrixAxisVariable::Roughness,
+            MatrixAxisVariable::TintBrightness,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MatrixAxisVariable::Metallic => "Metallic",
+            MatrixAxisVariable::Roughness => "Roughness",
+            MatrixAxisVariable::TintBrightness => "Tint Brightness",
+        }
+    }
+
+    /// Value range swept along the axis
+    pub fn range(&self) -> (f32, f32) {
+        match self {
+            MatrixAxisVariable::Metallic => (0.0, 1.0),
+            MatrixAxisVariable::Roughness => (0.05, 1.0),
+            MatrixAxisVariable::TintBrightness => (0.1, 1.0),
+        }
+    }
+
+    /// Value at position `t` in [0, 1] along the axis
+    pub fn value_at(&self, t: f32) -> f32 {
+        let (min, max) = self.range();
+        min + (max - min) * t
+    }
+
+    /// Bit set in the instance override mask (must match pbr.wgsl)
+    pub fn override_bit(&self) -> u32 {
+        1 << self.param_index()
+    }
+
+    /// Component of the instance params vector holding this variable
+    pub fn param_index(&self) -> usize {
+        match self {
+            MatrixAxisVariable::Metallic => 0,
+            MatrixAxisVariable::Roughness => 1,
+            MatrixAxisVariable::TintBrightness => 2,
+        }
+    }
+}
+
+/// Grid of mesh instances sweeping two material parameters
 #[derive(Debug, Clone)]
+pub struct MaterialMatrixParams {
+    pub enabled: bool,
+    pub columns: u32,
+    pub rows: u32,
+    pub spacing: f32,
+    pub x_axis: MatrixAxisVariable,
+    pub y_axis: MatrixAxisVariable,
+}
+
+impl Default for MaterialMatrixParams {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            columns: 5,
+            rows: 5,
+            spacing: 2.5,
+            x_axis: MatrixAxisVariable::Metallic,
+            y_axis: MatrixAxisVariable::Roughness,
+        }
+    }
+}
+
+/// How the light color is edited in the UI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LightColorMode {
+    #[default]
+    Rgb,
+    /// Color derived from a blackbody temperature
+    Kelvin,
+}
+
+/// Light parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct LightParams {
     pub direction: glam::Vec3,
     pub intensity: f32,
+    /// Linear RGB light color (derived from `temperature_kelvin` in Kelvin mode)
     pub color: [f32; 3],
+    pub color_mode: LightColorMode,
+    pub temperature_kelvin: f32,
+    /// Linear RGB color of the constant ambient light
+    pub ambient_color: [f32; 3],
     pub ambient_intensity: f32,
 }
 
@@ -148,13 +697,186 @@ impl Default for LightParams {
     fn default() -> Self {
         Self {
             direction: glam::Vec3::new(-1.0, -1.0, -1.0).normalize(),
-            intensity: 15.0,
+            intensity: 3.0,
             color: [1.0, 1.0, 1.0],
+            color_mode: LightColorMode::Rgb,
+            temperature_kelvin: 6500.0,
+            ambient_color: [1.0, 1.0, 1.0],
             ambient_intensity: 0.4,
         }
     }
 }
 
+/// Approximate the color of a blackbody at `kelvin` (1000K–40000K) as linear RGB
+/// normalized so the brightest channel is 1. Uses Tanner Helland's curve fit,
+/// which produces sRGB values, then converts them to linear.
+pub fn kelvin_to_rgb(kelvin: f32) -> [f32; 3] {
+    let t = kelvin.clamp(1000.0, 40000.0) / 100.0;
+    
+    let red = if t <= 66.0 {
+        255.0
+    } else {
+        329.698_73 * (t - 60.0).powf(-0.133_204_76)
+    };
+    let green = if t <= 66.0 {
+        99.470_8 * t.ln() - 161.119_57
+    } else {
+        288.122_16 * (t - 60.0).powf(-0.075_514_85)
+    };
+    let blue = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.517_73 * (t - 10.0).ln() - 305.044_8
+    };
+    
+    let srgb_to_linear = |c: f32| {
+        let c = (c / 255.0).clamp(0.0, 1.0);
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    let rgb = [srgb_to_linear(red), srgb_to_linear(green), srgb_to_linear(blue)];
+    let max = rgb[0].max(rgb[1]).max(rgb[2]).max(1e-6);
+    [rgb[0] / max, rgb[1] / max, rgb[2] / max]
+}
+
+/// Ready-made light setups, selectable without loading an environment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LightingPreset {
+    Default,
+    StudioThreePoint,
+}
+
+impl LightingPreset {
+    pub fn all() -> &'static [LightingPreset] {
+        &[LightingPreset::Default, LightingPreset::StudioThreePoint]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LightingPreset::Default => "Single Light",
+            LightingPreset::StudioThreePoint => "Three-Point Studio",
+        }
+    }
+
+    /// Lights of the preset, key light first
+    pub fn lights(&self) -> Vec<LightParams> {
+        match self {
+            LightingPreset::Default => vec![LightParams::default()],
+            LightingPreset::StudioThreePoint => studio_three_point_lights(),
+        }
+    }
+}
+
+/// Key, fill and rim lights for a camera looking from +Z: a warm key from the upper
+/// left front, a dim cool fill from the right and a bright rim from behind
+pub fn studio_three_point_lights() -> Vec<LightParams> {
+    let light = |direction: Vec3, intensity: f32, temperature_kelvin: f32| LightParams {
+        direction: direction.normalize(),
+        intensity,
+        color: kelvin_to_rgb(temperature_kelvin),
+        color_mode: LightColorMode::Kelvin,
+        temperature_kelvin,
+        ambient_intensity: 0.15,
+        ..LightParams::default()
+    };
+    vec![
+        light(Vec3::new(-1.0, 1.2, 1.0), 3.0, 5600.0),
+        light(Vec3::new(1.0, 0.3, 0.8), 0.8, 7500.0),
+        light(Vec3::new(0.3, 0.8, -1.0), 2.5, 6500.0),
+    ]
+}
+
+/// Animated orbit of the main light around the model
+#[derive(Debug, Clone)]
+pub struct LightOrbitParams {
+    pub playing: bool,
+    /// Time for one full revolution, in seconds
+    pub period_seconds: f32,
+}
+
+impl Default for LightOrbitParams {
+    fn default() -> Self {
+        Self {
+            playing: false,
+            period_seconds: 10.0,
+        }
+    }
+}
+
+impl LightOrbitParams {
+    /// Rotation of the light direction around the world Y axis for a frame of `dt` seconds
+    pub fn step(&self, dt: f32) -> Quat {
+        if !self.playing || self.period_seconds <= 0.0 {
+            return Quat::IDENTITY;
+        }
+        Quat::from_rotation_y(std::f32::consts::TAU * dt / self.period_seconds)
+    }
+}
+
+/// Turntable auto-rotation of the model
+#[derive(Debug, Clone)]
+pub struct TurntableParams {
+    pub auto_rotate: bool,
+    /// Rotation speed around the world Y axis in degrees per second (negative spins clockwise)
+    pub speed_degrees: f32,
+}
+
+impl Default for TurntableParams {
+    fn default() -> Self {
+        Self {
+            auto_rotate: false,
+            speed_degrees: 30.0,
+        }
+    }
+}
+
+impl TurntableParams {
+    /// Rotation of the model around the world Y axis for a frame of `dt` seconds
+    pub fn step(&self, dt: f32) -> Quat {
+        if !self.auto_rotate {
+            return Quat::IDENTITY;
+        }
+        Quat::from_rotation_y(self.speed_degrees.to_radians() * dt)
+    }
+}
+
+/// Environment map (IBL/skybox) settings
+#[derive(Debug, Clone)]
+pub struct EnvironmentParams {
+    /// Path of the loaded environment map, if any
+    pub path: Option<String>,
+    /// Azimuth rotation of the environment around the world Y axis, in degrees
+    pub rotation_degrees: f32,
+    /// Draw the environment behind the model instead of the background color
+    pub show_background: bool,
+}
+
+impl Default for EnvironmentParams {
+    fn default() -> Self {
+        Self {
+            path: None,
+            rotation_degrees: 0.0,
+            show_background: true,
+        }
+    }
+}
+
+/// Outcome of a texture load, shown in the Textures tab
+#[derive(Debug, Clone, Default)]
+pub struct TextureLoadReport {
+    /// Number of maps uploaded
+    pub loaded: usize,
+    pub failures: Vec<TextureLoadFailure>,
+}
+
+/// Slot names shared by `TextureHandles`, `TexturePaths` and the texture rows
+pub const TEXTURE_SLOTS: [&str; 8] = ["base_color", "normal", "roughness", "metallic", "orm", "ao", "emissive", "height"];
+
 /// Texture handles (using paths for now, will load into wgpu later)
 #[derive(Debug, Clone, Default)]
 pub struct TextureHandles {
@@ -166,29 +888,96 @@ pub struct TextureHandles {
     pub ao: Option<String>,
     pub emissive: Option<String>,
     pub height: Option<String>,
+    /// Slots the user assigned explicitly; these win over folder detection
+    pub overrides: HashSet<&'static str>,
 }
 
 impl TextureHandles {
+    fn slot_mut(&mut self, texture_type: &str) -> Option<&mut Option<String>> {
+        match texture_type {
+            "base_color" => Some(&mut self.base_color),
+            "normal" => Some(&mut self.normal),
+            "roughness" => Some(&mut self.roughness),
+            "metallic" => Some(&mut self.metallic),
+            "orm" => Some(&mut self.orm),
+            "ao" => Some(&mut self.ao),
+            "emissive" => Some(&mut self.emissive),
+            "height" => Some(&mut self.height),
+            _ => None,
+        }
+    }
+    
+    pub fn get(&self, texture_type: &str) -> Option<&String> {
+        match texture_type {
+            "base_color" => self.base_color.as_ref(),
+            "normal" => self.normal.as_ref(),
+            "roughness" => self.roughness.as_ref(),
+            "metallic" => self.metallic.as_ref(),
+            "orm" => self.orm.as_ref(),
+            "ao" => self.ao.as_ref(),
+            "emissive" => self.emissive.as_ref(),
+            "height" => self.height.as_ref(),
+            _ => None,
+        }
+    }
+    
     pub fn get_file_name(&self, texture_type: &str) -> Option<String> {
-        let path = match texture_type {
-            "base_color" => &self.base_color,
-            "normal" => &self.normal,
-            "roughness" => &self.roughness,
-            "metallic" => &self.metallic,
-            "orm" => &self.orm,
-            "ao" => &self.ao,
-            "emissive" => &self.emissive,
-            "height" => &self.height,
-            _ => return None,
-        };
-        
-        path.as_ref().and_then(|p| {
+        self.get(texture_type).and_then(|p| {
             std::path::Path::new(p)
                 .file_name()
                 .and_then(|n| n.to_str())
                 .map(|s| s.to_string())
         })
     }
+    
+    /// Explicitly assign a file to a slot, overriding auto-detection
+    pub fn set_override(&mut self, texture_type: &'static str, path: String) {
+        if let Some(slot) = self.slot_mut(texture_type) {
+            *slot = Some(path);
+            self.overrides.insert(texture_type);
+        }
+    }
+    
+    /// Drop an explicit assignment so the slot falls back to auto-detection
+    pub fn clear_override(&mut self, texture_type: &'static str) {
+        if self.overrides.remove(texture_type) {
+            if let Some(slot) = self.slot_mut(texture_type) {
+                *slot = None;
+            }
+        }
+    }
+    
+    pub fn is_overridden(&self, texture_type: &str) -> bool {
+        self.overrides.contains(texture_type)
+    }
+    
+    /// Empty a slot and keep auto-detection from filling it again
+    pub fn clear(&mut self, texture_type: &'static str) {
+        if let Some(slot) = self.slot_mut(texture_type) {
+            *slot = None;
+            self.overrides.insert(texture_type);
+        }
+    }
+    
+    /// Put the manually assigned (or cleared) slots over the detected ones
+    pub fn apply_overrides(&self, paths: &mut TexturePaths) {
+        for slot in &self.overrides {
+            paths.set(slot, self.get(slot).map(PathBuf::from));
+        }
+    }
+    
+    /// Show the detected files in every slot that wasn't assigned manually, dropping
+    /// the ones detected in a previously loaded folder
+    pub fn set_detected(&mut self, detected: &TexturePaths) {
+        for slot in TEXTURE_SLOTS {
+            if self.is_overridden(slot) {
+                continue;
+            }
+            if let Some(handle) = self.slot_mut(slot) {
+                *handle = detected.get(slot).map(|path| path.to_string_lossy().to_string());
+            }
+        }
+    }
 }
 
 /// Tracks which textures have been loaded
@@ -208,6 +997,48 @@ impl LoadedTextures {
     pub fn reset(&mut self) {
         *self = Self::default();
     }
+    
+    /// Status of the maps in a loaded texture set. A packed metallic-roughness
+    /// map provides both channels.
+    pub fn from_paths(paths: &TexturePaths) -> Self {
+        let packed = paths.metallic_roughness.is_some();
+        Self {
+            base_color: paths.base_color.is_some(),
+            normal: paths.normal.is_some(),
+            roughness: paths.roughness.is_some() || packed,
+            metallic: paths.metallic.is_some() || packed,
+            orm: paths.orm.is_some(),
+            ao: paths.ao.is_some(),
+            emissive: paths.emissive.is_some(),
+            height: paths.height.is_some(),
+        }
+    }
+}
+
+/// Maps that contribute to Lit shading. A disabled map falls back to its neutral
+/// default (flat normal, white AO, ...) to isolate which one causes an artifact;
+/// the debug view modes still show it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnabledMaps {
+    pub base_color: bool,
+    pub normal: bool,
+    pub metallic_roughness: bool,
+    pub ao: bool,
+    pub emissive: bool,
+    pub height: bool,
+}
+
+impl Default for EnabledMaps {
+    fn default() -> Self {
+        Self {
+            base_color: true,
+            normal: true,
+            metallic_roughness: true,
+            ao: true,
+            emissive: true,
+            height: true,
+        }
+    }
 }
 
 /// Main application state
@@ -215,24 +1046,67 @@ pub struct AppState {
     // Current settings
     pub current_mesh: MeshType,
     pub view_mode: ViewMode,
+    pub normal_debug_space: NormalDebugSpace,
+    pub tangent_frame_axis: TangentFrameAxis,
     pub material_params: MaterialParams,
     pub light_params: LightParams,
+    // Unshadowed fill/rim lights next to the key light; beyond MAX_SECONDARY_LIGHTS they are ignored
+    pub secondary_lights: Vec<LightParams>,
+    pub light_orbit: LightOrbitParams,
+    pub turntable: TurntableParams,
+    pub environment: EnvironmentParams,
+    
+    // Material matrix (instanced parameter sweep)
+    pub material_matrix: MaterialMatrixParams,
     
     // CPU Tessellation (for mesh generation)
     pub tessellation_level: u32,
+    /// Corner radius of the rounded rectangle mesh
+    pub corner_radius: f32,
     
     // GPU Tessellation parameters
     pub gpu_tessellation: GpuTessellationParams,
     
     // Texture folder
     pub texture_folder: Option<String>,
+    // Also detect textures in subfolders of the texture folder
+    pub search_subfolders: bool,
     
     // Loaded texture info
     pub loaded_textures: LoadedTextures,
+    // Maps used by Lit shading (per-map isolation)
+    pub enabled_maps: EnabledMaps,
     
     // Texture handles (paths)
     pub texture_handles: TextureHandles,
     
+    // Size and color space of the uploaded textures
+    pub texture_info: TextureSetInfo,
+    
+    // Image files in the texture folder/archive (drag sources for slot assignment)
+    pub texture_folder_files: Vec<String>,
+    // (maps decoded, maps to decode) while a texture set loads in the background
+    pub texture_load_progress: Option<(usize, usize)>,
+    // Outcome of the last texture load
+    pub last_texture_load: Option<TextureLoadReport>,
+    // Slots several folder files matched equally well
+    pub texture_conflicts: Vec<DetectionConflict>,
+    // Slot to reload on its own after an explicit assignment
+    pub texture_slot_reload: Option<&'static str>,
+    
+    // Textures above this size are downscaled on load; limited by the device
+    pub max_texture_size: u32,
+    pub device_max_texture_size: u32,
+    
+    // Texture filtering (preset and individual settings)
+    pub texture_quality: TextureQuality,
+    pub texture_filter: TextureFilterSettings,
+    // Per-slot wrap and filter overrides on top of `texture_filter`
+    pub slot_samplers: SlotSamplers,
+    pub samplers_changed: bool,
+    /// Highest anisotropy the device supports (1 without anisotropic filtering)
+    pub max_anisotropy: u16,
+    
     // Model rotation
     pub model_rotation: Quat,
     pub is_rotating_model: bool,
@@ -243,6 +1117,7 @@ pub struct AppState {
     // Change flags
     pub mesh_changed: bool,
     pub material_changed: bool,
+    pub instances_changed: bool,
     pub textures_need_reload: bool,
     
     // Drag and drop hover state
@@ -250,6 +1125,195 @@ pub struct AppState {
     
     // UI panel width (for resizable panel)
     pub ui_panel_width: f32,
+    
+    // Background clear color of the 3D viewport
+    pub background_color: [f32; 3],
+    
+    // Shading quality options
+    pub shading: ShadingParams,
+    
+    // Debug overlays on the surface
+    pub overlay: OverlayParams,
+    
+    // Reference grid on the ground plane
+    pub grid: GridParams,
+    
+    // Self-shadowing from the directional light
+    pub shadows: ShadowParams,
+    
+    // Resolution of the 3D scene relative to the viewport (egui stays at native resolution)
+    pub render_scale: f32,
+    
+    // Multisample anti-aliasing of the 3D view; the choices are limited by the device
+    pub msaa_samples: u32,
+    pub supported_msaa_samples: Vec<u32>,
+    
+    // Vertical sync; the choices are limited by the surface
+    pub present_mode: PresentMode,
+    pub supported_present_modes: Vec<PresentMode>,
+    
+    // Depth-only pass before the color pass; only pays off for expensive fragment work
+    pub depth_prepass: bool,
+    
+    // Line rasterization for the wireframe view needs a device feature
+    pub wireframe_supported: bool,
+    
+    // Vertical field of view of the viewport camera
+    pub fov_degrees: f32,
+    pub clip_planes: ClipPlanes,
+    
+    // Time constant of the camera easing in seconds (0 = instant)
+    pub camera_smoothing: f32,
+    
+    // Screenshot of the 3D viewport to capture on the next frame
+    pub screenshot_request: Option<PathBuf>,
+    // Capture the whole window including the UI panel instead of only the 3D viewport
+    pub screenshot_include_ui: bool,
+    
+    // Model-space bounds of the current mesh, optionally drawn as a wire box
+    pub mesh_bounds: Option<Aabb>,
+    pub show_bounds: bool,
+    // Sun and arrow drawn in the scene along the light direction
+    pub show_light_gizmo: bool,
+    pub mesh_stats: MeshStats,
+    
+    // Vertical gap between coplanar presentation layers (model base, grid floor) to avoid z-fighting
+    pub layer_separation: f32,
+    
+    // "Reset All" confirmation popup and pending camera reset
+    pub confirm_reset_all: bool,
+    pub camera_reset_requested: bool,
+    
+    // Custom model file: the loaded one and a pending load
+    pub model_path: Option<String>,
+    pub model_load_request: Option<PathBuf>,
+    // Ignore tangents stored in model files and generate them from the UVs
+    pub regenerate_model_tangents: bool,
+    
+    // Recently loaded texture folders/archives and models, most recent first
+    pub recent_folders: Vec<String>,
+    pub recent_models: Vec<String>,
+    
+    // Pending HDR environment map load
+    pub environment_load_request: Option<PathBuf>,
+    
+    // Reframe the camera on the mesh bounds next frame
+    pub frame_requested: bool,
+    
+    // Move the camera to a canonical view next frame (true = snap without animating)
+    pub view_preset_request: Option<(ViewPreset, bool)>,
+    
+    // Captured log messages (only with --debug)
+    pub debug_log: Option<LogBuffer>,
+    pub show_debug_log: bool,
+    
+    // Toast notifications for load results and errors
+    pub notifications: Notifications,
+    
+    // Frame-time history and the FPS overlay (F3)
+    pub frame_stats: FrameStats,
+    pub show_frame_stats: bool,
+    
+    // Borderless fullscreen: mirrors the window, toggled on request (F11)
+    pub fullscreen: bool,
+    pub fullscreen_toggle_requested: bool,
+    
+    // Text field for pasting share codes
+    pub share_code_input: String,
+    
+    // Command console (toggled with `~`)
+    pub console: Console,
+}
+
+impl AppState {
+    /// Vertical offset for a presentation layer lying on the ground plane.
+    /// Layer 0 is the model base and layer 1 the grid floor; each layer sits
+    /// one separation step lower so coplanar surfaces never z-fight.
+    pub fn ground_layer_offset(&self, layer: u32) -> f32 {
+        -(layer as f32) * self.layer_separation
+    }
+    
+    /// World-space height of the ground plane: just below the base of the mesh
+    pub fn ground_height(&self) -> f32 {
+        let base = self.mesh_bounds.map(|bounds| bounds.min.y).unwrap_or(0.0);
+        base + self.ground_layer_offset(1)
+    }
+    
+    /// Bounding sphere (center, radius) of everything the PBR pipeline draws:
+    /// the rotated mesh grown by the displacement, and every material matrix instance
+    pub fn scene_bounding_sphere(&self) -> (Vec3, f32) {
+        let (center, mesh_radius) = self.mesh_bounds
+            .map(|bounds| (self.model_rotation * bounds.center(), bounds.size().length() * 0.5))
+            .unwrap_or((Vec3::ZERO, 1.0));
+        let radius = mesh_radius
+            + self.gpu_tessellation.max_displacement()
+            + crate::instance::max_offset(&self.material_matrix);
+        (center, radius)
+    }
+    
+    /// Near and far clip distances for a camera at `camera_position`. Auto mode
+    /// tightly encloses the rotated, displaced mesh and the material matrix (and the
+    /// grid floor when shown) so neither large nor tiny models lose depth precision.
+    pub fn clip_distances(&self, camera_position: Vec3) -> (f32, f32) {
+        if !self.clip_planes.auto {
+            return (self.clip_planes.near, self.clip_planes.far);
+        }
+        let (center, radius) = self.scene_bounding_sphere();
+        let distance = (camera_position - center).length();
+        let mut far = (distance + radius) * 1.5;
+        if self.grid.enabled {
+            far = far.max(self.grid.fade_distance * 1.1);
+        }
+        let near = ((distance - radius) * 0.5).max(far * MIN_NEAR_FAR_RATIO);
+        (near, far)
+    }
+    
+    /// Record a successfully loaded texture folder or archive
+    pub fn remember_folder(&mut self, folder: &str) {
+        push_recent(&mut self.recent_folders, folder);
+    }
+    
+    /// Replace the key and secondary lights with those of a preset
+    pub fn apply_lighting_preset(&mut self, preset: LightingPreset) {
+        let mut lights = preset.lights().into_iter();
+        self.light_params = lights.next().unwrap_or_default();
+        self.secondary_lights = lights.collect();
+        self.material_changed = true;
+    }
+    
+    /// Record a successfully loaded model file
+    pub fn remember_model(&mut self, model: &str) {
+        push_recent(&mut self.recent_models, model);
+    }
+    
+    /// Restore material, light, tessellation and view settings to their defaults
+    /// and request a camera reset. Loaded textures and the current mesh are kept.
+    pub fn reset_all(&mut self) {
+        self.material_params = MaterialParams::default();
+        self.light_params = LightParams::default();
+        self.secondary_lights.clear();
+        self.gpu_tessellation = GpuTessellationParams::default();
+        self.view_mode = ViewMode::default();
+        self.enabled_maps = EnabledMaps::default();
+        self.model_rotation = Quat::IDENTITY;
+        self.fov_degrees = DEFAULT_FOV_DEGREES;
+        self.clip_planes = ClipPlanes::default();
+        self.camera_reset_requested = true;
+        self.material_changed = true;
+        self.mesh_changed = true;
+    }
+}
+
+/// Move `entry` to the front of a recent list, dropping duplicates and the oldest entries
+fn push_recent(list: &mut Vec<String>, entry: &str) {
+    list.retain(|existing| existing != entry);
+    list.insert(0, entry.to_string());
+    list.truncate(MAX_RECENT_ENTRIES);
+}
+
+/// Drop recent entries whose file or folder no longer exists
+pub fn prune_recent(list: &mut Vec<String>) {
+    list.retain(|entry| std::path::Path::new(entry).exists());
 }
 
 impl Default for AppState {
@@ -257,21 +1321,86 @@ impl Default for AppState {
         Self {
             current_mesh: MeshType::Sphere,
             view_mode: ViewMode::Lit,
+            normal_debug_space: NormalDebugSpace::default(),
+            tangent_frame_axis: TangentFrameAxis::default(),
             material_params: MaterialParams::default(),
             light_params: LightParams::default(),
+            secondary_lights: Vec::new(),
+            light_orbit: LightOrbitParams::default(),
+            turntable: TurntableParams::default(),
+            environment: EnvironmentParams::default(),
+            material_matrix: MaterialMatrixParams::default(),
             tessellation_level: 32,
+            corner_radius: DEFAULT_CORNER_RADIUS,
             gpu_tessellation: GpuTessellationParams::default(),
             texture_folder: None,
+            search_subfolders: false,
             loaded_textures: LoadedTextures::default(),
+            enabled_maps: EnabledMaps::default(),
             texture_handles: TextureHandles::default(),
+            texture_info: TextureSetInfo::default(),
+            texture_folder_files: Vec::new(),
+            texture_conflicts: Vec::new(),
+            texture_load_progress: None,
+            last_texture_load: None,
+            texture_slot_reload: None,
+            max_texture_size: DEFAULT_MAX_TEXTURE_SIZE,
+            device_max_texture_size: DEFAULT_MAX_TEXTURE_SIZE,
+            texture_quality: TextureQuality::default(),
+            texture_filter: TextureFilterSettings::default(),
+            slot_samplers: SlotSamplers::default(),
+            max_anisotropy: 16,
+            samplers_changed: false,
             model_rotation: Quat::IDENTITY,
             is_rotating_model: false,
             mesh_changed: false,
             material_changed: false,
+            instances_changed: false,
             textures_need_reload: false,
             drag_hover_path: None,
             ui_panel_width: DEFAULT_UI_PANEL_WIDTH,
             ui_tab: UiTab::default(),
+            background_color: DEFAULT_BACKGROUND_COLOR,
+            shading: ShadingParams::default(),
+            overlay: OverlayParams::default(),
+            grid: GridParams::default(),
+            shadows: ShadowParams::default(),
+            render_scale: 1.0,
+            msaa_samples: DEFAULT_MSAA_SAMPLES,
+            supported_msaa_samples: vec![1, DEFAULT_MSAA_SAMPLES],
+            present_mode: PresentMode::default(),
+            supported_present_modes: vec![PresentMode::Fifo],
+            depth_prepass: false,
+            wireframe_supported: false,
+            fov_degrees: DEFAULT_FOV_DEGREES,
+            clip_planes: ClipPlanes::default(),
+            camera_smoothing: 0.0,
+            screenshot_request: None,
+            screenshot_include_ui: false,
+            mesh_bounds: None,
+            mesh_stats: MeshStats::default(),
+            show_bounds: false,
+            show_light_gizmo: false,
+            layer_separation: DEFAULT_LAYER_SEPARATION,
+            confirm_reset_all: false,
+            camera_reset_requested: false,
+            model_path: None,
+            model_load_request: None,
+            regenerate_model_tangents: false,
+            recent_folders: Vec::new(),
+            recent_models: Vec::new(),
+            environment_load_request: None,
+            frame_requested: false,
+            view_preset_request: None,
+            debug_log: None,
+            show_debug_log: true,
+            notifications: Notifications::default(),
+            frame_stats: FrameStats::default(),
+            show_frame_stats: false,
+            fullscreen: false,
+            fullscreen_toggle_requested: false,
+            share_code_input: String::new(),
+            console: Console::default(),
         }
     }
 }
@@ -1,7 +1,249 @@
 //! Texture loading and management for wgpu
 
+use std::borrow::Cow;
 use wgpu::*;
-use image::DynamicImage;
+use half::f16;
+use image::{DynamicImage, ImageBuffer, Pixel, Rgba32FImage, RgbaImage};
+
+/// How texel values are interpreted when sampled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorSpace {
+    /// Color data (base color, emissive), decoded from sRGB to linear on sampling
+    #[default]
+    Srgb,
+    /// Non-color data (normal, metallic, roughness, AO, height), sampled as stored
+    Linear,
+}
+
+impl ColorSpace {
+    /// Texture format used to upload 8-bit RGBA data in this color space
+    pub fn format(&self) -> TextureFormat {
+        match self {
+            ColorSpace::Srgb => TextureFormat::Rgba8UnormSrgb,
+            ColorSpace::Linear => TextureFormat::Rgba8Unorm,
+        }
+    }
+    
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColorSpace::Srgb => "sRGB",
+            ColorSpace::Linear => "Linear",
+        }
+    }
+}
+
+/// Properties of an uploaded texture, recorded for display in the UI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextureInfo {
+    pub width: u32,
+    pub height: u32,
+    pub format: TextureFormat,
+    pub color_space: ColorSpace,
+}
+
+impl TextureInfo {
+    /// Read the size, format and color space back from the uploaded texture
+    pub fn from_texture(texture: &Texture) -> Self {
+        Self {
+            width: texture.width(),
+            height: texture.height(),
+            format: texture.format(),
+            color_space: if texture.format().is_srgb() {
+                ColorSpace::Srgb
+            } else {
+                ColorSpace::Linear
+            },
+        }
+    }
+
+    /// Short channel layout name; the color space is shown separately
+    pub fn format_name(&self) -> String {
+        match self.format.remove_srgb_suffix() {
+            TextureFormat::Rgba8Unorm => "RGBA8".to_string(),
+            TextureFormat::Rgba16Float => "RGBA16F".to_string(),
+            TextureFormat::Rgba32Float => "RGBA32F".to_string(),
+            other => format!("{:?}", other),
+        }
+    }
+
+    /// Non-power-of-two sizes break mip chains on some exporters and engines
+    pub fn is_power_of_two(&self) -> bool {
+        self.width.is_power_of_two() && self.height.is_power_of_two()
+    }
+}
+
+/// How UVs outside the 0-1 range are resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextureWrapMode {
+    /// Tile the texture
+    #[default]
+    Repeat,
+    /// Stretch the edge texels
+    Clamp,
+    /// Tile, flipping every other copy
+    Mirror,
+}
+
+impl TextureWrapMode {
+    pub fn all() -> &'static [TextureWrapMode] {
+        &[TextureWrapMode::Repeat, TextureWrapMode::Clamp, TextureWrapMode::Mirror]
+    }
+    
+    pub fn name(&self) -> &'static str {
+        match self {
+            TextureWrapMode::Repeat => "Repeat",
+            TextureWrapMode::Clamp => "Clamp",
+            TextureWrapMode::Mirror => "Mirror",
+        }
+    }
+    
+    pub fn address_mode(&self) -> AddressMode {
+        match self {
+            TextureWrapMode::Repeat => AddressMode::Repeat,
+            TextureWrapMode::Clamp => AddressMode::ClampToEdge,
+            TextureWrapMode::Mirror => AddressMode::MirrorRepeat,
+        }
+    }
+}
+
+/// Sampler and mipmap settings shared by all material textures
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextureFilterSettings {
+    /// Blend between mip levels (trilinear) instead of picking the nearest one
+    pub linear_mipmaps: bool,
+    /// Maximum anisotropy (1 disables anisotropic filtering)
+    pub anisotropy: u16,
+    /// Generate a full mip chain on load
+    pub generate_mipmaps: bool,
+    /// Addressing outside the 0-1 UV range (not part of the quality presets)
+    pub wrap_mode: TextureWrapMode,
+    /// Nearest-neighbor magnification and minification, for pixel art
+    /// (not part of the quality presets; disables anisotropy)
+    pub nearest: bool,
+}
+
+impl TextureFilterSettings {
+    /// The mip and anisotropy settings of a quality preset, keeping the wrap and filter mode
+    pub fn with_preset(&self, preset: TextureFilterSettings) -> Self {
+        Self {
+            wrap_mode: self.wrap_mode,
+            nearest: self.nearest,
+            ..preset
+        }
+    }
+    
+    /// These settings with the overrides of one material slot applied
+    pub fn for_slot(&self, slot: &SlotSampler) -> Self {
+        Self {
+            wrap_mode: slot.wrap_mode.unwrap_or(self.wrap_mode),
+            nearest: slot.nearest.unwrap_or(self.nearest),
+            // As with the global setting, blending mips would smear nearest-filtered pixels
+            linear_mipmaps: self.linear_mipmaps && slot.nearest != Some(true),
+            ..*self
+        }
+    }
+}
+
+impl Default for TextureFilterSettings {
+    fn default() -> Self {
+        Self {
+            linear_mipmaps: true,
+            anisotropy: 4,
+            generate_mipmaps: true,
+            wrap_mode: TextureWrapMode::default(),
+            nearest: false,
+        }
+    }
+}
+
+/// Sampler settings of one material slot that differ from the global filtering
+/// (`None` follows the global setting)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SlotSampler {
+    pub wrap_mode: Option<TextureWrapMode>,
+    pub nearest: Option<bool>,
+}
+
+/// Per-slot sampler overrides, one per sampler of the texture set
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SlotSamplers {
+    pub base_color: SlotSampler,
+    pub normal: SlotSampler,
+    /// Shared by the metallic, roughness and ORM maps
+    pub metallic_roughness: SlotSampler,
+    pub ao: SlotSampler,
+    pub emissive: SlotSampler,
+    pub height: SlotSampler,
+}
+
+impl SlotSamplers {
+    /// Overrides of a slot by its `TextureHandles` or `TextureSet` name
+    pub fn get(&self, slot: &str) -> &SlotSampler {
+        match slot {
+            "base_color" => &self.base_color,
+            "normal" => &self.normal,
+            "ao" => &self.ao,
+            "emissive" => &self.emissive,
+            "height" => &self.height,
+            _ => &self.metallic_roughness,
+        }
+    }
+    
+    pub fn get_mut(&mut self, slot: &str) -> &mut SlotSampler {
+        match slot {
+            "base_color" => &mut self.base_color,
+            "normal" => &mut self.normal,
+            "ao" => &mut self.ao,
+            "emissive" => &mut self.emissive,
+            "height" => &mut self.height,
+            _ => &mut self.metallic_roughness,
+        }
+    }
+}
+
+/// Create the sampler used for material textures
+pub fn create_sampler(device: &Device, filter: &TextureFilterSettings) -> Sampler {
+    // Anisotropic filtering requires linear filtering in every dimension
+    let anisotropy = if filter.nearest { 1 } else { filter.anisotropy.clamp(1, 16) };
+    let mipmap_filter = if filter.linear_mipmaps || anisotropy > 1 {
+        FilterMode::Linear
+    } else {
+        FilterMode::Nearest
+    };
+    let filter_mode = if filter.nearest { FilterMode::Nearest } else { FilterMode::Linear };
+    device.create_sampler(&SamplerDescriptor {
+        label: Some("material_sampler"),
+        address_mode_u: filter.wrap_mode.address_mode(),
+        address_mode_v: filter.wrap_mode.address_mode(),
+        address_mode_w: filter.wrap_mode.address_mode(),
+        mag_filter: filter_mode,
+        min_filter: filter_mode,
+        mipmap_filter,
+        anisotropy_clamp: anisotropy,
+        ..Default::default()
+    })
+}
+
+/// Options applied when loading a texture from an image
+#[derive(Debug, Clone, Copy)]
+pub struct TextureLoadOptions {
+    /// Images larger than this in either dimension are downscaled before upload
+    pub max_size: u32,
+    /// Color space the texel data is stored in
+    pub color_space: ColorSpace,
+    /// Mipmap generation and sampler filtering
+    pub filter: TextureFilterSettings,
+}
+
+impl Default for TextureLoadOptions {
+    fn default() -> Self {
+        Self {
+            max_size: Limits::default().max_texture_dimension_2d,
+            color_space: ColorSpace::default(),
+            filter: TextureFilterSettings::default(),
+        }
+    }
+}
 
 /// Load a texture from bytes
 pub fn load_texture(
@@ -9,66 +251,141 @@ pub fn load_texture(
     queue: &Queue,
     bytes: &[u8],
     label: Option<&str>,
+    options: &TextureLoadOptions,
 ) -> Result<(Texture, TextureView, Sampler), anyhow::Error> {
     let img = image::load_from_memory(bytes)?;
-    load_texture_from_image(device, queue, &img, label)
+    load_texture_from_image(device, queue, &img, label, options)
 }
 
-/// Load a texture from an image
+/// Load a texture from an image. Float images (HDR, EXR) keep their range and
+/// are uploaded as linear `Rgba16Float`; everything else is converted to 8-bit RGBA.
 pub fn load_texture_from_image(
     device: &Device,
     queue: &Queue,
     img: &DynamicImage,
     label: Option<&str>,
+    options: &TextureLoadOptions,
 ) -> Result<(Texture, TextureView, Sampler), anyhow::Error> {
-    let rgba = img.to_rgba8();
-    let dimensions = rgba.dimensions();
+    let resized = downscaled(img, options.max_size, label);
+    let img = resized.as_ref().unwrap_or(img);
     
+    let texture = if matches!(img, DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_)) {
+        upload_with_mips(device, queue, img.to_rgba32f(), TextureFormat::Rgba16Float, rgba16f_bytes, label, options)
+    } else {
+        upload_with_mips(device, queue, img.to_rgba8(), options.color_space.format(), rgba8_bytes, label, options)
+    };
+    let view = texture.create_view(&TextureViewDescriptor::default());
+    let sampler = create_sampler(device, &options.filter);
+    
+    Ok((texture, view, sampler))
+}
+
+/// Downscale an oversized image (keeping its aspect ratio) to avoid VRAM blowups;
+/// `None` if it already fits within `max_size`
+pub fn downscaled(img: &DynamicImage, max_size: u32, label: Option<&str>) -> Option<DynamicImage> {
+    let max_size = max_size.max(1);
+    if img.width() <= max_size && img.height() <= max_size {
+        return None;
+    }
+    let resized = img.resize(max_size, max_size, image::imageops::FilterType::Triangle);
+    log::info!(
+        "Downscaled {} from {}x{} to {}x{} (max texture size {})",
+        label.unwrap_or("texture"),
+        img.width(),
+        img.height(),
+        resized.width(),
+        resized.height(),
+        max_size,
+    );
+    Some(resized)
+}
+
+fn rgba8_bytes(image: &RgbaImage) -> Cow<'_, [u8]> {
+    Cow::Borrowed(image.as_raw())
+}
+
+fn rgba16f_bytes(image: &Rgba32FImage) -> Cow<'_, [u8]> {
+    let max = f16::MAX.to_f32();
+    Cow::Owned(
+        image.as_raw()
+            .iter()
+            // NaN texels become 0; values beyond the half float range are clamped
+            .map(|v| if v.is_nan() { 0.0 } else { v.clamp(-max, max) })
+            .flat_map(|v| f16::from_f32(v).to_le_bytes())
+            .collect(),
+    )
+}
+
+/// Converts an image to the bytes of its GPU texture format
+type PixelPacker<P> = for<'a> fn(&'a ImageBuffer<P, Vec<<P as Pixel>::Subpixel>>) -> Cow<'a, [u8]>;
+
+/// Create a texture from its first mip level and, if enabled, build the rest of the
+/// chain on the CPU, each level from the previous one
+fn upload_with_mips<P>(
+    device: &Device,
+    queue: &Queue,
+    base: ImageBuffer<P, Vec<P::Subpixel>>,
+    format: TextureFormat,
+    to_bytes: PixelPacker<P>,
+    label: Option<&str>,
+    options: &TextureLoadOptions,
+) -> Texture
+where
+    P: Pixel + 'static,
+    P::Subpixel: 'static,
+{
     let size = Extent3d {
-        width: dimensions.0,
-        height: dimensions.1,
+        width: base.width(),
+        height: base.height(),
         depth_or_array_layers: 1,
     };
+    let mip_level_count = if options.filter.generate_mipmaps {
+        size.max_mips(TextureDimension::D2)
+    } else {
+        1
+    };
     
     let texture = device.create_texture(&TextureDescriptor {
         label,
         size,
-        mip_level_count: 1,
+        mip_level_count,
         sample_count: 1,
         dimension: TextureDimension::D2,
-        format: TextureFormat::Rgba8UnormSrgb,
+        format,
         usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
         view_formats: &[],
     });
     
-    queue.write_texture(
-        ImageCopyTexture {
-            texture: &texture,
-            mip_level: 0,
-            origin: Origin3d::ZERO,
-            aspect: TextureAspect::All,
-        },
-        &rgba,
-        ImageDataLayout {
-            offset: 0,
-            bytes_per_row: Some(4 * dimensions.0),
-            rows_per_image: Some(dimensions.1),
-        },
-        size,
-    );
+    let mut previous = base;
+    for mip_level in 0..mip_level_count {
+        let mip_size = size.mip_level_size(mip_level, TextureDimension::D2);
+        if mip_level > 0 {
+            previous = image::imageops::resize(
+                &previous,
+                mip_size.width,
+                mip_size.height,
+                image::imageops::FilterType::Triangle,
+            );
+        }
+        let bytes = to_bytes(&previous);
+        queue.write_texture(
+            ImageCopyTexture {
+                texture: &texture,
+                mip_level,
+                origin: Origin3d::ZERO,
+                aspect: TextureAspect::All,
+            },
+            &bytes,
+            ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(bytes.len() as u32 / mip_size.height),
+                rows_per_image: Some(mip_size.height),
+            },
+            mip_size,
+        );
+    }
     
-    let view = texture.create_view(&TextureViewDescriptor::default());
-    let sampler = device.create_sampler(&SamplerDescriptor {
-        address_mode_u: AddressMode::Repeat,
-        address_mode_v: AddressMode::Repeat,
-        address_mode_w: AddressMode::Repeat,
-        mag_filter: FilterMode::Linear,
-        min_filter: FilterMode::Linear,
-        mipmap_filter: FilterMode::Linear,  // Use Linear for better mipmap quality
-        ..Default::default()
-    });
-    
-    Ok((texture, view, sampler))
+    texture
 }
 
 /// Create a 1x1 placeholder texture
@@ -76,6 +393,7 @@ pub fn create_placeholder_texture(
     device: &Device,
     queue: &Queue,
     color: [u8; 4],
+    color_space: ColorSpace,
     label: Option<&str>,
 ) -> (Texture, TextureView, Sampler) {
     let size = Extent3d {
@@ -90,7 +408,7 @@ pub fn create_placeholder_texture(
         mip_level_count: 1,
         sample_count: 1,
         dimension: TextureDimension::D2,
-        format: TextureFormat::Rgba8UnormSrgb,
+        format: color_space.format(),
         usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
         view_formats: &[],
     });
@@ -112,15 +430,7 @@ pub fn create_placeholder_texture(
     );
     
     let view = texture.create_view(&TextureViewDescriptor::default());
-    let sampler = device.create_sampler(&SamplerDescriptor {
-        address_mode_u: AddressMode::Repeat,
-        address_mode_v: AddressMode::Repeat,
-        address_mode_w: AddressMode::Repeat,
-        mag_filter: FilterMode::Linear,
-        min_filter: FilterMode::Linear,
-        mipmap_filter: FilterMode::Linear,  // Use Linear for better mipmap quality
-        ..Default::default()
-    });
+    let sampler = create_sampler(device, &TextureFilterSettings::default());
     
     (texture, view, sampler)
 }
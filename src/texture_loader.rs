@@ -1,160 +1,300 @@
 //! Texture loading from files
 
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::Read;
 use std::path::{Path, PathBuf};
+use image::DynamicImage;
 use wgpu::*;
-use crate::texture;
+use crate::texture::{self, ColorSpace, SlotSamplers, TextureInfo, TextureLoadOptions};
 
 /// Texture detection patterns for different texture types
 pub struct TexturePatterns;
 
+/// Slots a file can be detected as, most specific first: on equal scores the
+/// earlier slot wins
+const DETECTED_SLOTS: [&str; 9] = [
+    "normal", "base_color", "emissive", "height", "orm", "metallic_roughness", "metallic", "roughness", "ao",
+];
+
+/// Full names of each single-map type, then common abbreviations
+const KEYWORDS: &[(&str, &[&str], &[&str])] = &[
+    ("base_color", &["basecolor", "albedo", "diffuse", "color", "colour"], &["col", "diff", "base"]),
+    ("normal", &["normal", "normals"], &["norm", "nrm", "nor"]),
+    ("metallic", &["metallic", "metalness", "metal"], &["met"]),
+    ("roughness", &["roughness", "rough"], &["rgh"]),
+    ("ao", &["ao", "occlusion", "ambientocclusion"], &["occ", "ambient"]),
+    ("emissive", &["emissive", "emission", "glow"], &["emiss", "emit"]),
+    ("height", &["height", "displacement", "bump"], &["disp", "depth", "tess", "tessellation"]),
+];
+
 impl TexturePatterns {
-    /// Check if a filename matches base color patterns
-    pub fn is_base_color(name: &str) -> bool {
-        let name_lower = name.to_lowercase();
-        name_lower.contains("basecolor") || 
-        name_lower.contains("base_color") ||
-        name_lower.contains("basecolor") ||
-        name_lower.contains("diffuse") ||
-        name_lower.contains("albedo") ||
-        name_lower.contains("color") ||
-        name_lower.contains("col")
+    /// Split a file stem into lowercase words at separators, case changes and digits,
+    /// so "T_Rock_BaseColor_2K" gives "t", "rock", "base", "color", "2", "k"
+    fn words(name: &str) -> Vec<String> {
+        let stem = Path::new(name).file_stem().and_then(|s| s.to_str()).unwrap_or(name);
+        let mut words = Vec::new();
+        let mut current = String::new();
+        let mut previous: Option<char> = None;
+        for c in stem.chars() {
+            let boundary = match previous {
+                _ if !c.is_alphanumeric() => true,
+                Some(p) => (p.is_lowercase() && c.is_uppercase()) || (p.is_ascii_digit() != c.is_ascii_digit()),
+                None => false,
+            };
+            if boundary && !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            if c.is_alphanumeric() {
+                current.extend(c.to_lowercase());
+            }
+            previous = Some(c);
+        }
+        if !current.is_empty() {
+            words.push(current);
+        }
+        words
     }
     
-    /// Check if a filename matches normal map patterns
-    pub fn is_normal(name: &str) -> bool {
-        let name_lower = name.to_lowercase();
-        name_lower.contains("normal") ||
-        name_lower.contains("norm") ||
-        name_lower.contains("nrm")
+    /// How well the words match a single-map type: 4 for a full name as a word,
+    /// 3 for an abbreviation as a word, 1 for a full name inside a longer word
+    /// (e.g. "basecolor" run together, but also "color" in "watercolor")
+    fn keyword_score(words: &[String], slot: &str) -> u32 {
+        let Some((_, full, short)) = KEYWORDS.iter().find(|(s, _, _)| *s == slot) else {
+            return 0;
+        };
+        words.iter()
+            .map(|word| {
+                if full.contains(&word.as_str()) {
+                    4
+                } else if short.contains(&word.as_str()) {
+                    3
+                } else if full.iter().any(|keyword| keyword.len() > 2 && word.contains(keyword)) {
+                    1
+                } else {
+                    0
+                }
+            })
+            .max()
+            .unwrap_or(0)
     }
     
-    /// Check if a filename matches metallic patterns
-    pub fn is_metallic(name: &str) -> bool {
-        let name_lower = name.to_lowercase();
-        (name_lower.contains("metallic") || name_lower.contains("metal")) &&
-        !name_lower.contains("roughness") &&
-        !name_lower.contains("rough")
+    /// Score of a file name for one slot (0 when it doesn't match at all).
+    /// Packed maps score above the single maps they contain.
+    pub fn score(name: &str, slot: &str) -> u32 {
+        let words = Self::words(name);
+        let metal = Self::keyword_score(&words, "metallic");
+        let rough = Self::keyword_score(&words, "roughness");
+        let occlusion = Self::keyword_score(&words, "ao");
+        match slot {
+            "orm" if words.iter().any(|word| word == "orm") => 5,
+            "orm" if occlusion > 0 && metal > 0 && rough > 0 => occlusion.min(metal).min(rough) + 2,
+            "orm" => 0,
+            "metallic_roughness" if metal > 0 && rough > 0 => metal.min(rough) + 1,
+            "metallic_roughness" => 0,
+            _ => Self::keyword_score(&words, slot),
+        }
     }
     
-    /// Check if a filename matches roughness patterns
-    pub fn is_roughness(name: &str) -> bool {
-        let name_lower = name.to_lowercase();
-        (name_lower.contains("roughness") || name_lower.contains("rough")) &&
-        !name_lower.contains("metallic") &&
-        !name_lower.contains("metal")
+    /// The slot a file name matches best, with its score
+    pub fn classify(name: &str) -> Option<(&'static str, u32)> {
+        DETECTED_SLOTS.iter()
+            .map(|slot| (*slot, Self::score(name, slot)))
+            .filter(|(_, score)| *score > 0)
+            // `max_by_key` keeps the last maximum, so walk from the least specific slot
+            .rev()
+            .max_by_key(|(_, score)| *score)
     }
-    
-    /// Check if a filename matches metallic+roughness combined patterns
-    pub fn is_metallic_roughness(name: &str) -> bool {
-        let name_lower = name.to_lowercase();
-        // Exclude if it's clearly a different texture type
-        if name_lower.contains("normal") || 
-           name_lower.contains("basecolor") || 
-           name_lower.contains("base_color") ||
-           name_lower.contains("diffuse") ||
-           name_lower.contains("albedo") ||
-           name_lower.contains("emissive") ||
-           name_lower.contains("height") ||
-           name_lower.contains("displacement") ||
-           name_lower.contains("orm") {
-            return false;
+}
+
+/// Several files that match the same slot equally well
+#[derive(Debug, Clone)]
+pub struct DetectionConflict {
+    /// Slot name, as in [`TexturePaths`]
+    pub slot: &'static str,
+    /// File that detection picked
+    pub chosen: PathBuf,
+    /// The other files with the same score
+    pub others: Vec<PathBuf>,
+}
+
+/// Result of texture type detection over a folder or archive
+#[derive(Debug, Clone, Default)]
+pub struct TextureDetection {
+    pub paths: TexturePaths,
+    pub conflicts: Vec<DetectionConflict>,
+}
+
+impl TextureDetection {
+    /// Classify files given with their subfolder depth. Each file goes to the slot it
+    /// matches best; each slot takes its best scoring file, a file closer to the
+    /// selected folder winning a tie. Files left tied are reported as conflicts.
+    fn from_files(files: impl IntoIterator<Item = (usize, PathBuf)>) -> Self {
+        let mut candidates: HashMap<&'static str, Vec<(u32, usize, PathBuf)>> = HashMap::new();
+        for (depth, path) in files.into_iter().filter(|(_, path)| is_image_file(path)) {
+            let file_name = path.file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default()
+                .to_string();
+            if let Some((slot, score)) = TexturePatterns::classify(&file_name) {
+                candidates.entry(slot).or_default().push((score, depth, path));
+            }
+        }
+        
+        let mut detection = Self::default();
+        for slot in DETECTED_SLOTS {
+            let Some(mut matches) = candidates.remove(slot) else {
+                continue;
+            };
+            matches.sort_by(|(score_a, depth_a, path_a), (score_b, depth_b, path_b)| {
+                score_b.cmp(score_a).then(depth_a.cmp(depth_b)).then_with(|| path_a.cmp(path_b))
+            });
+            let (best_score, best_depth, chosen) = matches.remove(0);
+            let others: Vec<PathBuf> = matches.into_iter()
+                .filter(|(score, depth, _)| *score == best_score && *depth == best_depth)
+                .map(|(_, _, path)| path)
+                .collect();
+            if !others.is_empty() {
+                detection.conflicts.push(DetectionConflict { slot, chosen: chosen.clone(), others });
+            }
+            if let Some(target) = detection.paths.slot_mut(slot) {
+                *target = Some(chosen);
+            }
         }
-        // Match metallic+roughness patterns
-        (name_lower.contains("metallic") || name_lower.contains("metal")) &&
-        (name_lower.contains("roughness") || name_lower.contains("rough"))
+        detection
     }
+}
+
+/// Deepest subfolder level visited when searching subfolders (the folder itself is level 0)
+pub const MAX_SUBFOLDER_DEPTH: usize = 3;
+
+/// Files in a directory and, up to `max_depth` levels down, its subfolders, paired with
+/// their depth. Symlinked folders are not followed; unreadable subfolders are skipped.
+fn collect_files(dir_path: &Path, max_depth: usize) -> Result<Vec<(usize, PathBuf)>, anyhow::Error> {
+    let mut files = Vec::new();
+    let mut pending = vec![(0, dir_path.to_path_buf())];
     
-    /// Check if a filename matches ORM (Occlusion-Roughness-Metallic) patterns
-    /// ORM is a combined texture, so we exclude files that are clearly other texture types
-    pub fn is_orm(name: &str) -> bool {
-        let name_lower = name.to_lowercase();
-        // Exclude if it's clearly a different texture type
-        if name_lower.contains("normal") || 
-           name_lower.contains("basecolor") || 
-           name_lower.contains("base_color") ||
-           name_lower.contains("diffuse") ||
-           name_lower.contains("albedo") ||
-           name_lower.contains("emissive") ||
-           name_lower.contains("height") ||
-           name_lower.contains("displacement") {
-            return false;
+    while let Some((depth, dir)) = pending.pop() {
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            // Only the selected folder itself must be readable
+            Err(e) if depth == 0 => return Err(e.into()),
+            Err(e) => {
+                log::warn!("Skipping unreadable folder {}: {}", dir.display(), e);
+                continue;
+            }
+        };
+        for entry in entries {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                if depth < max_depth {
+                    pending.push((depth + 1, entry.path()));
+                }
+            } else if entry.path().is_file() {
+                files.push((depth, entry.path()));
+            }
         }
-        // Match ORM patterns
-        name_lower.contains("orm") ||
-        (name_lower.contains("ao") && name_lower.contains("roughness") && name_lower.contains("metallic")) ||
-        (name_lower.contains("occlusion") && name_lower.contains("roughness") && name_lower.contains("metallic"))
     }
     
-    /// Check if a filename matches AO patterns
-    pub fn is_ao(name: &str) -> bool {
-        let name_lower = name.to_lowercase();
-        (name_lower.contains("ao") || name_lower.contains("occlusion") || name_lower.contains("ambient")) &&
-        !name_lower.contains("roughness") &&
-        !name_lower.contains("metallic")
+    Ok(files)
+}
+
+/// Detect texture type from filename and return detected paths. With `search_subfolders`
+/// set, subfolders down to [`MAX_SUBFOLDER_DEPTH`] are searched too and, between files
+/// matching a type equally well, one closer to the selected folder wins.
+pub fn detect_textures_in_directory(dir_path: &Path, search_subfolders: bool) -> Result<TextureDetection, anyhow::Error> {
+    let max_depth = if search_subfolders { MAX_SUBFOLDER_DEPTH } else { 0 };
+    Ok(TextureDetection::from_files(collect_files(dir_path, max_depth)?))
+}
+
+/// Image file extensions textures can be loaded from: used for folder detection,
+/// the folder listing and the file dialogs. HDR and EXR load as float textures.
+pub const SUPPORTED_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "tga", "bmp", "dds", "tif", "tiff", "webp", "exr", "hdr"];
+
+/// Check whether a path has a supported image extension
+pub fn is_image_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| SUPPORTED_EXTENSIONS.iter().any(|ext| e.eq_ignore_ascii_case(ext)))
+        .unwrap_or(false)
+}
+
+/// List the image files in a directory (and its subfolders if requested), sorted by path
+pub fn list_texture_files(dir_path: &Path, search_subfolders: bool) -> Result<Vec<PathBuf>, anyhow::Error> {
+    let max_depth = if search_subfolders { MAX_SUBFOLDER_DEPTH } else { 0 };
+    let mut files: Vec<PathBuf> = collect_files(dir_path, max_depth)?
+        .into_iter()
+        .map(|(_, path)| path)
+        .filter(|path| is_image_file(path))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Check whether a path points to a zip archive
+pub fn is_zip_archive(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.eq_ignore_ascii_case("zip"))
+        .unwrap_or(false)
+}
+
+/// Texture set decompressed in memory from a zip archive
+pub struct TextureArchive {
+    /// Archive file name, shown in place of a folder name
+    pub display_name: String,
+    entries: HashMap<PathBuf, Vec<u8>>,
+}
+
+impl TextureArchive {
+    /// Read all file entries of a zip archive into memory
+    pub fn open(path: &Path) -> Result<Self, anyhow::Error> {
+        let file = std::fs::File::open(path)?;
+        let mut archive = zip::ZipArchive::new(std::io::BufReader::new(file))?;
+        let mut entries = HashMap::new();
+        
+        for index in 0..archive.len() {
+            let mut entry = archive.by_index(index)?;
+            if !entry.is_file() {
+                continue;
+            }
+            // Skip entries with unsafe paths (absolute or containing "..")
+            let Some(entry_path) = entry.enclosed_name() else {
+                continue;
+            };
+            let mut bytes = Vec::with_capacity(entry.size() as usize);
+            entry.read_to_end(&mut bytes)?;
+            entries.insert(entry_path, bytes);
+        }
+        
+        let display_name = path.file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("archive.zip")
+            .to_string();
+        
+        Ok(Self { display_name, entries })
     }
     
-    /// Check if a filename matches emissive patterns
-    pub fn is_emissive(name: &str) -> bool {
-        let name_lower = name.to_lowercase();
-        name_lower.contains("emissive") ||
-        name_lower.contains("emission") ||
-        name_lower.contains("emiss") ||
-        name_lower.contains("glow")
+    /// Run texture type detection over the archive entries
+    pub fn detect_textures(&self) -> TextureDetection {
+        TextureDetection::from_files(self.entries.keys().map(|path| (0, path.clone())))
     }
     
-    /// Check if a filename matches height/displacement patterns
-    pub fn is_height(name: &str) -> bool {
-        let name_lower = name.to_lowercase();
-        name_lower.contains("height") ||
-        name_lower.contains("displacement") ||
-        name_lower.contains("depth") ||
-        name_lower.contains("tessellation") ||
-        name_lower.contains("tess") ||
-        name_lower.contains("bump") ||
-        name_lower.contains("disp")
+    /// Image entries of the archive, sorted by path
+    pub fn texture_files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = self.entries.keys()
+            .filter(|path| is_image_file(path))
+            .cloned()
+            .collect();
+        files.sort();
+        files
     }
-}
-
-/// Detect texture type from filename and return detected paths
-pub fn detect_textures_in_directory(dir_path: &Path) -> Result<TexturePaths, anyhow::Error> {
-    let entries = std::fs::read_dir(dir_path)?;
-    let mut paths = TexturePaths::default();
     
-    for entry in entries {
-        let entry = entry?;
-        let path = entry.path();
-        if path.is_file() {
-            let file_name = path.file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or_default();
-            
-            // Check each texture type (order matters - check specific textures first, then combined)
-            // Specific textures should be checked before combined textures to avoid misclassification
-            if TexturePatterns::is_normal(file_name) {
-                paths.normal = Some(path.clone());
-            } else if TexturePatterns::is_base_color(file_name) {
-                paths.base_color = Some(path.clone());
-            } else if TexturePatterns::is_emissive(file_name) {
-                paths.emissive = Some(path.clone());
-            } else if TexturePatterns::is_height(file_name) {
-                paths.height = Some(path.clone());
-            } else if TexturePatterns::is_orm(file_name) {
-                // ORM is a combined texture (Occlusion-Roughness-Metallic)
-                paths.orm = Some(path.clone());
-            } else if TexturePatterns::is_metallic_roughness(file_name) {
-                // Metallic+Roughness combined texture
-                paths.metallic_roughness = Some(path.clone());
-            } else if TexturePatterns::is_metallic(file_name) {
-                paths.metallic = Some(path.clone());
-            } else if TexturePatterns::is_roughness(file_name) {
-                paths.roughness = Some(path.clone());
-            } else if TexturePatterns::is_ao(file_name) {
-                paths.ao = Some(path.clone());
-            }
-        }
+    /// Decompressed bytes of an entry
+    pub fn get(&self, entry_path: &Path) -> Option<&[u8]> {
+        self.entries.get(entry_path).map(|bytes| bytes.as_slice())
     }
-    
-    Ok(paths)
 }
 
 /// Detected texture file paths
@@ -171,6 +311,67 @@ pub struct TexturePaths {
     pub height: Option<PathBuf>,
 }
 
+impl TexturePaths {
+    /// Path of a slot by its `TextureHandles` name (or `metallic_roughness`)
+    pub fn get(&self, slot: &str) -> Option<&PathBuf> {
+        match slot {
+            "base_color" => self.base_color.as_ref(),
+            "normal" => self.normal.as_ref(),
+            "metallic" => self.metallic.as_ref(),
+            "roughness" => self.roughness.as_ref(),
+            "metallic_roughness" => self.metallic_roughness.as_ref(),
+            "orm" => self.orm.as_ref(),
+            "ao" => self.ao.as_ref(),
+            "emissive" => self.emissive.as_ref(),
+            "height" => self.height.as_ref(),
+            _ => None,
+        }
+    }
+    
+    fn slot_mut(&mut self, slot: &str) -> Option<&mut Option<PathBuf>> {
+        match slot {
+            "base_color" => Some(&mut self.base_color),
+            "normal" => Some(&mut self.normal),
+            "metallic" => Some(&mut self.metallic),
+            "roughness" => Some(&mut self.roughness),
+            "metallic_roughness" => Some(&mut self.metallic_roughness),
+            "orm" => Some(&mut self.orm),
+            "ao" => Some(&mut self.ao),
+            "emissive" => Some(&mut self.emissive),
+            "height" => Some(&mut self.height),
+            _ => None,
+        }
+    }
+    
+    /// Replace a slot with a manually chosen file, or empty it. Clearing the
+    /// metallic or roughness slot also drops a packed metallic-roughness map.
+    pub fn set(&mut self, slot: &str, path: Option<PathBuf>) {
+        if path.is_none() && matches!(slot, "metallic" | "roughness") {
+            self.metallic_roughness = None;
+        }
+        if let Some(target) = self.slot_mut(slot) {
+            *target = path;
+        }
+    }
+    
+    /// The subset of these paths the loaders actually upload. Separate metallic and
+    /// roughness maps have no slot of their own, and ORM wins over a packed
+    /// metallic-roughness map.
+    fn loaded_subset(&self) -> TexturePaths {
+        TexturePaths {
+            base_color: self.base_color.clone(),
+            normal: self.normal.clone(),
+            metallic: None,
+            roughness: None,
+            metallic_roughness: self.metallic_roughness.clone().filter(|_| self.orm.is_none()),
+            orm: self.orm.clone(),
+            ao: self.ao.clone(),
+            emissive: self.emissive.clone(),
+            height: self.height.clone(),
+        }
+    }
+}
+
 /// Load textures from a directory
 pub struct TextureLoader;
 
@@ -181,84 +382,213 @@ impl TextureLoader {
         queue: &Queue,
         path: &Path,
         label: Option<&str>,
+        options: &TextureLoadOptions,
     ) -> Result<(Texture, TextureView, Sampler), anyhow::Error> {
         let bytes = std::fs::read(path)?;
-        let texture = texture::load_texture(device, queue, &bytes, label)?;
+        let texture = texture::load_texture(device, queue, &bytes, label, options)?;
         Ok(texture)
     }
     
-    /// Search for texture files in a directory and load them
+    /// Load a single texture, reading from the archive when the path is one of its entries
+    pub fn load_texture_entry(
+        device: &Device,
+        queue: &Queue,
+        path: &Path,
+        archive: Option<&TextureArchive>,
+        label: Option<&str>,
+        options: &TextureLoadOptions,
+    ) -> Result<(Texture, TextureView, Sampler), anyhow::Error> {
+        match archive.and_then(|a| a.get(path)) {
+            Some(bytes) => texture::load_texture(device, queue, bytes, label, options),
+            None => Self::load_texture_file(device, queue, path, label, options),
+        }
+    }
+    
+    /// Search for texture files in a directory and load them. Also returns the
+    /// paths that were loaded, so callers can report which maps are present.
     #[allow(dead_code)]
     pub fn load_from_directory(
         device: &Device,
         queue: &Queue,
         dir_path: &Path,
-    ) -> Result<TextureSet, anyhow::Error> {
-        let paths = detect_textures_in_directory(dir_path)?;
+        options: &TextureLoadOptions,
+    ) -> Result<(TextureSet, TexturePaths), anyhow::Error> {
+        let paths = detect_textures_in_directory(dir_path, false)?.paths;
+        // Base color and emissive are color data; every other map holds linear values
+        let srgb = TextureLoadOptions { color_space: ColorSpace::Srgb, ..*options };
+        let linear = TextureLoadOptions { color_space: ColorSpace::Linear, ..*options };
         
         // Load textures (use placeholder if not found)
         let base_color = if let Some(path) = &paths.base_color {
-            Self::load_texture_file(device, queue, path, Some("base_color"))?
+            Self::load_texture_file(device, queue, path, Some("base_color"), &srgb)?
         } else {
-            texture::create_placeholder_texture(device, queue, [128, 128, 128, 255], Some("base_color_placeholder"))
+            texture::create_placeholder_texture(device, queue, [128, 128, 128, 255], ColorSpace::Srgb, Some("base_color_placeholder"))
         };
         
         let normal = if let Some(path) = &paths.normal {
-            Self::load_texture_file(device, queue, path, Some("normal"))?
+            Self::load_texture_file(device, queue, path, Some("normal"), &linear)?
         } else {
-            texture::create_placeholder_texture(device, queue, [128, 128, 255, 255], Some("normal_placeholder"))
+            texture::create_placeholder_texture(device, queue, [128, 128, 255, 255], ColorSpace::Linear, Some("normal_placeholder"))
         };
         
-        // Prefer ORM, then metallic_roughness, then separate metallic/roughness
+        // Prefer ORM, then metallic_roughness
         let metallic_roughness = if let Some(path) = &paths.orm {
-            Self::load_texture_file(device, queue, path, Some("orm"))?
+            Self::load_texture_file(device, queue, path, Some("orm"), &linear)?
         } else if let Some(path) = &paths.metallic_roughness {
-            Self::load_texture_file(device, queue, path, Some("metallic_roughness"))?
+            Self::load_texture_file(device, queue, path, Some("metallic_roughness"), &linear)?
         } else {
-            texture::create_placeholder_texture(device, queue, [0, 128, 0, 255], Some("metallic_roughness_placeholder"))
+            texture::create_placeholder_texture(device, queue, [0, 128, 0, 255], ColorSpace::Linear, Some("metallic_roughness_placeholder"))
         };
         
-        Ok(TextureSet {
-            base_color,
-            normal,
-            metallic_roughness,
-        })
-    }
-    
-    /// Load textures from individual file paths (allows manual selection)
-    pub fn load_from_paths(
-        device: &Device,
-        queue: &Queue,
-        paths: &TexturePaths,
-    ) -> Result<TextureSet, anyhow::Error> {
-        let base_color = if let Some(path) = &paths.base_color {
-            Self::load_texture_file(device, queue, path, Some("base_color"))?
+        let ao = if let Some(path) = &paths.ao {
+            Self::load_texture_file(device, queue, path, Some("ao"), &linear)?
         } else {
-            texture::create_placeholder_texture(device, queue, [128, 128, 128, 255], Some("base_color_placeholder"))
+            texture::create_placeholder_texture(device, queue, [255, 255, 255, 255], ColorSpace::Linear, Some("ao_placeholder"))
         };
         
-        let normal = if let Some(path) = &paths.normal {
-            Self::load_texture_file(device, queue, path, Some("normal"))?
+        let emissive = if let Some(path) = &paths.emissive {
+            Self::load_texture_file(device, queue, path, Some("emissive"), &srgb)?
         } else {
-            texture::create_placeholder_texture(device, queue, [128, 128, 255, 255], Some("normal_placeholder"))
+            texture::create_placeholder_texture(device, queue, [0, 0, 0, 255], ColorSpace::Srgb, Some("emissive_placeholder"))
         };
         
-        // Prefer ORM, then metallic_roughness
-        let metallic_roughness = if let Some(path) = &paths.orm {
-            Self::load_texture_file(device, queue, path, Some("orm"))?
-        } else if let Some(path) = &paths.metallic_roughness {
-            Self::load_texture_file(device, queue, path, Some("metallic_roughness"))?
+        let height = if let Some(path) = &paths.height {
+            Self::load_texture_file(device, queue, path, Some("height"), &linear)?
         } else {
-            texture::create_placeholder_texture(device, queue, [0, 128, 0, 255], Some("metallic_roughness_placeholder"))
+            texture::create_placeholder_texture(device, queue, [128, 128, 128, 255], ColorSpace::Linear, Some("height_placeholder"))
         };
         
-        Ok(TextureSet {
-            base_color,
-            normal,
-            metallic_roughness,
-        })
+        let info = TextureSetInfo {
+            base_color: paths.base_color.as_ref().map(|_| TextureInfo::from_texture(&base_color.0)),
+            normal: paths.normal.as_ref().map(|_| TextureInfo::from_texture(&normal.0)),
+            metallic_roughness: paths.orm.as_ref().or(paths.metallic_roughness.as_ref())
+                .map(|_| TextureInfo::from_texture(&metallic_roughness.0)),
+            ao: paths.ao.as_ref().map(|_| TextureInfo::from_texture(&ao.0)),
+            emissive: paths.emissive.as_ref().map(|_| TextureInfo::from_texture(&emissive.0)),
+            height: paths.height.as_ref().map(|_| TextureInfo::from_texture(&height.0)),
+        };
+        
+        Ok((
+            TextureSet {
+                base_color,
+                normal,
+                metallic_roughness,
+                ao,
+                emissive,
+                height,
+                info,
+            },
+            paths.loaded_subset(),
+        ))
+    }
+}
+
+/// A map that could not be read, decoded or uploaded
+#[derive(Debug, Clone)]
+pub struct TextureLoadFailure {
+    pub path: PathBuf,
+    pub error: String,
+}
+
+/// Images decoded for the slots of a texture set (on a worker thread), waiting to
+/// be uploaded on the render thread
+pub struct DecodedTextures {
+    /// Slot name, as in [`TexturePaths`], and its image
+    images: Vec<(&'static str, DynamicImage)>,
+    /// The paths the images came from (see [`TexturePaths::loaded_subset`])
+    pub paths: TexturePaths,
+    pub failures: Vec<TextureLoadFailure>,
+}
+
+/// A texture set uploaded from [`DecodedTextures`]
+pub struct UploadedTextures {
+    pub texture_set: TextureSet,
+    /// The paths that were loaded; failed maps are left out
+    pub paths: TexturePaths,
+    pub failures: Vec<TextureLoadFailure>,
+}
+
+impl DecodedTextures {
+    /// Read and decode the maps of `paths`, from `archive` for paths that are its
+    /// entries. Images larger than `max_size` are downscaled here already. A map that
+    /// fails is recorded and left out; `on_progress` gets (maps done, maps in total).
+    pub fn decode(
+        paths: &TexturePaths,
+        archive: Option<&TextureArchive>,
+        max_size: u32,
+        mut on_progress: impl FnMut(usize, usize),
+    ) -> Self {
+        let mut paths = paths.loaded_subset();
+        let slots: Vec<&'static str> = ["base_color", "normal", "orm", "metallic_roughness", "ao", "emissive", "height"]
+            .into_iter()
+            .filter(|slot| paths.get(slot).is_some())
+            .collect();
+        let mut images = Vec::new();
+        let mut failures = Vec::new();
+        on_progress(0, slots.len());
+        
+        for (index, slot) in slots.iter().enumerate() {
+            let Some(path) = paths.get(slot).cloned() else {
+                continue;
+            };
+            match Self::decode_file(&path, archive, max_size, slot) {
+                Ok(image) => images.push((*slot, image)),
+                Err(e) => {
+                    log::error!("Failed to load {} texture {}: {}", slot, path.display(), e);
+                    failures.push(TextureLoadFailure { path, error: e.to_string() });
+                    paths.set(slot, None);
+                }
+            }
+            on_progress(index + 1, slots.len());
+        }
+        
+        Self { images, paths, failures }
+    }
+    
+    fn decode_file(path: &Path, archive: Option<&TextureArchive>, max_size: u32, slot: &str) -> Result<DynamicImage, anyhow::Error> {
+        let bytes = match archive.and_then(|a| a.get(path)) {
+            Some(bytes) => Cow::Borrowed(bytes),
+            None => Cow::Owned(std::fs::read(path)?),
+        };
+        let image = image::load_from_memory(&bytes)?;
+        Ok(texture::downscaled(&image, max_size, Some(slot)).unwrap_or(image))
+    }
+    
+    /// Upload the images into a new texture set, with placeholders in the other slots.
+    /// Each slot samples with `options.filter` plus its overrides in `samplers`.
+    pub fn upload(
+        self,
+        device: &Device,
+        queue: &Queue,
+        options: &TextureLoadOptions,
+        samplers: &SlotSamplers,
+    ) -> UploadedTextures {
+        let Self { images, mut paths, mut failures } = self;
+        let mut texture_set = TextureSet::create_placeholder(device, queue);
+        for (slot, image) in &images {
+            // Base color and emissive are color data; every other map holds linear values
+            let color_space = match *slot {
+                "base_color" | "emissive" => ColorSpace::Srgb,
+                _ => ColorSpace::Linear,
+            };
+            let options = TextureLoadOptions {
+                color_space,
+                filter: options.filter.for_slot(samplers.get(slot)),
+                ..*options
+            };
+            match texture::load_texture_from_image(device, queue, image, Some(slot), &options) {
+                Ok(texture) => texture_set.set_slot(slot, texture),
+                Err(e) => {
+                    let path = paths.get(slot).cloned().unwrap_or_default();
+                    log::error!("Failed to upload {} texture {}: {}", slot, path.display(), e);
+                    failures.push(TextureLoadFailure { path, error: e.to_string() });
+                    paths.set(slot, None);
+                }
+            }
+        }
+        UploadedTextures { texture_set, paths, failures }
     }
 }
 
-use crate::texture_manager::TextureSet;
+use crate::texture_manager::{TextureSet, TextureSetInfo};
 
@@ -1,133 +1,244 @@
 //! Texture management for PBR rendering
 
 use wgpu::*;
-use crate::texture;
+use crate::environment::EnvironmentMap;
+use crate::texture::{self, ColorSpace, SlotSamplers, TextureFilterSettings, TextureInfo};
 
 /// Texture resources for PBR material
 pub struct TextureSet {
     pub base_color: (Texture, TextureView, Sampler),
     pub normal: (Texture, TextureView, Sampler),
     pub metallic_roughness: (Texture, TextureView, Sampler),
+    pub ao: (Texture, TextureView, Sampler),
+    pub emissive: (Texture, TextureView, Sampler),
+    pub height: (Texture, TextureView, Sampler),
+    pub info: TextureSetInfo,
 }
 
-impl TextureSet {
-    pub fn create_placeholder(device: &Device, queue: &Queue) -> Self {
-        let base_color = texture::create_placeholder_texture(
-            device,
-            queue,
-            [128, 128, 128, 255], // Gray
-            Some("base_color_placeholder"),
-        );
-
-        let normal = texture::create_placeholder_texture(
-            device,
-            queue,
-            [128, 128, 255, 255], // Normal map default (flat blue)
-            Some("normal_placeholder"),
-        );
+/// Upload info for each loaded slot (`None` while the slot holds a placeholder)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TextureSetInfo {
+    pub base_color: Option<TextureInfo>,
+    pub normal: Option<TextureInfo>,
+    pub metallic_roughness: Option<TextureInfo>,
+    pub ao: Option<TextureInfo>,
+    pub emissive: Option<TextureInfo>,
+    pub height: Option<TextureInfo>,
+}
 
-        let metallic_roughness = texture::create_placeholder_texture(
-            device,
-            queue,
-            [0, 128, 0, 255], // Default metallic/roughness
-            Some("metallic_roughness_placeholder"),
-        );
+impl TextureSetInfo {
+    /// Number of slots holding a loaded map
+    pub fn loaded_count(&self) -> usize {
+        [self.base_color, self.normal, self.metallic_roughness, self.ao, self.emissive, self.height]
+            .iter()
+            .filter(|info| info.is_some())
+            .count()
+    }
+    
+    /// Size the set is expected to share: that of the first loaded map
+    pub fn reference_size(&self) -> Option<(u32, u32)> {
+        [self.base_color, self.normal, self.metallic_roughness, self.ao, self.emissive, self.height]
+            .into_iter()
+            .flatten()
+            .map(|info| (info.width, info.height))
+            .next()
+    }
+}
 
+impl TextureSet {
+    pub fn create_placeholder(device: &Device, queue: &Queue) -> Self {
         Self {
-            base_color,
-            normal,
-            metallic_roughness,
+            base_color: placeholder_texture(device, queue, "base_color"),
+            normal: placeholder_texture(device, queue, "normal"),
+            metallic_roughness: placeholder_texture(device, queue, "metallic_roughness"),
+            ao: placeholder_texture(device, queue, "ao"),
+            emissive: placeholder_texture(device, queue, "emissive"),
+            height: placeholder_texture(device, queue, "height"),
+            info: TextureSetInfo::default(),
         }
     }
+    
+    /// Put an uploaded texture into a slot and record its info. `orm` and
+    /// `metallic_roughness` both name the metallic-roughness slot.
+    pub fn set_slot(&mut self, slot: &str, texture: (Texture, TextureView, Sampler)) {
+        let info = Some(TextureInfo::from_texture(&texture.0));
+        match slot {
+            "base_color" => {
+                self.base_color = texture;
+                self.info.base_color = info;
+            }
+            "normal" => {
+                self.normal = texture;
+                self.info.normal = info;
+            }
+            "ao" => {
+                self.ao = texture;
+                self.info.ao = info;
+            }
+            "emissive" => {
+                self.emissive = texture;
+                self.info.emissive = info;
+            }
+            "height" => {
+                self.height = texture;
+                self.info.height = info;
+            }
+            _ => {
+                self.metallic_roughness = texture;
+                self.info.metallic_roughness = info;
+            }
+        }
+    }
+    
+    /// Swap one slot back to its placeholder. The metallic, roughness and ORM
+    /// slots all share the metallic-roughness texture.
+    pub fn clear_slot(&mut self, device: &Device, queue: &Queue, slot: &str) {
+        match slot {
+            "base_color" => {
+                self.base_color = placeholder_texture(device, queue, slot);
+                self.info.base_color = None;
+            }
+            "normal" => {
+                self.normal = placeholder_texture(device, queue, slot);
+                self.info.normal = None;
+            }
+            "ao" => {
+                self.ao = placeholder_texture(device, queue, slot);
+                self.info.ao = None;
+            }
+            "emissive" => {
+                self.emissive = placeholder_texture(device, queue, slot);
+                self.info.emissive = None;
+            }
+            "height" => {
+                self.height = placeholder_texture(device, queue, slot);
+                self.info.height = None;
+            }
+            _ => {
+                self.metallic_roughness = placeholder_texture(device, queue, "metallic_roughness");
+                self.info.metallic_roughness = None;
+            }
+        }
+    }
+    
+    pub fn rebuild_samplers(&mut self, device: &Device, filter: &TextureFilterSettings, slots: &SlotSamplers) {
+        self.base_color.2 = texture::create_sampler(device, &filter.for_slot(&slots.base_color));
+        self.normal.2 = texture::create_sampler(device, &filter.for_slot(&slots.normal));
+        self.metallic_roughness.2 = texture::create_sampler(device, &filter.for_slot(&slots.metallic_roughness));
+        self.ao.2 = texture::create_sampler(device, &filter.for_slot(&slots.ao));
+        self.emissive.2 = texture::create_sampler(device, &filter.for_slot(&slots.emissive));
+        self.height.2 = texture::create_sampler(device, &filter.for_slot(&slots.height));
+    }
 
+    /// Layout of group 1: a (texture, sampler) pair per material slot at
+    /// bindings 0-11, then the environment cubes and their sampler at 12-14
     pub fn bind_group_layout(device: &Device) -> BindGroupLayout {
         device.create_bind_group_layout(&BindGroupLayoutDescriptor {
             entries: &[
+                texture_entry(0, TextureViewDimension::D2),  // base color
+                sampler_entry(1),
+                texture_entry(2, TextureViewDimension::D2),  // normal
+                sampler_entry(3),
+                texture_entry(4, TextureViewDimension::D2),  // metallic/roughness (ORM)
+                sampler_entry(5),
+                texture_entry(6, TextureViewDimension::D2),  // ambient occlusion
+                sampler_entry(7),
+                texture_entry(8, TextureViewDimension::D2),  // emissive
+                sampler_entry(9),
+                // height (also sampled by the vertex stage for displacement)
                 BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: ShaderStages::FRAGMENT,
-                    ty: BindingType::Texture {
-                        multisampled: false,
-                        view_dimension: TextureViewDimension::D2,
-                        sample_type: TextureSampleType::Float { filterable: true },
-                    },
-                    count: None,
-                },
-                BindGroupLayoutEntry {
-                    binding: 1,
-                    visibility: ShaderStages::FRAGMENT,
-                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
-                    count: None,
-                },
-                BindGroupLayoutEntry {
-                    binding: 2,
-                    visibility: ShaderStages::FRAGMENT,
-                    ty: BindingType::Texture {
-                        multisampled: false,
-                        view_dimension: TextureViewDimension::D2,
-                        sample_type: TextureSampleType::Float { filterable: true },
-                    },
-                    count: None,
-                },
-                BindGroupLayoutEntry {
-                    binding: 3,
-                    visibility: ShaderStages::FRAGMENT,
-                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
-                    count: None,
-                },
-                BindGroupLayoutEntry {
-                    binding: 4,
-                    visibility: ShaderStages::FRAGMENT,
-                    ty: BindingType::Texture {
-                        multisampled: false,
-                        view_dimension: TextureViewDimension::D2,
-                        sample_type: TextureSampleType::Float { filterable: true },
-                    },
-                    count: None,
+                    visibility: ShaderStages::VERTEX | ShaderStages::FRAGMENT,
+                    ..texture_entry(10, TextureViewDimension::D2)
                 },
                 BindGroupLayoutEntry {
-                    binding: 5,
-                    visibility: ShaderStages::FRAGMENT,
-                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
-                    count: None,
+                    visibility: ShaderStages::VERTEX | ShaderStages::FRAGMENT,
+                    ..sampler_entry(11)
                 },
+                // Environment irradiance and prefiltered specular cubes (IBL)
+                texture_entry(12, TextureViewDimension::Cube),
+                texture_entry(13, TextureViewDimension::Cube),
+                sampler_entry(14),
             ],
             label: Some("texture_bind_group_layout"),
         })
     }
 
-    pub fn create_bind_group(&self, device: &Device, layout: &BindGroupLayout) -> BindGroup {
+    pub fn create_bind_group(&self, device: &Device, layout: &BindGroupLayout, environment: &EnvironmentMap) -> BindGroup {
+        let slots = [
+            &self.base_color,
+            &self.normal,
+            &self.metallic_roughness,
+            &self.ao,
+            &self.emissive,
+            &self.height,
+        ];
+        let mut entries: Vec<BindGroupEntry> = Vec::with_capacity(slots.len() * 2 + 3);
+        for (index, (_, view, sampler)) in slots.into_iter().enumerate() {
+            let binding = index as u32 * 2;
+            entries.push(BindGroupEntry {
+                binding,
+                resource: BindingResource::TextureView(view),
+            });
+            entries.push(BindGroupEntry {
+                binding: binding + 1,
+                resource: BindingResource::Sampler(sampler),
+            });
+        }
+        entries.extend([
+            BindGroupEntry {
+                binding: 12,
+                resource: BindingResource::TextureView(&environment.irradiance.1),
+            },
+            BindGroupEntry {
+                binding: 13,
+                resource: BindingResource::TextureView(&environment.specular.1),
+            },
+            BindGroupEntry {
+                binding: 14,
+                resource: BindingResource::Sampler(&environment.sampler),
+            },
+        ]);
+
         device.create_bind_group(&BindGroupDescriptor {
             layout,
-            entries: &[
-                BindGroupEntry {
-                    binding: 0,
-                    resource: BindingResource::TextureView(&self.base_color.1),
-                },
-                BindGroupEntry {
-                    binding: 1,
-                    resource: BindingResource::Sampler(&self.base_color.2),
-                },
-                BindGroupEntry {
-                    binding: 2,
-                    resource: BindingResource::TextureView(&self.normal.1),
-                },
-                BindGroupEntry {
-                    binding: 3,
-                    resource: BindingResource::Sampler(&self.normal.2),
-                },
-                BindGroupEntry {
-                    binding: 4,
-                    resource: BindingResource::TextureView(&self.metallic_roughness.1),
-                },
-                BindGroupEntry {
-                    binding: 5,
-                    resource: BindingResource::Sampler(&self.metallic_roughness.2),
-                },
-            ],
+            entries: &entries,
             label: Some("texture_bind_group"),
         })
     }
 }
 
+/// 1x1 texture holding the neutral value of a slot
+fn placeholder_texture(device: &Device, queue: &Queue, slot: &str) -> (Texture, TextureView, Sampler) {
+    let (color, color_space) = match slot {
+        "base_color" => ([128, 128, 128, 255], ColorSpace::Srgb), // Gray
+        "normal" => ([128, 128, 255, 255], ColorSpace::Linear), // Normal map default (flat blue)
+        "ao" => ([255, 255, 255, 255], ColorSpace::Linear), // Unoccluded
+        "emissive" => ([0, 0, 0, 255], ColorSpace::Srgb), // No emission
+        "height" => ([128, 128, 128, 255], ColorSpace::Linear), // Mid-level (no displacement)
+        _ => ([0, 128, 0, 255], ColorSpace::Linear), // Default metallic/roughness
+    };
+    let label = format!("{}_placeholder", slot);
+    texture::create_placeholder_texture(device, queue, color, color_space, Some(&label))
+}
 
+fn texture_entry(binding: u32, view_dimension: TextureViewDimension) -> BindGroupLayoutEntry {
+    BindGroupLayoutEntry {
+        binding,
+        visibility: ShaderStages::FRAGMENT,
+        ty: BindingType::Texture {
+            multisampled: false,
+            view_dimension,
+            sample_type: TextureSampleType::Float { filterable: true },
+        },
+        count: None,
+    }
+}
+
+fn sampler_entry(binding: u32) -> BindGroupLayoutEntry {
+    BindGroupLayoutEntry {
+        binding,
+        visibility: ShaderStages::FRAGMENT,
+        ty: BindingType::Sampler(SamplerBindingType::Filtering),
+        count: None,
+    }
+}
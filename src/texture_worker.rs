@@ -0,0 +1,148 @@
+//! Background loading of texture sets
+//!
+//! Opening a zip archive, detecting the maps and decoding them runs on a worker
+//! thread. The decoded images come back over a channel and are uploaded on the
+//! render thread, which owns the wgpu queue. Every load is tagged with a generation,
+//! so a folder that was replaced before its load finished is dropped on arrival.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use crate::state_wgpu::TextureHandles;
+use crate::texture_loader::{
+    detect_textures_in_directory, is_zip_archive, list_texture_files, DecodedTextures, TextureArchive,
+    TextureDetection,
+};
+
+/// What to load: the selected folder or archive plus the manual slot assignments
+pub struct TextureLoadJob {
+    pub folder: Option<String>,
+    pub search_subfolders: bool,
+    pub handles: TextureHandles,
+    pub max_size: u32,
+}
+
+/// A finished load, ready for upload
+pub struct TextureLoadResult {
+    generation: u64,
+    /// File name of the archive when the folder was a zip archive
+    pub archive_name: Option<String>,
+    /// Detection over the folder (empty without one)
+    pub detection: TextureDetection,
+    /// Image files of the folder or archive
+    pub folder_files: Vec<PathBuf>,
+    pub textures: DecodedTextures,
+    /// Problems that didn't stop the load, such as an unreadable archive
+    pub errors: Vec<String>,
+}
+
+/// Maps decoded so far out of the maps to decode, written by the worker thread
+#[derive(Default)]
+struct Progress {
+    done: AtomicUsize,
+    total: AtomicUsize,
+}
+
+impl TextureLoadJob {
+    fn run(self, generation: u64, progress: &Progress) -> TextureLoadResult {
+        let mut errors = Vec::new();
+        let folder = self.folder.as_deref().map(Path::new);
+
+        // Zip archives are decompressed in memory and treated as a virtual folder
+        let archive = folder
+            .filter(|path| is_zip_archive(path))
+            .and_then(|path| match TextureArchive::open(path) {
+                Ok(archive) => Some(archive),
+                Err(e) => {
+                    errors.push(format!("Failed to open texture archive {}: {}", path.display(), e));
+                    None
+                }
+            });
+
+        let (detection, folder_files) = match (&archive, folder) {
+            (Some(archive), _) => (archive.detect_textures(), archive.texture_files()),
+            (None, Some(folder)) => (
+                detect_textures_in_directory(folder, self.search_subfolders).unwrap_or_default(),
+                list_texture_files(folder, self.search_subfolders).unwrap_or_default(),
+            ),
+            (None, None) => Default::default(),
+        };
+
+        // Individual selections override detection
+        let mut paths = detection.paths.clone();
+        self.handles.apply_overrides(&mut paths);
+        let textures = DecodedTextures::decode(&paths, archive.as_ref(), self.max_size, |done, total| {
+            progress.total.store(total, Ordering::Relaxed);
+            progress.done.store(done, Ordering::Relaxed);
+        });
+
+        TextureLoadResult {
+            generation,
+            archive_name: archive.map(|archive| archive.display_name),
+            detection,
+            folder_files,
+            textures,
+            errors,
+        }
+    }
+}
+
+/// Runs texture loads on worker threads and hands back the result of the latest one
+pub struct TextureLoadWorker {
+    generation: u64,
+    /// Progress of the latest load while it runs
+    progress: Option<Arc<Progress>>,
+    results_tx: Sender<TextureLoadResult>,
+    results_rx: Receiver<TextureLoadResult>,
+}
+
+impl Default for TextureLoadWorker {
+    fn default() -> Self {
+        let (results_tx, results_rx) = mpsc::channel();
+        Self {
+            generation: 0,
+            progress: None,
+            results_tx,
+            results_rx,
+        }
+    }
+}
+
+impl TextureLoadWorker {
+    /// Start a load on a new thread, superseding any load still running
+    pub fn start(&mut self, job: TextureLoadJob) {
+        self.generation += 1;
+        let generation = self.generation;
+        let progress = Arc::new(Progress::default());
+        self.progress = Some(progress.clone());
+        let results_tx = self.results_tx.clone();
+        std::thread::spawn(move || {
+            let _ = results_tx.send(job.run(generation, &progress));
+        });
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.progress.is_some()
+    }
+
+    /// (maps decoded, maps to decode) of the running load; the total is 0 while
+    /// the folder is still being scanned
+    pub fn progress(&self) -> Option<(usize, usize)> {
+        self.progress.as_ref().map(|progress| {
+            (progress.done.load(Ordering::Relaxed), progress.total.load(Ordering::Relaxed))
+        })
+    }
+
+    /// The result of the latest load once it has finished; superseded results are dropped
+    pub fn poll(&mut self) -> Option<TextureLoadResult> {
+        let mut latest = None;
+        while let Ok(result) = self.results_rx.try_recv() {
+            if result.generation == self.generation {
+                self.progress = None;
+                latest = Some(result);
+            }
+        }
+        latest
+    }
+}
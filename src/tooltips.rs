@@ -0,0 +1,503 @@
+//! Central table of UI control descriptions shown as hover tooltips
+//!
+//! Keep the ranges here in sync with the slider ranges in `ui_wgpu`.
+
+/// Description and valid range of a UI control
+pub struct ControlHelp {
+    pub description: &'static str,
+    pub range: &'static str,
+}
+
+impl ControlHelp {
+    /// Tooltip text: the description followed by the valid range
+    pub fn text(&self) -> String {
+        format!("{}\n\nRange: {}", self.description, self.range)
+    }
+}
+
+// Material
+
+pub const BASE_COLOR_TINT: ControlHelp = ControlHelp {
+    description: "Color multiplied with the base color texture (or used directly when none is loaded).",
+    range: "RGB, 0–1 per channel",
+};
+
+pub const METALLIC: ControlHelp = ControlHelp {
+    description: "Multiplier for the metallic map. 0 = dielectric (plastic, wood), 1 = bare metal. Used as the metallic value when no map is loaded.",
+    range: "0–1",
+};
+
+pub const ROUGHNESS: ControlHelp = ControlHelp {
+    description: "Multiplier for the roughness map. 0 = mirror-like, 1 = fully diffuse highlights. Used as the roughness value when no map is loaded.",
+    range: "0–1",
+};
+
+pub const NORMAL_STRENGTH: ControlHelp = ControlHelp {
+    description: "Scales the tangent-space XY of the normal map. 0 flattens the surface, 1 is as authored, above 1 exaggerates the bumps.",
+    range: "0–2",
+};
+
+pub const NORMAL_DEBUG_SPACE: ControlHelp = ControlHelp {
+    description: "Tangent shows the normal map as stored. World shows the final shading normal after the tangent-space transform and normal strength, remapped to 0–1 color; seams or flipped shading here point to bad tangents or a wrong green channel.",
+    range: "Tangent / World",
+};
+
+pub const TANGENT_FRAME_AXIS: ControlHelp = ControlHelp {
+    description: "Vector of the vertex tangent frame to show in world space, remapped to 0–1 color. The bitangent is derived from the tangent's handedness (w), so mirrored UV islands show it flipped. Frames whose tangent or bitangent isn't perpendicular to the normal are magenta.",
+    range: "Tangent / Bitangent / Normal",
+};
+
+pub const FLIP_NORMAL_Y: ControlHelp = ControlHelp {
+    description: "Invert the normal map's green channel. Enable for DirectX-convention maps (Unreal, Substance \"DirectX\" export), where bumps otherwise look lit from the wrong side.",
+    range: "on / off",
+};
+
+pub const AO_STRENGTH: ControlHelp = ControlHelp {
+    description: "Strength of the ambient occlusion map. 0 ignores the map, 1 is as authored, above 1 darkens crevices further.",
+    range: "0–2",
+};
+
+pub const INVERT_ROUGHNESS: ControlHelp = ControlHelp {
+    description: "Treat the roughness channel as glossiness and use 1 - value. Enable for specular/glossiness workflow exports, where shiny areas are white.",
+    range: "on / off",
+};
+
+pub const INVERT_AO: ControlHelp = ControlHelp {
+    description: "Use 1 - value of the ambient occlusion map, for maps where white marks occluded areas.",
+    range: "on / off",
+};
+
+pub const OCCLUSION_UV1: ControlHelp = ControlHelp {
+    description: "Sample the ambient occlusion map with the second UV set (UV1) instead of the primary one, as baked lightmap-style AO usually requires.",
+    range: "on / off",
+};
+
+pub const EMISSIVE_STRENGTH: ControlHelp = ControlHelp {
+    description: "Multiplier for the emissive map. Values above 1 make the surface glow brighter than its texture.",
+    range: "0–5",
+};
+
+pub const PARALLAX_DEPTH: ControlHelp = ControlHelp {
+    description: "Depth of parallax occlusion mapping, in UV units. The height map is ray-marched along the view direction so surface detail appears to recede and occlude itself. Only active in the Lit view with a height texture loaded; 0 disables it.",
+    range: "0–0.2",
+};
+
+pub const PARALLAX_STEPS: ControlHelp = ControlHelp {
+    description: "Number of layers the view ray is marched through when searching the height map. More steps remove stair-stepping at grazing angles at a higher shading cost.",
+    range: "4–64",
+};
+
+pub const UV_TILE_SIZE: ControlHelp = ControlHelp {
+    description: "Size of one texture tile in UV space. Tiling is scaled around the UV center (0.5, 0.5); smaller values repeat the texture more often.",
+    range: "0.1–5 (logarithmic)",
+};
+
+pub const UV_TILE_LINK: ControlHelp = ControlHelp {
+    description: "Use one tile size for both directions. Turn off to tile U and V separately, e.g. a brick wall repeated 4× horizontally and 2× vertically.",
+    range: "on / off",
+};
+
+pub const UV_OFFSET: ControlHelp = ControlHelp {
+    description: "Shift the texture projection along U and V, e.g. to align a decal or move a seam into view. One unit moves by a whole texture.",
+    range: "-1 to 1 (UV units)",
+};
+
+pub const UV_ROTATION: ControlHelp = ControlHelp {
+    description: "Rotate the texture projection around the UV center. The normal map's tangent frame is rotated along, so lighting stays correct.",
+    range: "-180° to 180°",
+};
+
+pub const UV_TILE_LINES: ControlHelp = ControlHelp {
+    description: "Draw thin lines where the texture repeats (integer UV boundaries) to check whether it tiles seamlessly at the current tile size.",
+    range: "on / off, line color",
+};
+
+pub const SAVE_AS_DEFAULT: ControlHelp = ControlHelp {
+    description: "Store the current material, light and background settings in the config directory and apply them on every launch.",
+    range: "—",
+};
+
+pub const SHARE_CODE: ControlHelp = ControlHelp {
+    description: "Copy the material, light and view mode settings as a compact text code for issue reports or chat. Paste a code below and press Apply to restore it. Codes from other format versions are rejected.",
+    range: "up to 1024 characters",
+};
+
+// GPU tessellation
+
+pub const GPU_TESSELLATION: ControlHelp = ControlHelp {
+    description: "Move the mesh vertices along their normals by the height map. The detail follows the mesh density, so raise the tessellation level for fine maps. Parallax mapping is skipped while this is on.",
+    range: "on / off",
+};
+
+pub const MIN_TESS_FACTOR: ControlHelp = ControlHelp {
+    description: "Subdivision factor used for distant geometry. 1 = no subdivision.",
+    range: "1–16",
+};
+
+pub const MAX_TESS_FACTOR: ControlHelp = ControlHelp {
+    description: "Subdivision factor used for geometry close to the camera.",
+    range: "1–128",
+};
+
+pub const QUALITY_CAP: ControlHelp = ControlHelp {
+    description: "Hard upper limit on the subdivision factor regardless of distance, to bound the triangle count on slower GPUs.",
+    range: "1–128",
+};
+
+pub const DISPLACEMENT_SCALE: ControlHelp = ControlHelp {
+    description: "Distance in world units that a full-range height sample moves the surface along its normal.",
+    range: "0–1 world units",
+};
+
+pub const DISPLACEMENT_MIDPOINT: ControlHelp = ControlHelp {
+    description: "Height value that leaves the surface in place. Samples above it push outward, below it push inward (0.5 for signed maps, 0 for maps that only raise).",
+    range: "0–1 (height map value)",
+};
+
+pub const DISPLACEMENT_BIAS: ControlHelp = ControlHelp {
+    description: "Constant offset added to every displacement, moving the whole surface in or out.",
+    range: "-1–1 world units",
+};
+
+pub const DISPLACEMENT_CLAMP_MIN: ControlHelp = ControlHelp {
+    description: "Lowest allowed displacement after scale and bias; limits how far the surface can sink.",
+    range: "-2–0 world units",
+};
+
+pub const DISPLACEMENT_CLAMP_MAX: ControlHelp = ControlHelp {
+    description: "Highest allowed displacement after scale and bias; limits how far the surface can rise.",
+    range: "0–2 world units",
+};
+
+pub const SCREEN_SPACE_SCALE: ControlHelp = ControlHelp {
+    description: "Target edge length in pixels per subdivision. Higher values subdivide more for the same on-screen size.",
+    range: "10–500 (logarithmic)",
+};
+
+pub const DISTANCE_SCALE: ControlHelp = ControlHelp {
+    description: "How quickly the displacement fades with camera distance. The distance is multiplied by this before the falloff curve is applied.",
+    range: "0.01–1 per world unit (logarithmic)",
+};
+
+pub const DISTANCE_FALLOFF: ControlHelp = ControlHelp {
+    description: "Curve fading the displacement over camera distance. Linear drops evenly, Quadratic keeps detail longer, Exponential drops quickly then levels off.",
+    range: "Linear / Quadratic / Exponential",
+};
+
+pub const TESSELLATION_DEBUG: ControlHelp = ControlHelp {
+    description: "Visualize the tessellation. Density colors the mesh by how large its triangles appear on screen, from blue (coarse) to red (dense), to tune the tessellation level for the current view. Wireframe and Displacement need hardware tessellation and are unavailable.",
+    range: "None / Density / Wireframe / Displacement",
+};
+
+// Mesh
+
+pub const LOAD_MODEL: ControlHelp = ControlHelp {
+    description: "Load a model file to preview the textures on (also possible by dropping it on the window). Models without normals get flat shading, models without UVs a planar projection. Textures embedded in a glTF material replace the base color, normal and ORM slots.",
+    range: "OBJ, glTF, GLB",
+};
+
+pub const REGENERATE_TANGENTS: ControlHelp = ControlHelp {
+    description: "Ignore the tangents stored in model files and compute them from the positions, normals and UVs instead. Use it when the Tangents view shows broken (magenta) or inconsistent frames. Models without tangents always get generated ones.",
+    range: "on / off",
+};
+
+pub const TESSELLATION_LEVEL: ControlHelp = ControlHelp {
+    description: "Number of segments used when generating the mesh on the CPU. Higher values give smoother silhouettes.",
+    range: "4–128 segments",
+};
+
+pub const TURNTABLE: ControlHelp = ControlHelp {
+    description: "Continuously rotate the model around the vertical axis, e.g. for recording material showcases. Negative speeds spin the other way. Dragging the model pauses the spin while the button is held.",
+    range: "-180–180 °/s",
+};
+
+pub const WIREFRAME: ControlHelp = ControlHelp {
+    description: "Draw the mesh's triangle edges to inspect tessellation density, either over the shaded surface or on their own. Needs a GPU that can rasterize polygons as lines.",
+    range: "Off / Overlay / Wireframe Only, line color",
+};
+
+pub const CORNER_RADIUS: ControlHelp = ControlHelp {
+    description: "Radius of the rounded rectangle's corners as a fraction of its half-size. 0 gives a square plane.",
+    range: "0–0.45",
+};
+
+pub const MATERIAL_MATRIX: ControlHelp = ControlHelp {
+    description: "Draw a grid of instances where each axis sweeps a material parameter, overriding the textures for that parameter.",
+    range: "on / off",
+};
+
+pub const MATRIX_AXIS: ControlHelp = ControlHelp {
+    description: "Parameter swept along this axis and the number of instances along it.",
+    range: "1–8 instances",
+};
+
+pub const MATRIX_SPACING: ControlHelp = ControlHelp {
+    description: "Distance between neighbouring instances in the material matrix.",
+    range: "2–5 world units",
+};
+
+pub const SHOW_BOUNDS: ControlHelp = ControlHelp {
+    description: "Draw the mesh's axis-aligned bounding box (in model space) as a wireframe, to judge its scale and placement. Its size and center are listed below.",
+    range: "on / off",
+};
+
+pub const LAYER_SEPARATION: ControlHelp = ControlHelp {
+    description: "Vertical gap between the model base and the grid floor so they don't z-fight. The grid is also drawn with a small depth offset.",
+    range: "0–0.02 world units (logarithmic)",
+};
+
+// Light
+
+pub const LIGHT_DIRECTION: ControlHelp = ControlHelp {
+    description: "Direction the light comes from, in world space. The vector is normalized after editing. Right-drag in the viewport to rotate it.",
+    range: "-1–1 per axis",
+};
+
+pub const SHOW_LIGHT_GIZMO: ControlHelp = ControlHelp {
+    description: "Draw a sun with an arrow outside the scene, on the side the light comes from and pointing along its rays. It follows the sliders, the orbit and right-dragging.",
+    range: "on / off",
+};
+
+pub const LIGHT_ORBIT: ControlHelp = ControlHelp {
+    description: "Continuously rotate the light around the vertical axis. Period is the time for one full revolution.",
+    range: "2–60 seconds",
+};
+
+pub const LIGHT_COLOR: ControlHelp = ControlHelp {
+    description: "Color of the directional light, multiplied with its radiance.",
+    range: "RGB, 0–1 per channel",
+};
+
+pub const LIGHT_TEMPERATURE: ControlHelp = ControlHelp {
+    description: "Set the light color from a blackbody temperature. Low values are warm orange, ~6500K is neutral daylight, high values are cool blue.",
+    range: "1000–12000 K",
+};
+
+pub const LIGHTING_PRESET: ControlHelp = ControlHelp {
+    description: "Replace all lights with a ready-made setup. Three-Point Studio adds a warm key light, a dim cool fill and a bright rim light from behind, for a good-looking preview without an environment map.",
+    range: "Single Light, Three-Point Studio",
+};
+
+pub const SECONDARY_LIGHTS: ControlHelp = ControlHelp {
+    description: "Color and intensity of the fill and rim lights added by a preset. They cast no shadows and turn with the light orbit; right-dragging and the direction sliders only move the key light.",
+    range: "0–20",
+};
+
+pub const LIGHT_INTENSITY: ControlHelp = ControlHelp {
+    description: "Radiance of the directional light, multiplied with its color.",
+    range: "0–20",
+};
+
+pub const SHADOWS: ControlHelp = ControlHelp {
+    description: "Let the mesh shadow itself from the main light using a shadow map. Displaced surfaces cast shadows from their displaced shape; parallax detail does not.",
+    range: "on / off",
+};
+
+pub const SHADOW_BIAS: ControlHelp = ControlHelp {
+    description: "How far the shadow lookup is pushed off the surface along its normal. Raise it if lit areas show striped self-shadowing (acne); lower it if shadows detach from their casters.",
+    range: "0–0.1 units",
+};
+
+pub const AMBIENT_INTENSITY: ControlHelp = ControlHelp {
+    description: "Strength of the uniform ambient light that fills in shadowed areas. Scaled by ambient occlusion; an environment map replaces it.",
+    range: "0–2",
+};
+
+pub const AMBIENT_COLOR: ControlHelp = ControlHelp {
+    description: "Color of the uniform ambient light, e.g. a cool sky tint against a warm key light. Not used while an environment map provides the ambient lighting.",
+    range: "RGB, 0–1 per channel",
+};
+
+pub const LOAD_ENVIRONMENT: ControlHelp = ControlHelp {
+    description: "Load an equirectangular HDR panorama for image-based lighting (also possible by dropping it on the window). Replaces the flat ambient term with diffuse irradiance and roughness-filtered reflections.",
+    range: "HDR, EXR",
+};
+
+pub const SHOW_ENVIRONMENT_BACKGROUND: ControlHelp = ControlHelp {
+    description: "Draw the environment panorama behind the model. When off (or with no environment loaded) the background color is used.",
+    range: "on / off",
+};
+
+pub const ENVIRONMENT_ROTATION: ControlHelp = ControlHelp {
+    description: "Rotate the environment map (lighting and background) around the vertical axis without moving the camera.",
+    range: "-180°–180°",
+};
+
+// Textures
+
+pub const MAX_TEXTURE_SIZE: ControlHelp = ControlHelp {
+    description: "Images larger than this are downscaled when loaded, to save GPU memory. Changing it reloads the textures.",
+    range: "512 up to the device limit (pixels)",
+};
+
+pub const SEARCH_SUBFOLDERS: ControlHelp = ControlHelp {
+    description: "Also look for texture maps in subfolders of the texture folder, for sets that keep their maps in e.g. a Textures/ directory. A map closer to the selected folder wins over a deeper one of the same type. Archives always include their subfolders.",
+    range: "on / off (up to 3 levels deep)",
+};
+
+pub const TEXTURE_QUALITY: ControlHelp = ControlHelp {
+    description: "Preset for texture filtering. Performance picks the nearest mip level without anisotropy; Balanced blends mip levels with 4× anisotropy; Quality uses 16× anisotropy for sharp textures at grazing angles.",
+    range: "Performance / Balanced / Quality",
+};
+
+pub const LINEAR_MIPMAPS: ControlHelp = ControlHelp {
+    description: "Blend between the two nearest mip levels (trilinear filtering) instead of snapping to one, hiding visible seams where the level changes.",
+    range: "on / off",
+};
+
+pub const ENABLED_MAPS: ControlHelp = ControlHelp {
+    description: "Temporarily leave maps out of the Lit view to find the one causing an artifact. A disabled map is replaced by its neutral default (flat normal, white AO, no emission, ...). The debug view modes still show every loaded map.",
+    range: "on / off per map",
+};
+
+pub const WRAP_MODE: ControlHelp = ControlHelp {
+    description: "How UVs outside 0-1 are sampled. Repeat tiles the texture (a UV scale above 1 shows several copies), Clamp stretches the edge pixels (useful for decals and atlas previews), Mirror tiles with every other copy flipped.",
+    range: "Repeat / Clamp / Mirror",
+};
+
+pub const NEAREST_FILTERING: ControlHelp = ControlHelp {
+    description: "Sample the nearest texel instead of blending neighbors, keeping low-resolution pixel art crisp. Also switches to nearest mip filtering and turns off anisotropic filtering.",
+    range: "on / off",
+};
+
+pub const SLOT_SAMPLER: ControlHelp = ControlHelp {
+    description: "Wrap and filter mode for this map alone, e.g. a clamped decal map in an otherwise tiling set. Metallic, roughness and ORM share one sampler.",
+    range: "Global (follows the filtering settings above) / Repeat, Clamp, Mirror / Linear, Nearest",
+};
+
+pub const ANISOTROPY: ControlHelp = ControlHelp {
+    description: "Maximum number of samples taken along the stretched direction of a texture seen at a grazing angle. Higher values keep floors and slopes sharp at some GPU cost.",
+    range: "1×–16×",
+};
+
+pub const GENERATE_MIPMAPS: ControlHelp = ControlHelp {
+    description: "Build a chain of downscaled copies when loading a texture so distant surfaces don't shimmer. Changing it reloads the textures.",
+    range: "on / off",
+};
+
+// Render
+
+pub const RENDER_SCALE: ControlHelp = ControlHelp {
+    description: "Resolution of the 3D scene relative to the viewport. Above 1× supersamples for smoother edges, below 1× renders faster on weak GPUs. The UI always renders at native resolution.",
+    range: "0.5×–2×",
+};
+
+pub const PRESENT_MODE: ControlHelp = ControlHelp {
+    description: "How frames are shown. VSync caps the frame rate at the display refresh rate and saves power. Mailbox renders as fast as possible but only shows whole frames. Off presents immediately, for measuring raw performance, and may tear. Only modes the display supports are offered.",
+    range: "VSync / Mailbox / Off",
+};
+
+pub const MSAA: ControlHelp = ControlHelp {
+    description: "Multisample anti-aliasing smooths the edges of the model, grid and bounds. Higher counts cost more GPU memory and fill rate; only counts the GPU supports are offered.",
+    range: "Off, 2×, 4×, 8×",
+};
+
+pub const SCREENSHOT: ControlHelp = ControlHelp {
+    description: "Save the 3D viewport at the current render scale (F12 saves with a timestamped name). The image is read back and written in the background; a notification appears when the file is saved.",
+    range: "PNG / JPEG",
+};
+
+pub const CAMERA_SMOOTHING: ControlHelp = ControlHelp {
+    description: "Ease the camera into zoom, framing and view changes instead of jumping. The value is roughly the time the camera takes to cover two thirds of the way; 0 follows the input instantly.",
+    range: "0–0.5 s",
+};
+
+pub const VIEW_PRESETS: ControlHelp = ControlHelp {
+    description: "Turn the camera to look at the model from one side, keeping the zoom and orbit center. Hold Shift to snap without animating. Numpad 1, 3 and 7 give the front, right and top views, with Ctrl the opposite sides.",
+    range: "Front, Back, Left, Right, Top, Bottom",
+};
+
+pub const FIELD_OF_VIEW: ControlHelp = ControlHelp {
+    description: "Vertical field of view of the viewport camera. Narrow values flatten perspective like a telephoto lens, wide values exaggerate it.",
+    range: "10°–120°",
+};
+
+pub const CLIP_PLANES_AUTO: ControlHelp = ControlHelp {
+    description: "Fit the near and far clip planes around the model as the camera moves, so large models aren't cut off and small ones keep their depth precision. Turn off to set the planes by hand.",
+    range: "on / off",
+};
+
+pub const NEAR_PLANE: ControlHelp = ControlHelp {
+    description: "Distance from the camera below which geometry is clipped. Keep it as large as possible; a tiny near plane causes z-fighting on distant surfaces.",
+    range: "0.001–10 units",
+};
+
+pub const FAR_PLANE: ControlHelp = ControlHelp {
+    description: "Distance from the camera beyond which geometry is clipped. Always at least twice the near plane.",
+    range: "1–10000 units",
+};
+
+pub const FRAME_MODEL: ControlHelp = ControlHelp {
+    description: "Aim the camera at the center of the mesh and move it so the whole mesh fits the view at the current field of view. Shortcut: F in the viewport.",
+    range: "—",
+};
+
+pub const BACKGROUND_COLOR: ControlHelp = ControlHelp {
+    description: "Color behind the model. Check how a material reads against white, 18% gray (photographic middle gray) and black.",
+    range: "RGB, 0–1 per channel",
+};
+
+pub const DEPTH_PREPASS: ControlHelp = ControlHelp {
+    description: "Render the mesh depth first, then shade only the visible surface. Saves fragment work on dense, displaced or parallax-mapped meshes at the cost of drawing the geometry twice. The FPS overlay compares the frame time against the setting before the switch.",
+    range: "on / off",
+};
+
+pub const FRAME_STATS: ControlHelp = ControlHelp {
+    description: "Show the frame rate, the average and worst frame time over the last 120 frames, and a graph of recent frame times in the top-right corner of the viewport. The gray line marks 60 FPS.",
+    range: "on / off (F3)",
+};
+
+pub const SCREENSHOT_INCLUDE_UI: ControlHelp = ControlHelp {
+    description: "Capture the whole window including the settings panel, at window resolution, instead of only the 3D viewport.",
+    range: "on / off",
+};
+
+pub const GRID: ControlHelp = ControlHelp {
+    description: "Draw a procedural reference grid on the ground plane just below the mesh, to judge scale and placement. Every 10th line is highlighted.",
+    range: "on / off, line color",
+};
+
+pub const GRID_SPACING: ControlHelp = ControlHelp {
+    description: "Distance between neighbouring grid lines in world units.",
+    range: "0.05–5 world units (logarithmic)",
+};
+
+pub const GRID_FADE_DISTANCE: ControlHelp = ControlHelp {
+    description: "Distance from the camera at which the grid has fully faded out.",
+    range: "5–100 world units (logarithmic)",
+};
+
+pub const GRID_AXES: ControlHelp = ControlHelp {
+    description: "Draw the world X axis in red and the Z axis in blue where they cross the grid, so the model orientation stays readable while orbiting.",
+    range: "on / off",
+};
+
+pub const SPECULAR_AA: ControlHelp = ControlHelp {
+    description: "Geometric specular anti-aliasing: raises the effective roughness where the shading normal changes quickly between pixels, reducing sparkling highlights on detailed normal maps and curved surfaces.",
+    range: "on / off",
+};
+
+pub const SPECULAR_AA_STRENGTH: ControlHelp = ControlHelp {
+    description: "Scale of the screen-space normal variance added to the roughness. Higher values smooth highlights more aggressively; the added roughness is capped to avoid over-blurring.",
+    range: "0–4",
+};
+
+pub const MULTI_SCATTER: ControlHelp = ControlHelp {
+    description: "Multi-scatter adds back the energy single-scatter GGX loses to light bouncing between microfacets (Fdez-Agüera), so rough metals no longer look too dark. Switch to single-scatter to compare.",
+    range: "Single-Scatter / Multi-Scatter",
+};
+
+pub const EXPOSURE: ControlHelp = ControlHelp {
+    description: "Brightness of the lit view in photographic stops; each stop doubles or halves the light. Applied before tone mapping, so it also moves where highlights start to roll off.",
+    range: "-5–+5 EV",
+};
+
+pub const TONE_MAPPING: ControlHelp = ControlHelp {
+    description: "Curve compressing HDR lighting into the displayable range. None clips bright highlights; Reinhard rolls them off gently; ACES Filmic adds contrast and desaturates highlights like film. Debug views are never tone mapped.",
+    range: "None / Reinhard / ACES Filmic",
+};
+
+pub const AO_AFFECTS_DIRECT: ControlHelp = ControlHelp {
+    description: "Debug comparison: also multiply the direct light by ambient occlusion. Physically, AO should only attenuate ambient/indirect light; enable this to match engines that apply it everywhere.",
+    range: "on / off",
+};
@@ -1,8 +1,19 @@
 //! egui UI implementation for wgpu
 
 use egui::*;
-use crate::state_wgpu::{AppState, ViewMode, TessellationDebugMode, UiTab, MIN_UI_PANEL_WIDTH, MAX_UI_PANEL_WIDTH};
-use crate::mesh_wgpu::MeshType;
+use glam::Quat;
+use crate::camera_wgpu::ViewPreset;
+use crate::state_wgpu::{AppState, ViewMode, TessellationDebugMode, TessellationFalloff, MatrixAxisVariable, NormalDebugSpace, TangentFrameAxis, UiTab, LightColorMode, LightingPreset, TextureQuality, ToneMapping, WireframeMode, kelvin_to_rgb, MIN_UI_PANEL_WIDTH, MAX_UI_PANEL_WIDTH, MIN_RENDER_SCALE, MAX_RENDER_SCALE, MIN_FOV_DEGREES, MAX_FOV_DEGREES, MAX_CAMERA_SMOOTHING, BACKGROUND_PRESETS, DEFAULT_BACKGROUND_COLOR, MIN_PARALLAX_STEPS, MAX_PARALLAX_STEPS};
+use crate::mesh_wgpu::{MeshType, icosphere_subdivisions, MAX_ICOSPHERE_SUBDIVISIONS};
+use crate::config::ViewerConfig;
+use crate::console;
+use crate::share_code::{ShareCode, SHARE_CODE_PREFIX};
+use crate::texture_loader::{is_zip_archive, SUPPORTED_EXTENSIONS};
+use crate::model_loader::MODEL_EXTENSIONS;
+use crate::environment::ENVIRONMENT_EXTENSIONS;
+use crate::tooltips;
+use crate::notifications::NotificationLevel;
+use crate::texture::{ColorSpace, TextureInfo, TextureWrapMode};
 
 /// Build the egui UI with tabs and top bar
 /// Returns the current panel width for viewport calculations
@@ -39,6 +50,30 @@ pub fn build_ui(ctx: &Context, state: &mut AppState) -> f32 {
                         state.material_changed = true;
                     }
                 }
+                
+                // Normals can be shown as stored (tangent space) or as the final shading normal
+                if state.view_mode == ViewMode::Normals {
+                    ui.separator();
+                    for space in [NormalDebugSpace::Tangent, NormalDebugSpace::World] {
+                        if ui.selectable_value(&mut state.normal_debug_space, space, space.name())
+                            .on_hover_text(tooltips::NORMAL_DEBUG_SPACE.text())
+                            .changed()
+                        {
+                            state.material_changed = true;
+                        }
+                    }
+                }
+                if state.view_mode == ViewMode::TangentFrame {
+                    ui.separator();
+                    for axis in TangentFrameAxis::all() {
+                        if ui.selectable_value(&mut state.tangent_frame_axis, *axis, axis.name())
+                            .on_hover_text(tooltips::TANGENT_FRAME_AXIS.text())
+                            .changed()
+                        {
+                            state.material_changed = true;
+                        }
+                    }
+                }
             });
         });
 
@@ -50,11 +85,18 @@ pub fn build_ui(ctx: &Context, state: &mut AppState) -> f32 {
         .show(ctx, |ui| {
             // Title
             ui.add_space(8.0);
-            ui.heading(RichText::new("PBR Viewer").size(20.0).strong());
+            ui.horizontal(|ui| {
+                ui.heading(RichText::new("PBR Viewer").size(20.0).strong());
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    if ui.small_button("⟲ Reset All").clicked() {
+                        state.confirm_reset_all = true;
+                    }
+                });
+            });
             ui.add_space(8.0);
             
             // Tab buttons
-            ui.horizontal(|ui| {
+            ui.horizontal_wrapped(|ui| {
                 if ui.selectable_label(state.ui_tab == UiTab::Mesh, "📦 Mesh").clicked() {
                     state.ui_tab = UiTab::Mesh;
                 }
@@ -67,12 +109,37 @@ pub fn build_ui(ctx: &Context, state: &mut AppState) -> f32 {
                 if ui.selectable_label(state.ui_tab == UiTab::Textures, "📁 Textures").clicked() {
                     state.ui_tab = UiTab::Textures;
                 }
+                if ui.selectable_label(state.ui_tab == UiTab::Render, "🖥 Render").clicked() {
+                    state.ui_tab = UiTab::Render;
+                }
             });
             
             ui.add_space(8.0);
             ui.separator();
             ui.add_space(8.0);
             
+            // Shortcut reference pinned to the bottom of the panel
+            TopBottomPanel::bottom("shortcuts_help")
+                .frame(Frame::none())
+                .show_inside(ui, |ui| {
+                    ui.add_space(4.0);
+                    CollapsingHeader::new("⌨ Keyboard Shortcuts")
+                        .default_open(false)
+                        .show(ui, |ui| {
+                            Grid::new("shortcuts_grid").num_columns(2).show(ui, |ui| {
+                                ui.label(RichText::new(view_mode_keys_label()).monospace().strong());
+                                ui.label("Switch view mode");
+                                ui.end_row();
+                                for (keys, action) in SHORTCUTS {
+                                    ui.label(RichText::new(*keys).monospace().strong());
+                                    ui.label(*action);
+                                    ui.end_row();
+                                }
+                            });
+                        });
+                    ui.add_space(4.0);
+                });
+            
             // Tab content with scroll area
             ScrollArea::vertical()
                 .auto_shrink([false; 2])
@@ -82,16 +149,197 @@ pub fn build_ui(ctx: &Context, state: &mut AppState) -> f32 {
                         UiTab::Material => build_material_tab(ui, state),
                         UiTab::Light => build_light_tab(ui, state),
                         UiTab::Textures => build_textures_tab(ui, state),
+                        UiTab::Render => build_render_tab(ui, state),
                     }
                 });
         });
     
+    // Confirmation popup for "Reset All"
+    if state.confirm_reset_all {
+        Window::new("Reset All to Defaults?")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label("Material, light, tessellation, camera and view mode will be reset.");
+                ui.label(RichText::new("Loaded textures and the current mesh are kept.").weak().small());
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Reset").clicked() {
+                        state.reset_all();
+                        state.confirm_reset_all = false;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        state.confirm_reset_all = false;
+                    }
+                });
+            });
+    }
+    
+    // Debug log panel (only available with --debug)
+    build_debug_log_window(ctx, state);
+    
+    // Command console, toggled with the `~` key
+    if ctx.input_mut(|i| i.consume_key(Modifiers::NONE, Key::Backtick)) {
+        state.console.open = !state.console.open;
+        state.console.focus_input = state.console.open;
+    }
+    build_console_window(ctx, state);
+    
+    // FPS overlay, toggled with F3
+    if ctx.input_mut(|i| i.consume_key(Modifiers::NONE, Key::F3)) {
+        state.show_frame_stats = !state.show_frame_stats;
+    }
+    handle_view_shortcuts(ctx, state);
+    let overlay_bottom = if state.show_frame_stats { state.frame_stats.show(ctx) } else { None };
+    
+    // Toast notifications (stacked below the FPS overlay when it is shown)
+    let toast_top = overlay_bottom.map_or(40.0, |bottom| bottom + 6.0);
+    state.notifications.show(ctx, toast_top);
+    
     // Get the actual panel width and store it
     let panel_width = panel_response.response.rect.width();
     state.ui_panel_width = panel_width;
     panel_width
 }
 
+/// Keyboard bindings listed in the side panel (F, F11, F12 and the numpad views are handled with the window events)
+const SHORTCUTS: &[(&str, &str)] = &[
+    ("F", "Frame the model"),
+    ("Numpad 1 / 3 / 7", "Front / right / top view"),
+    ("Ctrl + Numpad", "Back / left / bottom view"),
+    ("R", "Reset model rotation"),
+    ("Space", "Play / pause turntable"),
+    ("~", "Toggle console"),
+    ("F3", "Toggle FPS overlay"),
+    ("F11", "Toggle fullscreen"),
+    ("F12", "Save screenshot"),
+];
+
+/// Number keys pick a view mode in top bar order
+const VIEW_MODE_KEYS: [Key; 10] = [
+    Key::Num1, Key::Num2, Key::Num3, Key::Num4, Key::Num5,
+    Key::Num6, Key::Num7, Key::Num8, Key::Num9, Key::Num0,
+];
+
+/// Number keys bound to a view mode, e.g. "1-8" (modes beyond the tenth have no key)
+fn view_mode_keys_label() -> String {
+    match ViewMode::all().len().min(VIEW_MODE_KEYS.len()) {
+        0 => "-".to_string(),
+        1 => "1".to_string(),
+        count @ 2..=9 => format!("1-{}", count),
+        _ => "1-9, 0".to_string(),
+    }
+}
+
+/// View mode, rotation and turntable shortcuts (ignored while a text field has focus)
+fn handle_view_shortcuts(ctx: &Context, state: &mut AppState) {
+    if ctx.wants_keyboard_input() {
+        return;
+    }
+    for (key, mode) in VIEW_MODE_KEYS.iter().zip(ViewMode::all()) {
+        if ctx.input_mut(|i| i.consume_key(Modifiers::NONE, *key)) {
+            state.view_mode = *mode;
+            state.material_changed = true;
+        }
+    }
+    if ctx.input_mut(|i| i.consume_key(Modifiers::NONE, Key::R)) {
+        state.model_rotation = Quat::IDENTITY;
+    }
+    if ctx.input_mut(|i| i.consume_key(Modifiers::NONE, Key::Space)) {
+        state.turntable.auto_rotate = !state.turntable.auto_rotate;
+    }
+}
+
+/// Build the floating debug log window showing captured log messages
+fn build_debug_log_window(ctx: &Context, state: &mut AppState) {
+    let Some(buffer) = state.debug_log.clone() else {
+        return;
+    };
+    
+    Window::new("🐞 Debug Log")
+        .open(&mut state.show_debug_log)
+        .default_size([520.0, 240.0])
+        .anchor(Align2::RIGHT_BOTTOM, [-8.0, -8.0])
+        .show(ctx, |ui| {
+            let Ok(mut entries) = buffer.lock() else {
+                return;
+            };
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(format!("{} messages", entries.len())).weak().small());
+                if ui.small_button("Clear").clicked() {
+                    entries.clear();
+                }
+            });
+            ui.separator();
+            ScrollArea::vertical()
+                .auto_shrink([false; 2])
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    for entry in entries.iter() {
+                        let color = match entry.level {
+                            log::Level::Error => Color32::from_rgb(255, 100, 100),
+                            log::Level::Warn => Color32::from_rgb(255, 200, 80),
+                            _ => Color32::from_rgb(180, 180, 180),
+                        };
+                        ui.label(
+                            RichText::new(format!("[{}] {}: {}", entry.level, entry.target, entry.message))
+                                .monospace()
+                                .small()
+                                .color(color),
+                        );
+                    }
+                });
+        });
+}
+
+/// Build the command console window
+fn build_console_window(ctx: &Context, state: &mut AppState) {
+    if !state.console.open {
+        return;
+    }
+    let mut open = true;
+    Window::new("⌨ Console")
+        .open(&mut open)
+        .default_size([520.0, 260.0])
+        .anchor(Align2::CENTER_BOTTOM, [0.0, -8.0])
+        .show(ctx, |ui| {
+            let input_height = ui.spacing().interact_size.y + 8.0;
+            ScrollArea::vertical()
+                .auto_shrink([false; 2])
+                .stick_to_bottom(true)
+                .max_height(ui.available_height() - input_height)
+                .show(ui, |ui| {
+                    for line in &state.console.lines {
+                        let color = if line.is_error {
+                            Color32::from_rgb(255, 100, 100)
+                        } else {
+                            Color32::from_rgb(200, 200, 200)
+                        };
+                        ui.label(RichText::new(&line.text).monospace().color(color));
+                    }
+                });
+            ui.separator();
+            
+            // The toggle key also produces a text event; keep it out of the input
+            state.console.input.retain(|c| c != '`' && c != '~');
+            let response = ui.add(
+                TextEdit::singleline(&mut state.console.input)
+                    .font(TextStyle::Monospace)
+                    .hint_text("type 'help' for commands")
+                    .desired_width(f32::INFINITY),
+            );
+            if response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)) {
+                console::submit(state);
+                response.request_focus();
+            } else if state.console.focus_input {
+                response.request_focus();
+                state.console.focus_input = false;
+            }
+        });
+    state.console.open &= open;
+}
+
 /// Build the Mesh tab content
 fn build_mesh_tab(ui: &mut Ui, state: &mut AppState) {
     ui.heading(RichText::new("Mesh Selection").size(16.0));
@@ -104,7 +352,55 @@ fn build_mesh_tab(ui: &mut Ui, state: &mut AppState) {
                 state.mesh_changed = true;
             }
         }
+        // The last loaded model stays selectable (reloaded from disk)
+        if let Some(model_path) = state.model_path.clone() {
+            let name = std::path::Path::new(&model_path)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| model_path.clone());
+            if ui.selectable_label(state.current_mesh == MeshType::Custom, format!("📦 {}", name))
+                .on_hover_text(model_path.as_str())
+                .clicked()
+                && state.current_mesh != MeshType::Custom
+            {
+                state.model_load_request = Some(model_path.into());
+            }
+        }
+    });
+    
+    ui.add_space(4.0);
+    if ui.button("📂 Load Model...").on_hover_text(tooltips::LOAD_MODEL.text()).clicked() {
+        if let Some(file) = rfd::FileDialog::new()
+            .set_title("Select Model")
+            .add_filter("3D Models", MODEL_EXTENSIONS)
+            .pick_file()
+        {
+            state.model_load_request = Some(file);
+        }
+    }
+    if let Some(model) = recent_list(ui, "Recent Models", &mut state.recent_models) {
+        state.model_load_request = Some(model.into());
+    }
+    // Reload the current model so the change applies right away
+    if ui.checkbox(&mut state.regenerate_model_tangents, "Regenerate tangents")
+        .on_hover_text(tooltips::REGENERATE_TANGENTS.text())
+        .changed()
+        && state.current_mesh == MeshType::Custom
+    {
+        state.model_load_request = state.model_path.clone().map(Into::into);
+    }
+    
+    ui.add_space(8.0);
+    ui.label("Turntable");
+    ui.horizontal(|ui| {
+        let label = if state.turntable.auto_rotate { "⏸ Pause" } else { "▶ Play" };
+        if ui.button(label).clicked() {
+            state.turntable.auto_rotate = !state.turntable.auto_rotate;
+        }
+        ui.add(Slider::new(&mut state.turntable.speed_degrees, -180.0..=180.0).suffix(" °/s").text("Speed"))
+            .on_hover_text(tooltips::TURNTABLE.text());
     });
+    ui.label(RichText::new("Spins the model; dragging it pauses the spin").weak().small());
     
     ui.add_space(16.0);
     ui.separator();
@@ -114,10 +410,391 @@ fn build_mesh_tab(ui: &mut Ui, state: &mut AppState) {
     ui.heading(RichText::new("Mesh Detail").size(14.0));
     ui.add_space(4.0);
     ui.label("Tessellation Level");
-    if ui.add(Slider::new(&mut state.tessellation_level, 4..=128)).changed() {
+    if ui.add(Slider::new(&mut state.tessellation_level, 4..=128)).on_hover_text(tooltips::TESSELLATION_LEVEL.text()).changed() {
         state.mesh_changed = true;
     }
     ui.label(RichText::new("Higher = more polygons").weak().small());
+    if state.current_mesh != MeshType::Custom {
+        ui.label(RichText::new(format!("{} triangles at this level", state.mesh_stats.triangles)).weak().small());
+    }
+    
+    ui.add_space(4.0);
+    ui.horizontal(|ui| {
+        ui.label("Wireframe");
+        ui.add_enabled_ui(state.wireframe_supported, |ui| {
+            ComboBox::from_id_source("wireframe_mode")
+                .selected_text(state.overlay.wireframe.name())
+                .show_ui(ui, |ui| {
+                    for mode in WireframeMode::all() {
+                        ui.selectable_value(&mut state.overlay.wireframe, *mode, mode.name());
+                    }
+                })
+                .response
+                .on_hover_text(tooltips::WIREFRAME.text())
+                .on_disabled_hover_text("Line rasterization is not supported by this GPU");
+        });
+        if state.overlay.wireframe != WireframeMode::Off
+            && ui.color_edit_button_rgb(&mut state.overlay.wireframe_color).changed()
+        {
+            state.material_changed = true;
+        }
+    });
+    if state.current_mesh == MeshType::Icosphere {
+        let level = icosphere_subdivisions(state.tessellation_level).min(MAX_ICOSPHERE_SUBDIVISIONS);
+        ui.label(RichText::new(format!("Icosphere subdivision level {}", level)).weak().small());
+    }
+    
+    if state.current_mesh == MeshType::RoundedRect {
+        ui.add_space(4.0);
+        ui.label("Corner Radius");
+        if ui.add(Slider::new(&mut state.corner_radius, 0.0..=0.45))
+            .on_hover_text(tooltips::CORNER_RADIUS.text())
+            .changed()
+        {
+            state.mesh_changed = true;
+        }
+    }
+    
+    ui.add_space(16.0);
+    ui.separator();
+    ui.add_space(8.0);
+    
+    // Bounds of the current mesh
+    ui.heading(RichText::new("Bounds").size(14.0));
+    ui.add_space(4.0);
+    ui.checkbox(&mut state.show_bounds, "Show Bounding Box")
+        .on_hover_text(tooltips::SHOW_BOUNDS.text());
+    if let Some(bounds) = state.mesh_bounds {
+        let size = bounds.size();
+        let center = bounds.center();
+        Grid::new("mesh_bounds_stats").num_columns(2).show(ui, |ui| {
+            ui.label("Size");
+            ui.label(format!("{:.3} × {:.3} × {:.3}", size.x, size.y, size.z));
+            ui.end_row();
+            ui.label("Center");
+            ui.label(format!("({:.3}, {:.3}, {:.3})", center.x, center.y, center.z));
+            ui.end_row();
+        });
+    } else {
+        ui.label(RichText::new("No mesh loaded").weak().small());
+    }
+    
+    ui.add_space(16.0);
+    ui.separator();
+    ui.add_space(8.0);
+    
+    // Size of the current mesh
+    ui.heading(RichText::new("Statistics").size(14.0));
+    ui.add_space(4.0);
+    let stats = state.mesh_stats;
+    Grid::new("mesh_stats").num_columns(2).show(ui, |ui| {
+        ui.label("Vertices");
+        ui.label(stats.vertices.to_string());
+        ui.end_row();
+        ui.label("Triangles");
+        ui.label(stats.triangles.to_string());
+        ui.end_row();
+        ui.label("Mean edge length");
+        ui.label(format!("{:.4}", stats.mean_edge_length));
+        ui.end_row();
+        // Every material matrix instance draws the whole mesh again
+        if state.material_matrix.enabled {
+            let instances = state.material_matrix.columns.max(1) * state.material_matrix.rows.max(1);
+            ui.label("Drawn triangles");
+            ui.label(format!("{} ({} instances)", stats.triangles as u64 * instances as u64, instances));
+            ui.end_row();
+        }
+    });
+    
+    ui.add_space(16.0);
+    ui.separator();
+    ui.add_space(8.0);
+    
+    // Material matrix (instanced parameter sweep)
+    ui.heading(RichText::new("Material Matrix").size(14.0));
+    ui.add_space(4.0);
+    let matrix = &mut state.material_matrix;
+    if ui.checkbox(&mut matrix.enabled, "Show Material Matrix").on_hover_text(tooltips::MATERIAL_MATRIX.text()).changed() {
+        state.instances_changed = true;
+    }
+    ui.label(RichText::new("Grid of instances sweeping two parameters").weak().small());
+    
+    if matrix.enabled {
+        ui.add_space(4.0);
+        let mut changed = false;
+        for (label, axis, count) in [
+            ("X Axis", &mut matrix.x_axis, &mut matrix.columns),
+            ("Y Axis", &mut matrix.y_axis, &mut matrix.rows),
+        ] {
+            ui.horizontal(|ui| {
+                ui.label(label);
+                ComboBox::from_id_source(label)
+                    .selected_text(axis.name())
+                    .show_ui(ui, |ui| {
+                        for variable in MatrixAxisVariable::all() {
+                            changed |= ui.selectable_value(axis, *variable, variable.name()).changed();
+                        }
+                    });
+                changed |= ui.add(Slider::new(count, 1..=8)).on_hover_text(tooltips::MATRIX_AXIS.text()).changed();
+            });
+            let (min, max) = axis.range();
+            ui.label(RichText::new(format!("{} {:.2} → {:.2}", axis.name(), min, max)).weak().small());
+        }
+        ui.label("Spacing");
+        changed |= ui.add(Slider::new(&mut matrix.spacing, 2.0..=5.0)).on_hover_text(tooltips::MATRIX_SPACING.text()).changed();
+        if changed {
+            state.instances_changed = true;
+        }
+    }
+    
+    ui.add_space(16.0);
+    CollapsingHeader::new("Debug Settings")
+        .default_open(false)
+        .show(ui, |ui| {
+            ui.label("Layer Separation");
+            ui.add(Slider::new(&mut state.layer_separation, 0.0..=0.02).logarithmic(true))
+                .on_hover_text(tooltips::LAYER_SEPARATION.text());
+            ui.label(RichText::new("Gap between the model base and the grid floor to avoid z-fighting").weak().small());
+        });
+}
+
+/// Build the Render tab content (viewport rendering settings)
+fn build_render_tab(ui: &mut Ui, state: &mut AppState) {
+    ui.heading(RichText::new("Resolution").size(16.0));
+    ui.add_space(8.0);
+    
+    ui.label("Render Scale");
+    ui.add(
+        Slider::new(&mut state.render_scale, MIN_RENDER_SCALE..=MAX_RENDER_SCALE)
+            .step_by(0.25)
+            .suffix("×"),
+    )
+    .on_hover_text(tooltips::RENDER_SCALE.text());
+    ui.horizontal(|ui| {
+        for scale in [0.5, 1.0, 2.0] {
+            if ui.selectable_label(state.render_scale == scale, format!("{}×", scale)).clicked() {
+                state.render_scale = scale;
+            }
+        }
+    });
+    ui.label(RichText::new("Above 1× supersamples for crisp screenshots, below 1× renders faster").weak().small());
+    
+    ui.add_space(8.0);
+    ui.label("Anti-aliasing (MSAA)");
+    ui.horizontal(|ui| {
+        for &samples in &state.supported_msaa_samples {
+            let label = if samples == 1 { "Off".to_string() } else { format!("{}×", samples) };
+            if ui.selectable_label(state.msaa_samples == samples, label)
+                .on_hover_text(tooltips::MSAA.text())
+                .clicked()
+            {
+                state.msaa_samples = samples;
+            }
+        }
+    });
+    
+    ui.add_space(8.0);
+    if ui.checkbox(&mut state.depth_prepass, "Depth pre-pass")
+        .on_hover_text(tooltips::DEPTH_PREPASS.text())
+        .changed()
+    {
+        let label = if state.depth_prepass { "Depth pre-pass on" } else { "Depth pre-pass off" };
+        state.frame_stats.start_comparison(label);
+    }
+    
+    ui.add_space(8.0);
+    ui.horizontal(|ui| {
+        ui.label("VSync");
+        ComboBox::from_id_source("present_mode")
+            .selected_text(state.present_mode.name())
+            .show_ui(ui, |ui| {
+                for mode in &state.supported_present_modes {
+                    ui.selectable_value(&mut state.present_mode, *mode, mode.name());
+                }
+            })
+            .response
+            .on_hover_text(tooltips::PRESENT_MODE.text());
+    });
+    
+    ui.add_space(8.0);
+    let fullscreen_label = if state.fullscreen { "🗗 Exit Fullscreen (F11)" } else { "⛶ Fullscreen (F11)" };
+    if ui.button(fullscreen_label).clicked() {
+        state.fullscreen_toggle_requested = true;
+    }
+    
+    ui.add_space(8.0);
+    ui.checkbox(&mut state.show_frame_stats, "Show FPS overlay (F3)")
+        .on_hover_text(tooltips::FRAME_STATS.text());
+    
+    ui.add_space(8.0);
+    if ui.button("📷 Save Screenshot...").on_hover_text(tooltips::SCREENSHOT.text()).clicked() {
+        let default_path = console::default_screenshot_path();
+        if let Some(path) = rfd::FileDialog::new()
+            .set_title("Save Screenshot")
+            .add_filter("PNG Image", &["png"])
+            .add_filter("JPEG Image", &["jpg", "jpeg"])
+            .set_file_name(default_path.to_string_lossy())
+            .save_file()
+        {
+            state.screenshot_request = Some(path);
+        }
+    }
+    ui.checkbox(&mut state.screenshot_include_ui, "Include UI")
+        .on_hover_text(tooltips::SCREENSHOT_INCLUDE_UI.text());
+    
+    ui.add_space(16.0);
+    ui.separator();
+    ui.add_space(8.0);
+    
+    ui.heading(RichText::new("Camera").size(16.0));
+    ui.add_space(8.0);
+    
+    ui.label("Field of View");
+    ui.add(Slider::new(&mut state.fov_degrees, MIN_FOV_DEGREES..=MAX_FOV_DEGREES).suffix("°"))
+        .on_hover_text(tooltips::FIELD_OF_VIEW.text());
+    
+    ui.add_space(4.0);
+    ui.checkbox(&mut state.clip_planes.auto, "Auto clip planes")
+        .on_hover_text(tooltips::CLIP_PLANES_AUTO.text());
+    ui.add_enabled_ui(!state.clip_planes.auto, |ui| {
+        ui.label("Near Plane");
+        ui.add(Slider::new(&mut state.clip_planes.near, 0.001..=10.0).logarithmic(true).suffix(" units"))
+            .on_hover_text(tooltips::NEAR_PLANE.text());
+        ui.label("Far Plane");
+        ui.add(Slider::new(&mut state.clip_planes.far, 1.0..=10000.0).logarithmic(true).suffix(" units"))
+            .on_hover_text(tooltips::FAR_PLANE.text());
+    });
+    // The projection degenerates if the planes meet
+    state.clip_planes.far = state.clip_planes.far.max(state.clip_planes.near * 2.0);
+    
+    ui.add_space(4.0);
+    if ui.button("⛶ Frame Model (F)").on_hover_text(tooltips::FRAME_MODEL.text()).clicked() {
+        state.frame_requested = true;
+    }
+    
+    ui.add_space(4.0);
+    ui.label("Smoothing");
+    ui.add(Slider::new(&mut state.camera_smoothing, 0.0..=MAX_CAMERA_SMOOTHING).suffix(" s"))
+        .on_hover_text(tooltips::CAMERA_SMOOTHING.text());
+    
+    ui.add_space(4.0);
+    ui.label("View").on_hover_text(tooltips::VIEW_PRESETS.text());
+    ui.horizontal_wrapped(|ui| {
+        for preset in ViewPreset::all() {
+            if ui.button(preset.name()).clicked() {
+                let instant = ui.input(|i| i.modifiers.shift);
+                state.view_preset_request = Some((*preset, instant));
+            }
+        }
+    });
+    
+    ui.add_space(16.0);
+    ui.separator();
+    ui.add_space(8.0);
+    
+    ui.heading(RichText::new("Grid Floor").size(16.0));
+    ui.add_space(8.0);
+    
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut state.grid.enabled, "Show Grid")
+            .on_hover_text(tooltips::GRID.text());
+        ui.color_edit_button_rgb(&mut state.grid.color);
+    });
+    ui.add_enabled_ui(state.grid.enabled, |ui| {
+        ui.label("Spacing");
+        ui.add(Slider::new(&mut state.grid.spacing, 0.05..=5.0).logarithmic(true).suffix(" units"))
+            .on_hover_text(tooltips::GRID_SPACING.text());
+        ui.label("Fade Distance");
+        ui.add(Slider::new(&mut state.grid.fade_distance, 5.0..=100.0).logarithmic(true).suffix(" units"))
+            .on_hover_text(tooltips::GRID_FADE_DISTANCE.text());
+        ui.checkbox(&mut state.grid.show_axes, "Highlight axes")
+            .on_hover_text(tooltips::GRID_AXES.text());
+    });
+    
+    ui.add_space(16.0);
+    ui.separator();
+    ui.add_space(8.0);
+    
+    ui.heading(RichText::new("Background").size(16.0));
+    ui.add_space(8.0);
+    
+    ui.horizontal(|ui| {
+        ui.color_edit_button_rgb(&mut state.background_color)
+            .on_hover_text(tooltips::BACKGROUND_COLOR.text());
+        for (name, color) in BACKGROUND_PRESETS {
+            if ui.selectable_label(state.background_color == *color, *name).clicked() {
+                state.background_color = *color;
+            }
+        }
+        if ui.button("Reset").clicked() {
+            state.background_color = DEFAULT_BACKGROUND_COLOR;
+        }
+    });
+    if state.environment.show_background && state.environment.path.is_some() {
+        ui.label(RichText::new("Hidden behind the environment (Light tab)").weak().small());
+    }
+    
+    ui.add_space(16.0);
+    ui.separator();
+    ui.add_space(8.0);
+    
+    ui.heading(RichText::new("Shading").size(16.0));
+    ui.add_space(8.0);
+    
+    if ui.checkbox(&mut state.shading.specular_aa, "Specular Anti-Aliasing")
+        .on_hover_text(tooltips::SPECULAR_AA.text())
+        .changed()
+    {
+        state.material_changed = true;
+    }
+    ui.label("Strength");
+    if ui.add_enabled(state.shading.specular_aa, Slider::new(&mut state.shading.specular_aa_strength, 0.0..=4.0))
+        .on_hover_text(tooltips::SPECULAR_AA_STRENGTH.text())
+        .changed()
+    {
+        state.material_changed = true;
+    }
+    
+    ui.add_space(8.0);
+    ui.horizontal(|ui| {
+        ui.label("Specular");
+        let single = ui.selectable_value(&mut state.shading.multi_scatter, false, "Single-Scatter");
+        let multi = ui.selectable_value(&mut state.shading.multi_scatter, true, "Multi-Scatter");
+        if single.union(multi).on_hover_text(tooltips::MULTI_SCATTER.text()).changed() {
+            state.material_changed = true;
+        }
+    });
+    
+    ui.add_space(8.0);
+    ui.label("Exposure");
+    if ui.add(Slider::new(&mut state.shading.exposure_ev, -5.0..=5.0).suffix(" EV"))
+        .on_hover_text(tooltips::EXPOSURE.text())
+        .changed()
+    {
+        state.material_changed = true;
+    }
+    ui.horizontal(|ui| {
+        ui.label("Tone Mapping");
+        ComboBox::from_id_source("tone_mapping")
+            .selected_text(state.shading.tone_mapping.name())
+            .show_ui(ui, |ui| {
+                for mode in ToneMapping::all() {
+                    if ui.selectable_value(&mut state.shading.tone_mapping, *mode, mode.name()).changed() {
+                        state.material_changed = true;
+                    }
+                }
+            })
+            .response
+            .on_hover_text(tooltips::TONE_MAPPING.text());
+    });
+    
+    ui.add_space(8.0);
+    if ui.checkbox(&mut state.shading.ao_affects_direct, "AO affects direct light")
+        .on_hover_text(tooltips::AO_AFFECTS_DIRECT.text())
+        .changed()
+    {
+        state.material_changed = true;
+    }
 }
 
 /// Build the Material tab content (includes tessellation settings)
@@ -128,7 +805,7 @@ fn build_material_tab(ui: &mut Ui, state: &mut AppState) {
     
     ui.label("Base Color Tint");
     ui.horizontal(|ui| {
-        if ui.color_edit_button_rgb(&mut state.material_params.base_color_tint).changed() {
+        if ui.color_edit_button_rgb(&mut state.material_params.base_color_tint).on_hover_text(tooltips::BASE_COLOR_TINT.text()).changed() {
             state.material_changed = true;
         }
         if ui.button("Reset").clicked() {
@@ -139,142 +816,255 @@ fn build_material_tab(ui: &mut Ui, state: &mut AppState) {
     
     ui.add_space(4.0);
     ui.label("Metallic");
-    if ui.add(Slider::new(&mut state.material_params.metallic_multiplier, 0.0..=1.0)).changed() {
+    if ui.add(Slider::new(&mut state.material_params.metallic_multiplier, 0.0..=1.0)).on_hover_text(tooltips::METALLIC.text()).changed() {
         state.material_changed = true;
     }
     
     ui.label("Roughness");
-    if ui.add(Slider::new(&mut state.material_params.roughness_multiplier, 0.0..=1.0)).changed() {
+    if ui.add(Slider::new(&mut state.material_params.roughness_multiplier, 0.0..=1.0)).on_hover_text(tooltips::ROUGHNESS.text()).changed() {
+        state.material_changed = true;
+    }
+    if ui.checkbox(&mut state.material_params.invert_roughness, "Map is glossiness (invert)").on_hover_text(tooltips::INVERT_ROUGHNESS.text()).changed() {
         state.material_changed = true;
     }
     
     ui.label("Normal Strength");
-    if ui.add(Slider::new(&mut state.material_params.normal_strength, 0.0..=2.0)).changed() {
+    if ui.add(Slider::new(&mut state.material_params.normal_strength, 0.0..=2.0)).on_hover_text(tooltips::NORMAL_STRENGTH.text()).changed() {
+        state.material_changed = true;
+    }
+    if ui.checkbox(&mut state.material_params.flip_normal_y, "Flip green channel (DirectX)").on_hover_text(tooltips::FLIP_NORMAL_Y.text()).changed() {
         state.material_changed = true;
     }
     
     ui.label("AO Strength");
-    if ui.add(Slider::new(&mut state.material_params.ao_strength, 0.0..=2.0)).changed() {
+    if ui.add(Slider::new(&mut state.material_params.ao_strength, 0.0..=2.0)).on_hover_text(tooltips::AO_STRENGTH.text()).changed() {
+        state.material_changed = true;
+    }
+    if ui.checkbox(&mut state.material_params.occlusion_uses_uv1, "AO uses second UV set (UV1)").on_hover_text(tooltips::OCCLUSION_UV1.text()).changed() {
+        state.material_changed = true;
+    }
+    if ui.checkbox(&mut state.material_params.invert_ao, "Invert AO map").on_hover_text(tooltips::INVERT_AO.text()).changed() {
         state.material_changed = true;
     }
     
     ui.label("Emissive Strength");
-    if ui.add(Slider::new(&mut state.material_params.emissive_strength, 0.0..=5.0)).changed() {
+    if ui.add(Slider::new(&mut state.material_params.emissive_strength, 0.0..=5.0)).on_hover_text(tooltips::EMISSIVE_STRENGTH.text()).changed() {
         state.material_changed = true;
     }
     
+    ui.label("Parallax Depth");
+    if ui.add(Slider::new(&mut state.material_params.displacement_strength, 0.0..=0.2)).on_hover_text(tooltips::PARALLAX_DEPTH.text()).changed() {
+        state.material_changed = true;
+    }
+    ui.label("Parallax Steps");
+    if ui.add(Slider::new(&mut state.material_params.parallax_steps, MIN_PARALLAX_STEPS..=MAX_PARALLAX_STEPS)).on_hover_text(tooltips::PARALLAX_STEPS.text()).changed() {
+        state.material_changed = true;
+    }
+    if !state.loaded_textures.height {
+        ui.label(RichText::new("Parallax needs a height texture").weak().small());
+    }
+    
     ui.add_space(8.0);
     ui.label("UV Tile Size");
     ui.label(RichText::new("Smaller = more repeats").weak().small());
-    if ui.add(Slider::new(&mut state.material_params.uv_scale, 0.1..=5.0).logarithmic(true)).changed() {
+    let mut linked = state.material_params.uv_scale_v.is_none();
+    if ui.checkbox(&mut linked, "🔒 Same size for U and V").on_hover_text(tooltips::UV_TILE_LINK.text()).changed() {
+        state.material_params.uv_scale_v = (!linked).then_some(state.material_params.uv_scale);
+        state.material_changed = true;
+    }
+    let u_slider = Slider::new(&mut state.material_params.uv_scale, 0.1..=5.0).logarithmic(true);
+    let u_slider = if linked { u_slider } else { u_slider.prefix("U: ") };
+    if ui.add(u_slider).on_hover_text(tooltips::UV_TILE_SIZE.text()).changed() {
+        state.material_changed = true;
+    }
+    if let Some(scale_v) = state.material_params.uv_scale_v.as_mut() {
+        if ui.add(Slider::new(scale_v, 0.1..=5.0).logarithmic(true).prefix("V: ")).on_hover_text(tooltips::UV_TILE_SIZE.text()).changed() {
+            state.material_changed = true;
+        }
+    }
+    ui.label("UV Offset");
+    ui.horizontal(|ui| {
+        for (axis, offset) in ["U", "V"].into_iter().zip(state.material_params.uv_offset.iter_mut()) {
+            if ui.add(DragValue::new(offset).speed(0.005).range(-1.0..=1.0).prefix(format!("{}: ", axis)))
+                .on_hover_text(tooltips::UV_OFFSET.text())
+                .changed()
+            {
+                state.material_changed = true;
+            }
+        }
+        if ui.button("Reset").clicked() {
+            state.material_params.uv_offset = [0.0, 0.0];
+            state.material_changed = true;
+        }
+    });
+    ui.label("UV Rotation");
+    if ui.add(Slider::new(&mut state.material_params.uv_rotation_degrees, -180.0..=180.0).suffix("°"))
+        .on_hover_text(tooltips::UV_ROTATION.text())
+        .changed()
+    {
         state.material_changed = true;
     }
+    ui.horizontal(|ui| {
+        if ui.checkbox(&mut state.overlay.uv_tile_lines, "Show Tile Boundaries")
+            .on_hover_text(tooltips::UV_TILE_LINES.text())
+            .changed()
+        {
+            state.material_changed = true;
+        }
+        if state.overlay.uv_tile_lines && ui.color_edit_button_rgb(&mut state.overlay.uv_tile_line_color).changed() {
+            state.material_changed = true;
+        }
+    });
+    
+    ui.add_space(8.0);
+    if ui.button("💾 Save as Default").on_hover_text(tooltips::SAVE_AS_DEFAULT.text()).clicked() {
+        match ViewerConfig::from_state(state).save_as_default() {
+            Ok(path) => state.notifications.push(
+                NotificationLevel::Success,
+                format!("Saved default config to {}", path.display()),
+            ),
+            Err(e) => state.notifications.push(
+                NotificationLevel::Error,
+                format!("Failed to save default config: {}", e),
+            ),
+        }
+    }
+    ui.label(RichText::new("Material, light and background used on startup").weak().small());
+    
+    ui.add_space(8.0);
+    ui.label("Share Code");
+    if ui.button("📋 Copy Share Code").on_hover_text(tooltips::SHARE_CODE.text()).clicked() {
+        match ShareCode::from_state(state).encode() {
+            Ok(code) => {
+                ui.output_mut(|o| o.copied_text = code);
+                state.notifications.push(NotificationLevel::Success, "Share code copied to clipboard");
+            }
+            Err(e) => state.notifications.push(
+                NotificationLevel::Error,
+                format!("Failed to create share code: {}", e),
+            ),
+        }
+    }
+    ui.horizontal(|ui| {
+        ui.add(TextEdit::singleline(&mut state.share_code_input).hint_text(format!("{}…", SHARE_CODE_PREFIX)).desired_width(180.0));
+        if ui.add_enabled(!state.share_code_input.trim().is_empty(), Button::new("Apply")).clicked() {
+            match ShareCode::decode(&state.share_code_input) {
+                Ok(code) => {
+                    code.apply_to(state);
+                    state.share_code_input.clear();
+                    state.notifications.push(NotificationLevel::Success, "Share code applied");
+                }
+                Err(e) => state.notifications.push(
+                    NotificationLevel::Error,
+                    format!("Invalid share code: {}", e),
+                ),
+            }
+        }
+    });
     
     ui.add_space(16.0);
     ui.separator();
     ui.add_space(8.0);
     
-    // GPU Tessellation Section
-    ui.heading(RichText::new("GPU Tessellation").size(16.0));
+    // Height map displacement (vertex shader; the mesh density sets the detail)
+    ui.heading(RichText::new("Height Displacement").size(16.0));
     ui.add_space(4.0);
     
-    if ui.checkbox(&mut state.gpu_tessellation.enabled, "Enable GPU Tessellation").changed() {
+    if ui.checkbox(&mut state.gpu_tessellation.enabled, "Displace vertices by height map").on_hover_text(tooltips::GPU_TESSELLATION.text()).changed() {
         state.material_changed = true;
     }
-    ui.label(RichText::new("Requires DX12/Vulkan with tessellation support").weak().small());
+    if !state.loaded_textures.height {
+        ui.label(RichText::new("Needs a height texture").weak().small());
+    }
+    ui.label(RichText::new("Raise the tessellation level above for finer detail").weak().small());
     
     if state.gpu_tessellation.enabled {
         ui.add_space(8.0);
         
-        ui.label("Min Tessellation Factor");
-        if ui.add(Slider::new(&mut state.gpu_tessellation.min_tess_factor, 1.0..=16.0)).changed() {
-            state.material_changed = true;
-        }
-        
-        ui.label("Max Tessellation Factor");
-        if ui.add(Slider::new(&mut state.gpu_tessellation.max_tess_factor, 1.0..=128.0)).changed() {
-            state.material_changed = true;
-        }
-        
-        ui.label("Quality Cap");
-        if ui.add(Slider::new(&mut state.gpu_tessellation.quality_cap, 1.0..=128.0)).changed() {
-            state.material_changed = true;
-        }
-        
-        ui.add_space(12.0);
-        ui.separator();
-        ui.add_space(8.0);
-        
-        // Displacement parameters
-        ui.heading(RichText::new("Displacement").size(14.0));
-        ui.add_space(4.0);
-        
         ui.label("Displacement Scale");
-        if ui.add(Slider::new(&mut state.gpu_tessellation.displacement_scale, 0.0..=1.0)).changed() {
+        if ui.add(Slider::new(&mut state.gpu_tessellation.displacement_scale, 0.0..=1.0)).on_hover_text(tooltips::DISPLACEMENT_SCALE.text()).changed() {
             state.material_changed = true;
         }
         
         ui.label("Displacement Midpoint");
-        if ui.add(Slider::new(&mut state.gpu_tessellation.displacement_midpoint, 0.0..=1.0)).changed() {
+        if ui.add(Slider::new(&mut state.gpu_tessellation.displacement_midpoint, 0.0..=1.0)).on_hover_text(tooltips::DISPLACEMENT_MIDPOINT.text()).changed() {
             state.material_changed = true;
         }
         
         ui.label("Displacement Bias");
-        if ui.add(Slider::new(&mut state.gpu_tessellation.displacement_bias, -1.0..=1.0)).changed() {
+        if ui.add(Slider::new(&mut state.gpu_tessellation.displacement_bias, -1.0..=1.0)).on_hover_text(tooltips::DISPLACEMENT_BIAS.text()).changed() {
             state.material_changed = true;
         }
         
         ui.label("Clamp Min");
-        if ui.add(Slider::new(&mut state.gpu_tessellation.displacement_clamp_min, -2.0..=0.0)).changed() {
+        if ui.add(Slider::new(&mut state.gpu_tessellation.displacement_clamp_min, -2.0..=0.0)).on_hover_text(tooltips::DISPLACEMENT_CLAMP_MIN.text()).changed() {
             state.material_changed = true;
         }
         
         ui.label("Clamp Max");
-        if ui.add(Slider::new(&mut state.gpu_tessellation.displacement_clamp_max, 0.0..=2.0)).changed() {
-            state.material_changed = true;
-        }
-        
-        ui.add_space(12.0);
-        ui.separator();
-        ui.add_space(8.0);
-        
-        // Tessellation quality settings
-        ui.heading(RichText::new("Quality Settings").size(14.0));
-        ui.add_space(4.0);
-        
-        ui.label("Screen Space Scale");
-        if ui.add(Slider::new(&mut state.gpu_tessellation.screen_space_scale, 10.0..=500.0).logarithmic(true)).changed() {
+        if ui.add(Slider::new(&mut state.gpu_tessellation.displacement_clamp_max, 0.0..=2.0)).on_hover_text(tooltips::DISPLACEMENT_CLAMP_MAX.text()).changed() {
             state.material_changed = true;
         }
         
         ui.label("Distance Scale");
-        if ui.add(Slider::new(&mut state.gpu_tessellation.distance_scale, 0.01..=1.0).logarithmic(true)).changed() {
+        if ui.add(Slider::new(&mut state.gpu_tessellation.distance_scale, 0.01..=1.0).logarithmic(true)).on_hover_text(tooltips::DISTANCE_SCALE.text()).changed() {
             state.material_changed = true;
         }
         
+        ui.label("Distance Falloff");
+        ComboBox::from_id_source("tessellation_falloff")
+            .selected_text(state.gpu_tessellation.distance_falloff.name())
+            .show_ui(ui, |ui| {
+                for falloff in TessellationFalloff::all() {
+                    if ui.selectable_value(&mut state.gpu_tessellation.distance_falloff, *falloff, falloff.name()).changed() {
+                        state.material_changed = true;
+                    }
+                }
+            })
+            .response
+            .on_hover_text(tooltips::DISTANCE_FALLOFF.text());
+        
         ui.add_space(12.0);
-        ui.separator();
-        ui.add_space(8.0);
         
-        // Debug visualization
-        ui.heading(RichText::new("Debug Visualization").size(14.0));
-        ui.add_space(4.0);
-        ui.horizontal(|ui| {
-            if ui.selectable_value(&mut state.gpu_tessellation.debug_visualization, TessellationDebugMode::None, "None").changed() {
-                state.material_changed = true;
-            }
-            if ui.selectable_value(&mut state.gpu_tessellation.debug_visualization, TessellationDebugMode::TessellationDensity, "Density").changed() {
-                state.material_changed = true;
-            }
-        });
-        ui.horizontal(|ui| {
-            if ui.selectable_value(&mut state.gpu_tessellation.debug_visualization, TessellationDebugMode::Wireframe, "Wireframe").changed() {
-                state.material_changed = true;
-            }
-            if ui.selectable_value(&mut state.gpu_tessellation.debug_visualization, TessellationDebugMode::DisplacementOnly, "Displacement").changed() {
-                state.material_changed = true;
-            }
+        // Adaptive factors need a hardware tessellation stage, which wgpu does not expose
+        CollapsingHeader::new("Adaptive Tessellation (unavailable)")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.label(RichText::new("wgpu has no tessellation shader stage; these settings are kept for a future implementation").weak().small());
+                ui.add_enabled_ui(false, |ui| {
+                    ui.label("Min Tessellation Factor");
+                    ui.add(Slider::new(&mut state.gpu_tessellation.min_tess_factor, 1.0..=16.0)).on_hover_text(tooltips::MIN_TESS_FACTOR.text());
+                    
+                    ui.label("Max Tessellation Factor");
+                    ui.add(Slider::new(&mut state.gpu_tessellation.max_tess_factor, 1.0..=128.0)).on_hover_text(tooltips::MAX_TESS_FACTOR.text());
+                    
+                    ui.label("Quality Cap");
+                    ui.add(Slider::new(&mut state.gpu_tessellation.quality_cap, 1.0..=128.0)).on_hover_text(tooltips::QUALITY_CAP.text());
+                    
+                    ui.label("Screen Space Scale");
+                    ui.add(Slider::new(&mut state.gpu_tessellation.screen_space_scale, 10.0..=500.0).logarithmic(true)).on_hover_text(tooltips::SCREEN_SPACE_SCALE.text());
+
+                });
+            });
+    }
+    
+    // The density heatmap works on any mesh; the other modes need hardware tessellation
+    ui.add_space(8.0);
+    ui.label("Debug Visualization").on_hover_text(tooltips::TESSELLATION_DEBUG.text());
+    let debug_before = state.gpu_tessellation.debug_visualization;
+    ui.horizontal_wrapped(|ui| {
+        ui.selectable_value(&mut state.gpu_tessellation.debug_visualization, TessellationDebugMode::None, "None");
+        ui.selectable_value(&mut state.gpu_tessellation.debug_visualization, TessellationDebugMode::TessellationDensity, "Density");
+        ui.add_enabled_ui(false, |ui| {
+            ui.selectable_value(&mut state.gpu_tessellation.debug_visualization, TessellationDebugMode::Wireframe, "Wireframe");
+            ui.selectable_value(&mut state.gpu_tessellation.debug_visualization, TessellationDebugMode::DisplacementOnly, "Displacement");
         });
+    });
+    if state.gpu_tessellation.debug_visualization == TessellationDebugMode::TessellationDensity {
+        ui.label(RichText::new("Red: dense (~2 px edges), blue: coarse (~128 px edges)").weak().small());
+    }
+    if state.gpu_tessellation.debug_visualization != debug_before {
+        state.material_changed = true;
     }
 }
 
@@ -283,6 +1073,27 @@ fn build_light_tab(ui: &mut Ui, state: &mut AppState) {
     ui.heading(RichText::new("Light Settings").size(16.0));
     ui.add_space(8.0);
     
+    // Presets replace every light, so the combo box acts as a menu of actions
+    let mut chosen_preset = None;
+    ui.horizontal(|ui| {
+        ui.label("Preset");
+        ComboBox::from_id_source("lighting_preset")
+            .selected_text("Apply…")
+            .show_ui(ui, |ui| {
+                for preset in LightingPreset::all() {
+                    if ui.selectable_label(false, preset.name()).clicked() {
+                        chosen_preset = Some(*preset);
+                    }
+                }
+            })
+            .response
+            .on_hover_text(tooltips::LIGHTING_PRESET.text());
+    });
+    if let Some(preset) = chosen_preset {
+        state.apply_lighting_preset(preset);
+    }
+    ui.add_space(8.0);
+    
     // Light direction visualization
     ui.group(|ui| {
         let size = egui::vec2(120.0, 120.0);
@@ -344,7 +1155,9 @@ fn build_light_tab(ui: &mut Ui, state: &mut AppState) {
     ui.separator();
     ui.add_space(8.0);
     
-    ui.label("Direction (Manual)");
+    ui.label("Direction (Manual)").on_hover_text(tooltips::LIGHT_DIRECTION.text());
+    ui.checkbox(&mut state.show_light_gizmo, "Show Light Gizmo")
+        .on_hover_text(tooltips::SHOW_LIGHT_GIZMO.text());
     let mut dir_changed = false;
     ui.horizontal(|ui| {
         if ui.add(Slider::new(&mut state.light_params.direction.x, -1.0..=1.0).text("X")).changed() {
@@ -370,16 +1183,182 @@ fn build_light_tab(ui: &mut Ui, state: &mut AppState) {
         }
     }
     
+    ui.add_space(8.0);
+    ui.label("Light Orbit");
+    ui.horizontal(|ui| {
+        let label = if state.light_orbit.playing { "⏸ Pause" } else { "▶ Play" };
+        if ui.button(label).clicked() {
+            state.light_orbit.playing = !state.light_orbit.playing;
+        }
+        ui.add(Slider::new(&mut state.light_orbit.period_seconds, 2.0..=60.0).suffix(" s").text("Period"))
+            .on_hover_text(tooltips::LIGHT_ORBIT.text());
+    });
+    ui.label(RichText::new("Orbits the light around the model").weak().small());
+    
+    ui.add_space(8.0);
+    ui.horizontal(|ui| {
+        ui.label("Light Color");
+        ui.selectable_value(&mut state.light_params.color_mode, LightColorMode::Rgb, "RGB");
+        ui.selectable_value(&mut state.light_params.color_mode, LightColorMode::Kelvin, "Kelvin");
+    });
+    match state.light_params.color_mode {
+        LightColorMode::Rgb => {
+            if ui.color_edit_button_rgb(&mut state.light_params.color)
+                .on_hover_text(tooltips::LIGHT_COLOR.text())
+                .changed()
+            {
+                state.material_changed = true;
+            }
+        }
+        LightColorMode::Kelvin => {
+            ui.horizontal(|ui| {
+                let response = ui.add(
+                    Slider::new(&mut state.light_params.temperature_kelvin, 1000.0..=12000.0)
+                        .step_by(100.0)
+                        .suffix(" K"),
+                )
+                .on_hover_text(tooltips::LIGHT_TEMPERATURE.text());
+                if response.changed() {
+                    state.light_params.color = kelvin_to_rgb(state.light_params.temperature_kelvin);
+                    state.material_changed = true;
+                }
+                // Swatch of the resulting color (egui colors are sRGB)
+                let swatch = Rgba::from_rgb(
+                    state.light_params.color[0],
+                    state.light_params.color[1],
+                    state.light_params.color[2],
+                );
+                let (rect, _) = ui.allocate_exact_size(vec2(18.0, 18.0), Sense::hover());
+                ui.painter().rect_filled(rect, 3.0, Color32::from(swatch));
+            });
+            ui.label(RichText::new("Candle ~1900K, tungsten ~2700K, daylight ~6500K").weak().small());
+        }
+    }
+    
     ui.add_space(8.0);
     ui.label("Intensity");
-    if ui.add(Slider::new(&mut state.light_params.intensity, 0.0..=50.0)).changed() {
+    if ui.add(Slider::new(&mut state.light_params.intensity, 0.0..=20.0)).on_hover_text(tooltips::LIGHT_INTENSITY.text()).changed() {
         state.material_changed = true;
     }
     
+    ui.horizontal(|ui| {
+        ui.label("Ambient");
+        if ui.color_edit_button_rgb(&mut state.light_params.ambient_color)
+            .on_hover_text(tooltips::AMBIENT_COLOR.text())
+            .changed()
+        {
+            state.material_changed = true;
+        }
+    });
     ui.label("Ambient Intensity");
-    if ui.add(Slider::new(&mut state.light_params.ambient_intensity, 0.0..=2.0)).changed() {
+    if ui.add(Slider::new(&mut state.light_params.ambient_intensity, 0.0..=2.0)).on_hover_text(tooltips::AMBIENT_INTENSITY.text()).changed() {
         state.material_changed = true;
     }
+    
+    if !state.secondary_lights.is_empty() {
+        ui.add_space(8.0);
+        ui.label("Secondary Lights").on_hover_text(tooltips::SECONDARY_LIGHTS.text());
+        let mut changed = false;
+        let mut removed = None;
+        for (index, light) in state.secondary_lights.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(match index {
+                    0 => "Fill",
+                    1 => "Rim",
+                    _ => "Extra",
+                });
+                if ui.color_edit_button_rgb(&mut light.color).changed() {
+                    light.color_mode = LightColorMode::Rgb;
+                    changed = true;
+                }
+                changed |= ui.add(Slider::new(&mut light.intensity, 0.0..=20.0)).changed();
+                if ui.small_button("✕").on_hover_text("Remove this light").clicked() {
+                    removed = Some(index);
+                }
+            });
+        }
+        if let Some(index) = removed {
+            state.secondary_lights.remove(index);
+            changed = true;
+        }
+        if changed {
+            state.material_changed = true;
+        }
+    }
+    
+    ui.add_space(8.0);
+    ui.checkbox(&mut state.shadows.enabled, "Shadows").on_hover_text(tooltips::SHADOWS.text());
+    ui.add_enabled_ui(state.shadows.enabled, |ui| {
+        ui.label("Shadow Bias");
+        ui.add(Slider::new(&mut state.shadows.bias, 0.0..=0.1).suffix(" units"))
+            .on_hover_text(tooltips::SHADOW_BIAS.text());
+    });
+    
+    ui.add_space(12.0);
+    ui.separator();
+    ui.add_space(8.0);
+    
+    // Environment rotation (spins IBL and skybox without moving the camera)
+    ui.heading(RichText::new("Environment").size(14.0));
+    ui.add_space(4.0);
+    let has_environment = state.environment.path.is_some();
+    if let Some(path) = &state.environment.path {
+        let name = std::path::Path::new(path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.clone());
+        ui.label(RichText::new(format!("🌅 {}", name)).small());
+    }
+    if ui.button("📂 Load Environment...").on_hover_text(tooltips::LOAD_ENVIRONMENT.text()).clicked() {
+        if let Some(file) = rfd::FileDialog::new()
+            .set_title("Select Environment Map")
+            .add_filter("HDR Images", ENVIRONMENT_EXTENSIONS)
+            .pick_file()
+        {
+            state.environment_load_request = Some(file);
+        }
+    }
+    ui.add_enabled(has_environment, Checkbox::new(&mut state.environment.show_background, "Show as background"))
+        .on_hover_text(tooltips::SHOW_ENVIRONMENT_BACKGROUND.text());
+    ui.add_space(4.0);
+    ui.label("Rotation");
+    if ui.add_enabled(has_environment, Slider::new(&mut state.environment.rotation_degrees, -180.0..=180.0).suffix("°"))
+        .on_hover_text(tooltips::ENVIRONMENT_ROTATION.text())
+        .changed() {
+        state.material_changed = true;
+    }
+    if !has_environment {
+        ui.label(RichText::new("Load an environment map to enable").weak().small());
+    }
+}
+
+/// Collapsible list of recent paths; returns the clicked one. Entries that
+/// no longer exist are pruned (checked when clicked, so the list stays cheap to draw).
+fn recent_list(ui: &mut Ui, title: &str, entries: &mut Vec<String>) -> Option<String> {
+    if entries.is_empty() {
+        return None;
+    }
+    let mut clicked = None;
+    CollapsingHeader::new(title)
+        .id_source(title)
+        .show(ui, |ui| {
+            for entry in entries.iter() {
+                let path = std::path::Path::new(entry);
+                let name = path.file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| entry.clone());
+                if ui.selectable_label(false, name).on_hover_text(entry.as_str()).clicked() {
+                    clicked = Some(entry.clone());
+                }
+            }
+        });
+    let clicked = clicked?;
+    if std::path::Path::new(&clicked).exists() {
+        Some(clicked)
+    } else {
+        entries.retain(|entry| *entry != clicked);
+        None
+    }
 }
 
 /// Build the Textures tab content
@@ -398,11 +1377,70 @@ fn build_textures_tab(ui: &mut Ui, state: &mut AppState) {
         }
     }
     
-    if let Some(ref folder) = state.texture_folder {
-        ui.label(RichText::new(format!("📂 {}", folder)).small());
+    // Load zip archive button (decompressed in memory, no extraction needed)
+    if ui.button("📦 Load Texture Archive (.zip)").clicked() {
+        if let Some(file) = rfd::FileDialog::new()
+            .set_title("Select Texture Archive")
+            .add_filter("Zip Archive", &["zip"])
+            .pick_file()
+        {
+            state.texture_folder = Some(file.to_string_lossy().to_string());
+            state.textures_need_reload = true;
+        }
+    }
+    
+    if let Some(folder) = state.texture_folder.clone() {
+        ui.horizontal(|ui| {
+            // Re-read the folder from disk, e.g. after an export finished
+            if ui.small_button("🔄 Reload").on_hover_text("Load the textures of this folder again from disk").clicked() {
+                state.textures_need_reload = true;
+            }
+            let folder_path = std::path::Path::new(&folder);
+            if is_zip_archive(folder_path) {
+                let name = folder_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                ui.label(RichText::new(format!("📦 {}", name)).small()).on_hover_text(&folder);
+            } else {
+                ui.label(RichText::new(format!("📂 {}", folder)).small());
+            }
+        });
     } else {
         ui.label(RichText::new("No texture folder loaded").weak().small());
     }
+    build_texture_load_status(ui, state);
+    if ui.checkbox(&mut state.search_subfolders, "Search subfolders")
+        .on_hover_text(tooltips::SEARCH_SUBFOLDERS.text())
+        .changed()
+    {
+        // Archives always include their subfolders; only a folder needs a reload
+        state.textures_need_reload = state.texture_folder.as_deref()
+            .is_some_and(|folder| !is_zip_archive(std::path::Path::new(folder)));
+    }
+    if let Some(folder) = recent_list(ui, "Recent Folders", &mut state.recent_folders) {
+        state.texture_folder = Some(folder);
+        state.textures_need_reload = true;
+    }
+    
+    ui.add_space(8.0);
+    ui.horizontal(|ui| {
+        ui.label("Max Texture Size");
+        ComboBox::from_id_source("max_texture_size")
+            .selected_text(format!("{}", state.max_texture_size))
+            .show_ui(ui, |ui| {
+                let mut size = 512;
+                while size <= state.device_max_texture_size {
+                    if ui.selectable_value(&mut state.max_texture_size, size, format!("{}", size)).changed() {
+                        state.textures_need_reload = true;
+                    }
+                    size *= 2;
+                }
+            })
+            .response
+            .on_hover_text(tooltips::MAX_TEXTURE_SIZE.text());
+    });
+    ui.label(RichText::new(format!("Larger images are downscaled on load (device limit: {})", state.device_max_texture_size)).weak().small());
+    
+    ui.add_space(8.0);
+    build_texture_quality(ui, state);
     
     ui.add_space(12.0);
     ui.separator();
@@ -413,40 +1451,122 @@ fn build_textures_tab(ui: &mut Ui, state: &mut AppState) {
     ui.add_space(4.0);
     
     // Helper macro for texture rows
+    ui.label(RichText::new("Drag a file from the folder listing onto a slot to assign it").weak().small());
+    ui.add_space(4.0);
+    
+    // Helper macro for texture rows (each row accepts a dragged file path)
     macro_rules! texture_row {
-        ($ui:expr, $state:expr, $label:expr, $checked:expr, $handle:ident) => {
-            $ui.horizontal(|ui| {
-                let checkbox_symbol = if $checked { "✓" } else { "☐" };
-                ui.label(RichText::new(format!("{} {}", checkbox_symbol, $label)).size(14.0));
-                
-                if let Some(name) = $state.texture_handles.get_file_name(stringify!($handle)) {
-                    ui.label(RichText::new(format!("({})", name)).weak().small());
-                } else {
-                    ui.label(RichText::new("(none)").weak().small());
-                }
-                
-                if ui.small_button("📄").clicked() {
-                    if let Some(file) = rfd::FileDialog::new()
-                        .set_title(&format!("Select {} Texture", $label))
-                        .add_filter("Image", &["png", "jpg", "jpeg", "tga", "bmp", "dds"])
-                        .pick_file()
+        ($ui:expr, $state:expr, $label:expr, $checked:expr, $info:expr, $handle:ident) => {
+            let slot = stringify!($handle);
+            let (_, dropped) = $ui.dnd_drop_zone::<String, _>(Frame::none(), |ui| {
+                ui.horizontal(|ui| {
+                    let checkbox_symbol = if $checked { "✓" } else { "☐" };
+                    ui.label(RichText::new(format!("{} {}", checkbox_symbol, $label)).size(14.0));
+                    if let Some(info) = $info {
+                        texture_size_label(ui, &info, $state.texture_info.reference_size());
+                        color_space_badge(ui, &info);
+                    }
+                    
+                    if let Some(name) = $state.texture_handles.get_file_name(slot) {
+                        ui.label(RichText::new(format!("({})", name)).weak().small());
+                    } else {
+                        ui.label(RichText::new("(none)").weak().small());
+                    }
+                    
+                    if ui.small_button("📄").clicked() {
+                        if let Some(file) = rfd::FileDialog::new()
+                            .set_title(&format!("Select {} Texture", $label))
+                            .add_filter("Image", SUPPORTED_EXTENSIONS)
+                            .pick_file()
+                        {
+                            $state.texture_handles.set_override(slot, file.to_string_lossy().to_string());
+                            $state.texture_slot_reload = Some(slot);
+                        }
+                    }
+                    
+                    if ($checked || $state.texture_handles.get(slot).is_some())
+                        && ui.small_button("✕").on_hover_text("Remove this texture and use the default value").clicked()
+                    {
+                        $state.texture_handles.clear(slot);
+                        $state.texture_slot_reload = Some(slot);
+                    }
+                    
+                    // Explicit assignments can be reverted to auto-detection
+                    if $state.texture_handles.is_overridden(slot)
+                        && ui.small_button("📌").on_hover_text("Set manually - click to use auto-detection").clicked()
                     {
-                        $state.texture_handles.$handle = Some(file.to_string_lossy().to_string());
+                        $state.texture_handles.clear_override(slot);
                         $state.textures_need_reload = true;
                     }
-                }
+                });
+                build_slot_sampler(ui, $state, slot);
             });
+            if let Some(path) = dropped {
+                $state.texture_handles.set_override(slot, (*path).clone());
+                $state.texture_slot_reload = Some(slot);
+            }
         };
     }
     
-    texture_row!(ui, state, "Base Color", state.loaded_textures.base_color, base_color);
-    texture_row!(ui, state, "Normal", state.loaded_textures.normal, normal);
-    texture_row!(ui, state, "Metallic", state.loaded_textures.metallic || state.loaded_textures.orm, metallic);
-    texture_row!(ui, state, "Roughness", state.loaded_textures.roughness || state.loaded_textures.orm, roughness);
-    texture_row!(ui, state, "ORM", state.loaded_textures.orm, orm);
-    texture_row!(ui, state, "AO", state.loaded_textures.ao || state.loaded_textures.orm, ao);
-    texture_row!(ui, state, "Emissive", state.loaded_textures.emissive, emissive);
-    texture_row!(ui, state, "Height", state.loaded_textures.height, height);
+    // A packed ORM map is shown on its own row, a metallic/roughness map on both of its channels
+    let info = state.texture_info;
+    let packed_info = info.metallic_roughness.filter(|_| !state.loaded_textures.orm);
+    let orm_info = info.metallic_roughness.filter(|_| state.loaded_textures.orm);
+    
+    texture_row!(ui, state, "Base Color", state.loaded_textures.base_color, info.base_color, base_color);
+    texture_row!(ui, state, "Normal", state.loaded_textures.normal, info.normal, normal);
+    texture_row!(ui, state, "Metallic", state.loaded_textures.metallic || state.loaded_textures.orm, packed_info, metallic);
+    texture_row!(ui, state, "Roughness", state.loaded_textures.roughness || state.loaded_textures.orm, packed_info, roughness);
+    texture_row!(ui, state, "ORM", state.loaded_textures.orm, orm_info, orm);
+    texture_row!(ui, state, "AO", state.loaded_textures.ao || state.loaded_textures.orm, info.ao, ao);
+    texture_row!(ui, state, "Emissive", state.loaded_textures.emissive, info.emissive, emissive);
+    texture_row!(ui, state, "Height", state.loaded_textures.height, info.height, height);
+    
+    build_detection_conflicts(ui, state);
+    
+    ui.add_space(8.0);
+    CollapsingHeader::new("Maps Used in Lit View")
+        .default_open(false)
+        .show(ui, |ui| {
+            let previous = state.enabled_maps;
+            let maps = &mut state.enabled_maps;
+            Grid::new("enabled_maps").num_columns(2).show(ui, |ui| {
+                ui.checkbox(&mut maps.base_color, "Base Color");
+                ui.checkbox(&mut maps.normal, "Normal");
+                ui.end_row();
+                ui.checkbox(&mut maps.metallic_roughness, "Metallic/Roughness");
+                ui.checkbox(&mut maps.ao, "AO");
+                ui.end_row();
+                ui.checkbox(&mut maps.emissive, "Emissive");
+                ui.checkbox(&mut maps.height, "Height");
+                ui.end_row();
+            })
+            .response
+            .on_hover_text(tooltips::ENABLED_MAPS.text());
+            if state.enabled_maps != previous {
+                state.material_changed = true;
+            }
+        });
+    
+    // Folder listing as drag sources for manual slot assignment
+    if !state.texture_folder_files.is_empty() {
+        ui.add_space(8.0);
+        CollapsingHeader::new(format!("Folder Files ({})", state.texture_folder_files.len()))
+            .default_open(false)
+            .show(ui, |ui| {
+                for path in &state.texture_folder_files {
+                    let name = std::path::Path::new(path)
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_else(|| path.clone());
+                    ui.dnd_drag_source(Id::new(("texture_file", path)), path.clone(), |ui| {
+                        ui.label(RichText::new(format!("🖼 {}", name)).small());
+                    })
+                    .response
+                    .on_hover_text(path);
+                }
+            });
+    }
     
     ui.add_space(12.0);
     
@@ -454,7 +1574,272 @@ fn build_textures_tab(ui: &mut Ui, state: &mut AppState) {
     if ui.button("🗑 Clear All Textures").clicked() {
         state.texture_folder = None;
         state.texture_handles = Default::default();
+        state.texture_folder_files.clear();
+        state.texture_conflicts.clear();
         state.loaded_textures.reset();
         state.textures_need_reload = true;
     }
 }
+
+/// Collapsible wrap and filter overrides of one texture row
+fn build_slot_sampler(ui: &mut Ui, state: &mut AppState, slot: &str) {
+    let global = state.texture_filter;
+    let global_filter = if global.nearest { "Nearest" } else { "Linear" };
+    let sampler = state.slot_samplers.get_mut(slot);
+    let previous = *sampler;
+    
+    CollapsingHeader::new(RichText::new("Sampler").small())
+        .id_source(("slot_sampler", slot))
+        .default_open(false)
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Wrap");
+                ComboBox::from_id_source(("slot_wrap_mode", slot))
+                    .selected_text(sampler.wrap_mode.map_or_else(
+                        || format!("Global ({})", global.wrap_mode.name()),
+                        |mode| mode.name().to_string(),
+                    ))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut sampler.wrap_mode, None, format!("Global ({})", global.wrap_mode.name()));
+                        for mode in TextureWrapMode::all() {
+                            ui.selectable_value(&mut sampler.wrap_mode, Some(*mode), mode.name());
+                        }
+                    });
+            });
+            ui.horizontal(|ui| {
+                ui.label("Filter");
+                let name = |nearest: bool| if nearest { "Nearest" } else { "Linear" };
+                ComboBox::from_id_source(("slot_filter", slot))
+                    .selected_text(sampler.nearest.map_or_else(
+                        || format!("Global ({})", global_filter),
+                        |nearest| name(nearest).to_string(),
+                    ))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut sampler.nearest, None, format!("Global ({})", global_filter));
+                        ui.selectable_value(&mut sampler.nearest, Some(false), name(false));
+                        ui.selectable_value(&mut sampler.nearest, Some(true), name(true));
+                    });
+            });
+        })
+        .header_response
+        .on_hover_text(tooltips::SLOT_SAMPLER.text());
+    
+    if *sampler != previous {
+        state.samplers_changed = true;
+    }
+}
+
+/// Progress of a running texture load, or the outcome of the last one
+fn build_texture_load_status(ui: &mut Ui, state: &AppState) {
+    match state.texture_load_progress {
+        Some((done, total)) if total > 0 => {
+            ui.add(
+                ProgressBar::new(done as f32 / total as f32)
+                    .desired_height(12.0)
+                    .text(RichText::new(format!("Decoding textures {}/{}", done, total)).small()),
+            );
+        }
+        Some(_) => {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label(RichText::new("Scanning for textures…").weak().small());
+            });
+        }
+        None => {
+            let Some(report) = &state.last_texture_load else {
+                return;
+            };
+            if report.loaded > 0 {
+                ui.label(RichText::new(format!("✓ {} textures loaded", report.loaded)).weak().small());
+            }
+            for failure in &report.failures {
+                let name = failure.path.file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| failure.path.display().to_string());
+                ui.label(
+                    RichText::new(format!("✗ {}: {}", name, failure.error))
+                        .small()
+                        .color(Color32::from_rgb(255, 100, 100)),
+                )
+                .on_hover_text(failure.path.display().to_string());
+            }
+        }
+    }
+}
+
+/// Warnings for slots several folder files matched equally well, until the slot
+/// is assigned manually
+fn build_detection_conflicts(ui: &mut Ui, state: &AppState) {
+    let file_name = |path: &std::path::Path| {
+        path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+    };
+    let handles = &state.texture_handles;
+    let conflicts: Vec<_> = state.texture_conflicts.iter()
+        .filter(|conflict| match conflict.slot {
+            "metallic_roughness" => !handles.is_overridden("metallic") && !handles.is_overridden("roughness"),
+            slot => !handles.is_overridden(slot),
+        })
+        .collect();
+    if conflicts.is_empty() {
+        return;
+    }
+    
+    ui.add_space(4.0);
+    for conflict in conflicts {
+        let others: Vec<String> = conflict.others.iter().map(|path| file_name(path.as_path())).collect();
+        ui.label(
+            RichText::new(format!(
+                "⚠ {}: using {}, also matched {}",
+                detected_slot_name(conflict.slot),
+                file_name(conflict.chosen.as_path()),
+                others.join(", "),
+            ))
+            .small()
+            .color(Color32::from_rgb(255, 200, 80)),
+        )
+        .on_hover_text("Several files match this map equally well. Pick the right one with the row's file button or by dragging it from the folder listing.");
+    }
+}
+
+/// Display name of a slot from texture detection
+fn detected_slot_name(slot: &str) -> &'static str {
+    match slot {
+        "base_color" => "Base Color",
+        "normal" => "Normal",
+        "metallic" => "Metallic",
+        "roughness" => "Roughness",
+        "metallic_roughness" => "Metallic/Roughness",
+        "orm" => "ORM",
+        "ao" => "AO",
+        "emissive" => "Emissive",
+        _ => "Height",
+    }
+}
+
+/// Texture quality preset plus the individual filtering settings it bundles
+fn build_texture_quality(ui: &mut Ui, state: &mut AppState) {
+    let previous = state.texture_filter;
+    
+    ui.horizontal(|ui| {
+        ui.label("Texture Quality");
+        ComboBox::from_id_source("texture_quality")
+            .selected_text(state.texture_quality.name())
+            .show_ui(ui, |ui| {
+                for preset in TextureQuality::presets() {
+                    if ui.selectable_value(&mut state.texture_quality, *preset, preset.name()).changed() {
+                        if let Some(filter) = preset.settings() {
+                            state.texture_filter = state.texture_filter.with_preset(filter);
+                        }
+                    }
+                }
+            })
+            .response
+            .on_hover_text(tooltips::TEXTURE_QUALITY.text());
+    });
+    
+    CollapsingHeader::new("Filtering Details")
+        .id_source("texture_filtering_details")
+        .default_open(false)
+        .show(ui, |ui| {
+            let anisotropic = state.texture_filter.anisotropy > 1 && !state.texture_filter.nearest;
+            ui.add_enabled(
+                !anisotropic,
+                Checkbox::new(&mut state.texture_filter.linear_mipmaps, "Linear mip filtering"),
+            )
+            .on_hover_text(tooltips::LINEAR_MIPMAPS.text())
+            .on_disabled_hover_text("Anisotropic filtering always blends between mip levels");
+            
+            ui.horizontal(|ui| {
+                ui.label("Anisotropy");
+                let supported = state.max_anisotropy > 1;
+                ui.add_enabled_ui(supported && !state.texture_filter.nearest, |ui| {
+                    ComboBox::from_id_source("texture_anisotropy")
+                        .selected_text(format!("{}×", state.texture_filter.anisotropy))
+                        .show_ui(ui, |ui| {
+                            for anisotropy in [1u16, 2, 4, 8, 16].into_iter().filter(|&a| a <= state.max_anisotropy) {
+                                ui.selectable_value(&mut state.texture_filter.anisotropy, anisotropy, format!("{}×", anisotropy));
+                            }
+                        })
+                        .response
+                        .on_hover_text(tooltips::ANISOTROPY.text())
+                        .on_disabled_hover_text(if supported {
+                            "Anisotropic filtering needs linear filtering"
+                        } else {
+                            "Anisotropic filtering is not supported by this GPU"
+                        });
+                });
+            });
+            
+            ui.checkbox(&mut state.texture_filter.generate_mipmaps, "Generate mipmaps")
+                .on_hover_text(tooltips::GENERATE_MIPMAPS.text());
+        });
+    
+    ui.horizontal(|ui| {
+        ui.label("Wrap Mode");
+        ComboBox::from_id_source("texture_wrap_mode")
+            .selected_text(state.texture_filter.wrap_mode.name())
+            .show_ui(ui, |ui| {
+                for mode in TextureWrapMode::all() {
+                    ui.selectable_value(&mut state.texture_filter.wrap_mode, *mode, mode.name());
+                }
+            })
+            .response
+            .on_hover_text(tooltips::WRAP_MODE.text());
+    });
+    if ui.checkbox(&mut state.texture_filter.nearest, "Nearest filtering (pixel art)")
+        .on_hover_text(tooltips::NEAREST_FILTERING.text())
+        .changed()
+        && state.texture_filter.nearest
+    {
+        // Blending mip levels would smear the pixels again at a distance
+        state.texture_filter.linear_mipmaps = false;
+    }
+    
+    // Presets may ask for more anisotropy than the device offers
+    state.texture_filter.anisotropy = state.texture_filter.anisotropy.min(state.max_anisotropy);
+    
+    if state.texture_filter != previous {
+        state.texture_quality = TextureQuality::from_settings(&state.texture_filter);
+        // Mip generation happens on upload; everything else only needs new samplers
+        if state.texture_filter.generate_mipmaps != previous.generate_mipmaps {
+            state.textures_need_reload = true;
+        } else {
+            state.samplers_changed = true;
+        }
+    }
+}
+
+/// Resolution and channel format of a loaded texture, in the warning color when
+/// the size is not a power of two or differs from the rest of the set
+fn texture_size_label(ui: &mut Ui, info: &TextureInfo, reference_size: Option<(u32, u32)>) {
+    let mismatched = reference_size.is_some_and(|size| size != (info.width, info.height));
+    let text = RichText::new(format!("{}×{} {}", info.width, info.height, info.format_name())).small();
+    let mut warnings = Vec::new();
+    if !info.is_power_of_two() {
+        warnings.push("Not a power of two");
+    }
+    if mismatched {
+        warnings.push("Resolution differs from the other maps in the set");
+    }
+    if warnings.is_empty() {
+        ui.label(text.weak());
+    } else {
+        ui.label(text.color(Color32::from_rgb(255, 200, 80)))
+            .on_hover_text(format!("{} - often a sign of a bad export", warnings.join("; ")));
+    }
+}
+
+/// Small badge showing whether a texture was uploaded as sRGB or linear data
+fn color_space_badge(ui: &mut Ui, info: &TextureInfo) {
+    let color = match info.color_space {
+        ColorSpace::Srgb => Color32::from_rgb(230, 160, 60),
+        ColorSpace::Linear => Color32::from_rgb(90, 170, 240),
+    };
+    ui.label(RichText::new(info.color_space.name()).small().strong().color(color))
+        .on_hover_text(format!(
+            "{}×{}, uploaded as {:?}",
+            info.width,
+            info.height,
+            info.color_space.format(),
+        ));
+}